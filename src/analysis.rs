@@ -0,0 +1,328 @@
+use crate::schema::Endianness;
+use std::ops::Range;
+
+/// Guess the record size of repeating structures via byte-level autocorrelation
+///
+/// For every period up to `max_period`, scores the fraction of bytes that equal the byte
+/// `period` positions later. Returns `(period, score)` candidates ranked best first;
+/// ties go to the smaller period, since multiples of a true period score just as well.
+pub fn guess_period(data: &[u8], max_period: usize) -> Vec<(usize, f32)> {
+    let max_period = max_period.min(data.len() / 2);
+    if max_period == 0 {
+        return Vec::new();
+    }
+
+    // Matches expected by chance given the byte distribution, so skewed data doesn't score high everywhere
+    let counts = byte_histogram(data);
+    let total = data.len() as f32;
+    let chance: f32 = counts.iter().map(|&c| (c as f32 / total).powi(2)).sum();
+
+    let mut candidates: Vec<(usize, f32)> = (1..=max_period)
+        .map(|period| {
+            let pairs = data.len() - period;
+            let matches = data.iter().zip(&data[period..]).filter(|(a, b)| a == b).count();
+            let rate = matches as f32 / pairs as f32;
+            // Normalize so 0 means "no better than chance" and 1 means "perfectly periodic"
+            let score = if chance < 1.0 { ((rate - chance) / (1.0 - chance)).max(0.0) } else { 1.0 };
+            (period, score)
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    candidates
+}
+
+/// Count how often each byte value occurs in `data`
+pub fn byte_histogram(data: &[u8]) -> [usize; 256] {
+    let mut counts = [0usize; 256];
+    for &b in data {
+        counts[b as usize] += 1;
+    }
+    counts
+}
+
+/// Shannon entropy of a byte distribution, from 0 (a single value) to 8 bits per byte (uniform)
+pub fn shannon_entropy(counts: &[usize; 256]) -> f64 {
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return 0.0;
+    }
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Entropy of each consecutive `window`-byte block of `data`; the last block may be shorter
+/// High entropy (close to 8) hints at compressed or encrypted data
+pub fn entropy_windows(data: &[u8], window: usize) -> Vec<f64> {
+    data.chunks(window.max(1))
+        .map(|block| shannon_entropy(&byte_histogram(block)))
+        .collect()
+}
+
+/// Find the ranges of bytes where `a` and `b` differ, in ascending order
+/// If the lengths differ, the tail of the longer one is part of the last range
+pub fn diff_ranges(a: &[u8], b: &[u8]) -> Vec<Range<usize>> {
+    let common = a.len().min(b.len());
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    let mut extend = |range: Range<usize>| match ranges.last_mut() {
+        Some(last) if last.end == range.start => last.end = range.end,
+        _ => ranges.push(range),
+    };
+
+    for (offset, (x, y)) in a[..common].iter().zip(&b[..common]).enumerate() {
+        if x != y {
+            extend(offset..offset + 1);
+        }
+    }
+    if a.len() != b.len() {
+        extend(common..a.len().max(b.len()));
+    }
+    ranges
+}
+
+/// Find the start offsets of the occurrences of `needle` in `data`, stopping after `limit` matches
+/// Overlapping occurrences are all reported
+pub fn find_all(data: &[u8], needle: &[u8], limit: usize) -> Vec<usize> {
+    if needle.is_empty() || needle.len() > data.len() {
+        return Vec::new();
+    }
+
+    data.windows(needle.len())
+        .enumerate()
+        .filter(|(_, window)| *window == needle)
+        .map(|(offset, _)| offset)
+        .take(limit)
+        .collect()
+}
+
+/// Whether `b` is printable ASCII or a tab, as counted by the string scanners
+fn is_printable(b: u8) -> bool {
+    b == b'\t' || (0x20..=0x7E).contains(&b)
+}
+
+/// Find runs of at least `min_len` printable ASCII bytes, like the Unix `strings` tool
+pub fn find_strings(data: &[u8], min_len: usize) -> Vec<(usize, String)> {
+    let mut found = Vec::new();
+    let mut start = 0;
+    for (offset, &b) in data.iter().chain([0u8].iter()).enumerate() {
+        if !is_printable(b) {
+            if offset - start >= min_len.max(1) {
+                found.push((start, String::from_utf8_lossy(&data[start..offset]).into_owned()));
+            }
+            start = offset + 1;
+        }
+    }
+    found
+}
+
+/// Find runs of at least `min_len` printable ASCII characters encoded as UTF-16LE
+/// Runs are searched at both even and odd offsets
+pub fn find_utf16_strings(data: &[u8], min_len: usize) -> Vec<(usize, String)> {
+    let mut found = Vec::new();
+    for parity in 0..2 {
+        let units = data.get(parity..).unwrap_or_default().chunks_exact(2);
+        let mut run = String::new();
+        let mut start = parity;
+        for (i, unit) in units.map(Some).chain([None]).enumerate() {
+            match unit {
+                Some(&[lo, 0]) if is_printable(lo) => run.push(lo as char),
+                _ => {
+                    if run.len() >= min_len.max(1) {
+                        found.push((start, std::mem::take(&mut run)));
+                    } else {
+                        run.clear();
+                    }
+                    start = parity + (i + 1) * 2;
+                }
+            }
+        }
+    }
+    found.sort_by_key(|&(offset, _)| offset);
+    found
+}
+
+/// Smallest non-zero magnitude considered plausible; catches denormals of both f32 and f64
+const MIN_PLAUSIBLE_FLOAT: f64 = 1e-30;
+
+/// Whether a decoded float is unlikely to really be a float: NaN, infinite, denormal-tiny,
+/// or larger in magnitude than `max_magnitude` (typical of wrong endianness or misalignment)
+pub fn float_looks_suspicious(value: f64, max_magnitude: f64) -> bool {
+    !value.is_finite() || value.abs() > max_magnitude || (value != 0.0 && value.abs() < MIN_PLAUSIBLE_FLOAT)
+}
+
+/// How much an endianness guess can be trusted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confidence {
+    /// A two-byte marker, which could also appear by chance
+    Medium,
+    /// A format signature of four bytes or more
+    High,
+}
+
+impl Confidence {
+    /// Get the display name of this confidence level
+    pub fn name(&self) -> &'static str {
+        match self {
+            Confidence::Medium => "medium",
+            Confidence::High => "high",
+        }
+    }
+}
+
+/// Endianness recognized from a marker at the start of the data, and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndiannessGuess {
+    pub endianness: Endianness,
+    pub confidence: Confidence,
+    /// The marker that was recognized, e.g. `UTF-16 byte order mark`
+    pub reason: String,
+}
+
+/// Format signatures that also tell the byte order, checked longest first
+const ENDIAN_SIGNATURES: &[(&[u8], Endianness, &str)] = &[
+    (&[0xFF, 0xFE, 0x00, 0x00], Endianness::Little, "UTF-32 byte order mark"),
+    (&[0x00, 0x00, 0xFE, 0xFF], Endianness::Big, "UTF-32 byte order mark"),
+    (b"II*\0", Endianness::Little, "TIFF header"),
+    (b"MM\0*", Endianness::Big, "TIFF header"),
+    (&[0xCE, 0xFA, 0xED, 0xFE], Endianness::Little, "Mach-O 32-bit magic"),
+    (&[0xCF, 0xFA, 0xED, 0xFE], Endianness::Little, "Mach-O 64-bit magic"),
+    (&[0xFE, 0xED, 0xFA, 0xCE], Endianness::Big, "Mach-O 32-bit magic"),
+    (&[0xFE, 0xED, 0xFA, 0xCF], Endianness::Big, "Mach-O 64-bit magic"),
+    (&[0xD4, 0xC3, 0xB2, 0xA1], Endianness::Little, "pcap magic"),
+    (&[0xA1, 0xB2, 0xC3, 0xD4], Endianness::Big, "pcap magic"),
+    (&[0xCA, 0xFE, 0xBA, 0xBE], Endianness::Big, "Java class / fat Mach-O magic"),
+    (&[0xFF, 0xFE], Endianness::Little, "UTF-16 byte order mark"),
+    (&[0xFE, 0xFF], Endianness::Big, "UTF-16 byte order mark"),
+];
+
+/// Recognize the byte order from a marker at the start of `data`
+/// See `detect_endianness_with` for the markers checked
+pub fn detect_endianness(data: &[u8]) -> Option<Endianness> {
+    detect_endianness_with(data, &[]).map(|guess| guess.endianness)
+}
+
+/// Recognize the byte order from a marker at the start of `data`: byte order marks, common
+/// format signatures (ELF, TIFF, Mach-O, pcap, Java class), then the 16-bit `magics`, which
+/// match when stored in either byte order
+pub fn detect_endianness_with(data: &[u8], magics: &[u16]) -> Option<EndiannessGuess> {
+    let guess = |endianness, confidence, reason: String| EndiannessGuess { endianness, confidence, reason };
+
+    // ELF stores the byte order in EI_DATA, right after the magic and class
+    if data.len() > 5 && data.starts_with(b"\x7FELF") {
+        match data[5] {
+            1 => return Some(guess(Endianness::Little, Confidence::High, "ELF header (EI_DATA = 1)".to_string())),
+            2 => return Some(guess(Endianness::Big, Confidence::High, "ELF header (EI_DATA = 2)".to_string())),
+            _ => {}
+        }
+    }
+
+    for &(signature, endianness, name) in ENDIAN_SIGNATURES {
+        if data.starts_with(signature) {
+            let confidence = if signature.len() >= 4 { Confidence::High } else { Confidence::Medium };
+            return Some(guess(endianness, confidence, name.to_string()));
+        }
+    }
+
+    let start: [u8; 2] = data.get(..2)?.try_into().ok()?;
+    for &magic in magics {
+        // A palindromic magic reads the same in both orders and tells nothing
+        if magic.to_le_bytes() == magic.to_be_bytes() {
+            continue;
+        }
+        if start == magic.to_le_bytes() {
+            return Some(guess(Endianness::Little, Confidence::Medium, format!("magic 0x{:04X} stored little-endian", magic)));
+        }
+        if start == magic.to_be_bytes() {
+            return Some(guess(Endianness::Big, Confidence::Medium, format!("magic 0x{:04X} stored big-endian", magic)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random bytes
+    fn noise(len: usize) -> Vec<u8> {
+        let mut state = 0x2545_F491u32;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                (state >> 16) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_the_record_size_of_a_stream() {
+        // 12-byte records with a fixed tag, a counter and a constant body
+        let data: Vec<u8> = (0..64u8)
+            .flat_map(|i| [0xCA, 0xFE, i, 0, 1, 2, 3, 4, 5, 6, 7, 8])
+            .collect();
+        let candidates = guess_period(&data, 64);
+        assert_eq!(candidates[0].0, 12);
+        assert!(candidates[0].1 > 0.9);
+    }
+
+    #[test]
+    fn aperiodic_data_scores_low() {
+        let candidates = guess_period(&noise(4096), 64);
+        assert_eq!(candidates.len(), 64);
+        assert!(candidates[0].1 < 0.1, "best score {:?}", candidates[0]);
+    }
+
+    #[test]
+    fn short_inputs_have_no_candidates() {
+        assert!(guess_period(&[], 16).is_empty());
+        assert!(guess_period(&[7], 16).is_empty());
+        assert!(guess_period(&[1, 2, 3, 4], 0).is_empty());
+        // Periods are limited to half the data
+        assert_eq!(guess_period(&[1, 2, 1, 2, 1], 16).len(), 2);
+    }
+
+    #[test]
+    fn uniform_data_has_full_entropy() {
+        let data: Vec<u8> = (0..=255).collect();
+        assert!((shannon_entropy(&byte_histogram(&data)) - 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn constant_data_has_no_entropy() {
+        assert_eq!(shannon_entropy(&byte_histogram(&[0x41; 100])), 0.0);
+        assert_eq!(shannon_entropy(&byte_histogram(&[])), 0.0);
+    }
+
+    #[test]
+    fn histogram_counts_each_value() {
+        let counts = byte_histogram(&[1, 2, 2, 255]);
+        assert_eq!((counts[1], counts[2], counts[255], counts[0]), (1, 2, 1, 0));
+    }
+
+    #[test]
+    fn entropy_windows_split_the_data() {
+        let mut data = vec![0; 256];
+        data.extend(0..=255);
+        let windows = entropy_windows(&data, 256);
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[0], 0.0);
+        assert!((windows[1] - 8.0).abs() < 1e-9);
+
+        // The last block may be shorter
+        assert_eq!(entropy_windows(&data, 300).len(), 2);
+    }
+
+    #[test]
+    fn window_larger_than_the_input_is_one_block() {
+        let windows = entropy_windows(&[0, 1], 4096);
+        assert_eq!(windows.len(), 1);
+        assert!((windows[0] - 1.0).abs() < 1e-9);
+        assert!(entropy_windows(&[], 4096).is_empty());
+    }
+}
@@ -1,19 +1,66 @@
+use crate::analysis;
+use crate::app_state::{AppState, RecentFiles};
+use crate::associations::SchemaAssociations;
 use crate::binary_data::BinaryData;
-use crate::schema::{DataType, Field, Schema};
-use crate::ui::{DataView, FieldAction, HexView};
-use std::collections::HashSet;
+use crate::export::{self, export_c_struct, export_kaitai, export_rust_struct};
+use crate::import::{parse_c_struct, parse_field_text, parse_imhex_pattern};
+use crate::preferences::Preferences;
+use crate::schema::{
+    self, BitOrder, ChecksumAlgorithm, DataType, Endianness, EnumLabel, Field, FloatNotation, IntType, NumberFormat, Schema, SchemaFormat,
+    StringPool,
+};
+use crate::templates::{Template, TEMPLATES};
+use crate::ui::{
+    BYTES_PER_ROW_CHOICES, AnalysisView, CompareAction, CompareView, DataView, FieldAction, FieldBytesView, HexAction, HexView, InspectorView, Notifications, OffsetFormat, OutlineView, Palettes, SearchAction, SearchBar, StringsAction, StringsView, ViewSettings, WatchView,
+};
+use crate::watch::ValueHistory;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// Longest field whose bytes are searched for elsewhere in the file
+const MAX_MATCH_NEEDLE: usize = 256;
+/// Maximum number of matches of a field's bytes that are highlighted
+const MAX_FIELD_MATCHES: usize = 10_000;
+
+/// Maximum number of schema edits that can be undone
+const MAX_UNDO_DEPTH: usize = 100;
+
+/// Bit spans and names of the placed fields, and the file size, that layout warnings depend on
+type LayoutKey = (Vec<(Range<usize>, String)>, usize);
 
 /// View focus state for keyboard shortcuts
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ViewFocus {
+    #[default]
     HexView,
     DataView,
+    Outline,
 }
 
-/// Main application state
-pub struct SchematicApp {
+/// Target language for struct export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StructLanguage {
+    C,
+    Rust,
+    /// Kaitai Struct definition (`.ksy`)
+    Kaitai,
+}
+
+/// Schema state saved before an edit, restored by undo/redo
+#[derive(Clone)]
+struct SchemaSnapshot {
+    fields: Vec<Field>,
+    selected_fields: HashSet<usize>,
+    last_selected_field: Option<usize>,
+    schema_expected_size: Option<usize>,
+    string_pool: Option<StringPool>,
+}
+
+/// Per-file state of an open tab
+struct Document {
     /// Loaded binary data
     binary_data: BinaryData,
     /// Defined fields for interpreting the binary
@@ -22,220 +69,2285 @@ pub struct SchematicApp {
     hex_view: HexView,
     /// Data view widget
     data_view: DataView,
+    /// Byte pattern search above the hex view
+    search_bar: SearchBar,
+    /// Value history of watched fields, keyed by field index
+    field_watches: HashMap<usize, ValueHistory>,
+    /// Data generation the watches were last recorded at
+    watched_generation: u64,
+    /// Data generation the hex view's changed-byte markers were last updated at
+    dirty_generation: u64,
+    /// Layout the field warnings were computed for
+    schema_warnings_key: Option<LayoutKey>,
+    /// Field index, offset, size and data generation the match highlights were computed for
+    field_matches_key: Option<(usize, usize, usize, u64)>,
+    /// Second file shown next to the hex view, with the differing bytes marked
+    comparison: Option<CompareView>,
+    /// Currently selected fields for highlighting (supports multi-selection)
+    selected_fields: HashSet<usize>,
+    /// Last selected field index for shift-click range selection
+    last_selected_field: Option<usize>,
+    /// Path to the current schema file (for save/save-as)
+    schema_file_path: Option<PathBuf>,
+    /// Explicit file size the schema expects, if set
+    schema_expected_size: Option<usize>,
+    /// Region that string index fields point into, if set
+    string_pool: Option<StringPool>,
+    /// Offset added to every field's offset when reading and highlighting
+    schema_base: usize,
+    /// Associated schema offered for loading after opening a binary
+    pending_schema_offer: Option<PathBuf>,
+    /// Schema states before the most recent edits, newest last
+    undo_stack: Vec<SchemaSnapshot>,
+    /// Schema states undone, newest last
+    redo_stack: Vec<SchemaSnapshot>,
+    /// Field whose inline comment edits are merged into the last undo step
+    comment_undo_field: Option<usize>,
+    /// Whether the fields are protected from edits (selection and highlighting still work)
+    schema_locked: bool,
+    /// Offset columns count from this file offset instead of the start of the file
+    relative_base: Option<usize>,
+}
+
+impl Document {
+    fn new() -> Self {
+        Self {
+            binary_data: BinaryData::new(),
+            fields: Vec::new(),
+            hex_view: HexView::new(),
+            data_view: DataView::new(),
+            search_bar: SearchBar::new(),
+            field_watches: HashMap::new(),
+            watched_generation: 0,
+            dirty_generation: 0,
+            schema_warnings_key: None,
+            field_matches_key: None,
+            comparison: None,
+            selected_fields: HashSet::new(),
+            last_selected_field: None,
+            schema_file_path: None,
+            schema_expected_size: None,
+            string_pool: None,
+            schema_base: 0,
+            pending_schema_offer: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            comment_undo_field: None,
+            schema_locked: false,
+            relative_base: None,
+        }
+    }
+}
+
+/// Main application state
+pub struct SchematicApp {
+    /// State of the active tab
+    doc: Document,
+    /// The other open tabs, in tab order without the active one
+    documents: Vec<Document>,
+    /// Position of the active tab among all of them
+    active_document: usize,
+    /// Tab with unsaved byte edits asking to confirm closing
+    close_tab_confirm: Option<usize>,
+    /// Byte strip of the selected field
+    field_bytes_view: FieldBytesView,
+    /// Outline panel widget
+    outline_view: OutlineView,
+    /// Panel decoding the bytes at the hovered or cursor byte
+    inspector_view: InspectorView,
+    /// Watch panel widget
+    watch_view: WatchView,
+    /// Analysis panel widget
+    analysis_view: AnalysisView,
+    analysis_window_open: bool,
+    /// Strings panel widget
+    strings_view: StringsView,
+    strings_window_open: bool,
+    /// Toasts and log of load and save messages
+    notifications: Notifications,
+    log_window_open: bool,
+    /// Display options shared by the hex and data views
+    view_settings: ViewSettings,
+    /// Field color palettes
+    palettes: Palettes,
+    palette_window_open: bool,
+    /// Editor behavior preferences
+    preferences: Preferences,
+    /// Asking to confirm overwriting the original file with the edited bytes
+    save_binary_confirm_open: bool,
+    /// Asking to confirm quitting with unsaved byte edits
+    quit_confirm_open: bool,
+    /// Quitting was confirmed, so the next close request goes through
+    quit_confirmed: bool,
+    /// File I/O error to show to the user
+    io_error: Option<String>,
+    /// UI state for the Go to Offset dialog
+    goto_window_open: bool,
+    /// Byte order detection window, and the custom magic being entered
+    detect_endianness_window_open: bool,
+    endian_magic_input: String,
+    goto_input: String,
+    goto_error: Option<String>,
+    /// Field most recently added through the Add Field dialog or repeated from it
+    last_added_field: Option<Field>,
     /// UI state for adding new fields
     add_field_window_open: bool,
     new_field_name: String,
+    new_field_label: String,
     new_field_offset: String,
     new_field_type_idx: usize,
+    new_field_endianness: Endianness,
     new_field_comment: String,
+    new_field_count: usize,
+    new_field_bit_offset: usize,
+    new_field_bit_width: usize,
+    new_field_bit_order: BitOrder,
+    /// Offset and length types of a slice descriptor
+    new_field_slice_types: (IntType, IntType),
+    /// Algorithm, start and length of the region a checksum covers
+    new_field_checksum: (ChecksumAlgorithm, usize, usize),
+    /// Length in bytes of a string or byte array
+    new_field_string_len: usize,
+    new_field_is_pointer: bool,
+    new_field_is_string_index: bool,
+    new_field_pointer_base: String,
     /// UI state for editing fields
     edit_field_window_open: bool,
     edit_field_idx: Option<usize>,
     edit_field_name: String,
+    edit_field_label: String,
     edit_field_offset: String,
     edit_field_type_idx: usize,
     edit_field_comment: String,
-    /// Currently selected fields for highlighting (supports multi-selection)
-    selected_fields: HashSet<usize>,
-    /// Last selected field index for shift-click range selection
-    last_selected_field: Option<usize>,
+    edit_field_count: usize,
+    edit_field_bit_offset: usize,
+    edit_field_bit_width: usize,
+    edit_field_bit_order: BitOrder,
+    edit_field_slice_types: (IntType, IntType),
+    edit_field_checksum: (ChecksumAlgorithm, usize, usize),
+    edit_field_string_len: usize,
+    edit_field_endianness: Endianness,
+    edit_field_is_pointer: bool,
+    edit_field_is_string_index: bool,
+    edit_field_pointer_base: String,
+    edit_field_enum_labels: Vec<EnumLabel>,
+    edit_field_color: Option<[u8; 3]>,
+    /// Name of the field the edited one follows, if its offset is relative
+    edit_field_anchor: Option<String>,
+    /// Name of the integer field the edited byte string takes its length from
+    edit_field_length_field: Option<String>,
+    /// Size and members of the struct being edited; its type can't be changed in the dialog
+    edit_field_struct: Option<(usize, Vec<Field>)>,
+    /// UI state for defining fields from text
+    define_text_window_open: bool,
+    define_text: String,
+    define_text_error: Option<String>,
+    /// UI state for filling the hex selection with fields
+    fill_window_open: bool,
+    fill_type_idx: usize,
+    fill_allow_remainder: bool,
+    fill_error: Option<String>,
+    /// "Shift Selected Fields" dialog state
+    shift_window_open: bool,
+    shift_delta: String,
+    shift_error: Option<String>,
     /// Current view focus (for keyboard shortcuts)
     view_focus: ViewFocus,
-    /// Path to the current schema file (for save/save-as)
-    schema_file_path: Option<PathBuf>,
+    /// Dark or light visuals, or whichever the OS prefers
+    theme: egui::ThemePreference,
+    /// Remembered binary -> schema associations (persisted)
+    schema_associations: SchemaAssociations,
+    /// Recently opened binaries and schemas (persisted)
+    recent: RecentFiles,
 }
 
 impl Default for SchematicApp {
     fn default() -> Self {
         Self {
-            binary_data: BinaryData::new(),
-            fields: Vec::new(),
-            hex_view: HexView::new(),
-            data_view: DataView::new(),
+            doc: Document::new(),
+            documents: Vec::new(),
+            active_document: 0,
+            close_tab_confirm: None,
+            field_bytes_view: FieldBytesView::new(),
+            outline_view: OutlineView::new(),
+            inspector_view: InspectorView::new(),
+            watch_view: WatchView::new(),
+            analysis_view: AnalysisView::new(),
+            analysis_window_open: false,
+            strings_view: StringsView::new(),
+            strings_window_open: false,
+            notifications: Notifications::new(),
+            log_window_open: false,
+            view_settings: ViewSettings::default(),
+            palettes: Palettes::default(),
+            palette_window_open: false,
+            preferences: Preferences::default(),
+            save_binary_confirm_open: false,
+            quit_confirm_open: false,
+            quit_confirmed: false,
+            io_error: None,
+            goto_window_open: false,
+            detect_endianness_window_open: false,
+            endian_magic_input: String::new(),
+            goto_input: String::new(),
+            goto_error: None,
+            last_added_field: None,
             add_field_window_open: false,
             new_field_name: String::new(),
+            new_field_label: String::new(),
             new_field_offset: String::from("0"),
             new_field_type_idx: 0,
+            new_field_endianness: Endianness::Little,
             new_field_comment: String::new(),
+            new_field_count: 1,
+            new_field_bit_offset: 0,
+            new_field_bit_width: 1,
+            new_field_bit_order: BitOrder::MsbFirst,
+            new_field_slice_types: (IntType::U32, IntType::U32),
+            new_field_checksum: (ChecksumAlgorithm::Crc32, 0, 0),
+            new_field_string_len: 16,
+            new_field_is_pointer: false,
+            new_field_is_string_index: false,
+            new_field_pointer_base: String::from("0"),
             edit_field_window_open: false,
             edit_field_idx: None,
             edit_field_name: String::new(),
+            edit_field_label: String::new(),
             edit_field_offset: String::from("0"),
             edit_field_type_idx: 0,
             edit_field_comment: String::new(),
-            selected_fields: HashSet::new(),
-            last_selected_field: None,
+            edit_field_count: 1,
+            edit_field_bit_offset: 0,
+            edit_field_bit_width: 1,
+            edit_field_bit_order: BitOrder::MsbFirst,
+            edit_field_slice_types: (IntType::U32, IntType::U32),
+            edit_field_checksum: (ChecksumAlgorithm::Crc32, 0, 0),
+            edit_field_string_len: 16,
+            edit_field_endianness: Endianness::Little,
+            edit_field_is_pointer: false,
+            edit_field_is_string_index: false,
+            edit_field_pointer_base: String::from("0"),
+            edit_field_enum_labels: Vec::new(),
+            edit_field_color: None,
+            edit_field_anchor: None,
+            edit_field_length_field: None,
+            edit_field_struct: None,
+            define_text_window_open: false,
+            define_text: String::new(),
+            define_text_error: None,
+            fill_window_open: false,
+            fill_type_idx: 0,
+            fill_allow_remainder: false,
+            fill_error: None,
+            shift_window_open: false,
+            shift_delta: String::new(),
+            shift_error: None,
             view_focus: ViewFocus::HexView,
-            schema_file_path: None,
+            theme: egui::ThemePreference::System,
+            schema_associations: SchemaAssociations::default(),
+            recent: RecentFiles::default(),
         }
     }
 }
 
 impl SchematicApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        Self::default()
+    /// Create the app, starting with `binary_data` (e.g. read from stdin) if given
+    pub fn new(cc: &eframe::CreationContext<'_>, binary_data: Option<BinaryData>) -> Self {
+        let state = AppState::load(cc.storage);
+        let restore_file = binary_data.is_none();
+        let mut app = Self {
+            doc: Document {
+                binary_data: binary_data.unwrap_or_default(),
+                ..Document::new()
+            },
+            view_focus: state.view_focus,
+            theme: state.theme,
+            recent: state.recent.clone(),
+            schema_associations: SchemaAssociations::load(cc.storage),
+            view_settings: ViewSettings::load(cc.storage),
+            palettes: Palettes::load(cc.storage),
+            preferences: Preferences::load(cc.storage),
+            outline_view: OutlineView::load(cc.storage),
+            ..Self::default()
+        };
+        app.recent.prune();
+        cc.egui_ctx.set_theme(app.theme);
+        app.doc.data_view.set_column_widths(&state.data_view_columns);
+        app.restore_session(&state, restore_file);
+        app
+    }
+
+    /// Reopen the binary and schema from the last run, skipping any that no longer exist
+    fn restore_session(&mut self, state: &AppState, restore_file: bool) {
+        if restore_file && let Some(path) = state.last_file.as_ref().filter(|path| path.exists()) {
+            match self.doc.binary_data.load_from_file(path.clone()) {
+                Ok(map_error) => {
+                    self.notifications.info(format!("Restored file: {}", path.display()));
+                    self.report_map_error(map_error);
+                }
+                Err(e) => self.notifications.error(format!("Error restoring {}: {}", path.display(), e)),
+            }
+        }
+        if let Some(path) = state.last_schema.as_ref().filter(|path| path.exists()) {
+            self.load_schema_from_path(path.clone());
+            // Restoring isn't an edit that can be undone
+            self.doc.undo_stack.clear();
+        }
     }
 
     /// Open a file dialog and load the selected binary file
     fn open_file(&mut self) {
         if let Some(path) = rfd::FileDialog::new().pick_file() {
-            if let Err(e) = self.binary_data.load_from_file(path.clone()) {
-                eprintln!("Error loading file: {}", e);
-            } else {
-                println!("Loaded file: {:?}", path);
+            self.open_path(path);
+        }
+    }
+
+    /// Load the binary file at `path`, in a new tab unless the active one has no file
+    fn open_path(&mut self, path: PathBuf) {
+        if self.doc.binary_data.is_loaded() {
+            self.new_document();
+        }
+        match self.doc.binary_data.load_from_file(path.clone()) {
+            Ok(map_error) => {
+                self.notifications.info(format!("Loaded file: {}", path.display()));
+                self.report_map_error(map_error);
+                self.doc.relative_base = None;
+                self.recent.add_file(&path);
+                self.analysis_view.invalidate();
+                self.strings_view.invalidate();
+                self.offer_associated_schema(&path);
             }
+            Err(e) => self.notifications.error(format!("Error loading {}: {}", path.display(), e)),
         }
     }
 
-    /// Render the top menu bar
-    fn show_menu(&mut self, ui: &mut egui::Ui) {
-        egui::menu::bar(ui, |ui| {
-            ui.menu_button("File", |ui| {
-                if ui.button("Open...").clicked() {
-                    self.open_file();
+    /// Warn that a large file couldn't be memory mapped and was read into memory instead
+    fn report_map_error(&mut self, map_error: Option<std::io::Error>) {
+        if let Some(e) = map_error {
+            self.notifications.warning(format!("Memory mapping failed, reading the file instead: {}", e));
+        }
+    }
+
+    /// Make `doc` the active tab's state, returning the previous one
+    fn exchange_document(&mut self, doc: Document) -> Document {
+        let previous = std::mem::replace(&mut self.doc, doc);
+
+        // The analysis caches and open dialogs belong to the previous tab
+        self.analysis_view.invalidate();
+        self.strings_view.invalidate();
+        self.edit_field_window_open = false;
+        self.edit_field_idx = None;
+        self.add_field_window_open = false;
+        self.define_text_window_open = false;
+        self.fill_window_open = false;
+        self.shift_window_open = false;
+        self.detect_endianness_window_open = false;
+        self.save_binary_confirm_open = false;
+        previous
+    }
+
+    /// Get the number of open tabs
+    fn document_count(&self) -> usize {
+        self.documents.len() + 1
+    }
+
+    /// Get the index in `documents` of tab `idx`, which isn't the active one
+    fn inactive_slot(&self, idx: usize) -> usize {
+        if idx < self.active_document { idx } else { idx - 1 }
+    }
+
+    /// Get the state of tab `idx`
+    fn document(&self, idx: usize) -> &Document {
+        if idx == self.active_document {
+            &self.doc
+        } else {
+            &self.documents[self.inactive_slot(idx)]
+        }
+    }
+
+    /// Make tab `idx` the active one
+    fn switch_document(&mut self, idx: usize) {
+        if idx == self.active_document || idx >= self.document_count() {
+            return;
+        }
+        let next = self.documents.remove(self.inactive_slot(idx));
+        let previous = self.exchange_document(next);
+        // The previous tab goes back to its place, with tab `idx` taken out
+        let slot = if self.active_document < idx { self.active_document } else { self.active_document - 1 };
+        self.documents.insert(slot, previous);
+        self.active_document = idx;
+    }
+
+    /// Open an empty tab and make it the active one
+    fn new_document(&mut self) {
+        let mut document = Document::new();
+        // New tabs keep the Data View's column layout
+        document.data_view.set_column_widths(&self.doc.data_view.column_widths());
+        self.documents.push(document);
+        self.switch_document(self.document_count() - 1);
+    }
+
+    /// Close tab `idx`, dropping its unsaved edits; closing the last tab leaves an empty one
+    fn close_document(&mut self, idx: usize) {
+        if idx >= self.document_count() {
+            return;
+        }
+        if self.documents.is_empty() {
+            self.exchange_document(Document::new());
+            return;
+        }
+        if idx == self.active_document {
+            self.switch_document(if idx + 1 < self.document_count() { idx + 1 } else { idx - 1 });
+        }
+        self.documents.remove(self.inactive_slot(idx));
+        if self.active_document > idx {
+            self.active_document -= 1;
+        }
+    }
+
+    /// Get the name shown on tab `idx`
+    fn document_title(&self, idx: usize) -> String {
+        let data = &self.document(idx).binary_data;
+        let name = data
+            .file_path()
+            .and_then(|path| path.file_name())
+            .map_or_else(|| "Untitled".to_string(), |name| name.to_string_lossy().into_owned());
+        if data.is_modified() { format!("{} ●", name) } else { name }
+    }
+
+    /// Check whether any tab has unsaved byte edits
+    fn any_document_modified(&self) -> bool {
+        std::iter::once(&self.doc)
+            .chain(&self.documents)
+            .any(|doc| doc.binary_data.is_modified())
+    }
+
+    /// Render the tab strip, with a close button on each tab and one to open a new tab
+    fn show_tabs(&mut self, ui: &mut egui::Ui) {
+        let mut switch = None;
+        let mut close = None;
+        let mut new = false;
+        ui.horizontal(|ui| {
+            for idx in 0..self.document_count() {
+                let hover = self
+                    .document(idx)
+                    .binary_data
+                    .file_path()
+                    .map_or_else(|| "No file".to_string(), |path| path.display().to_string());
+                if ui
+                    .selectable_label(idx == self.active_document, self.document_title(idx))
+                    .on_hover_text(hover)
+                    .clicked()
+                {
+                    switch = Some(idx);
+                }
+                if ui.small_button("✖").on_hover_text("Close tab").clicked() {
+                    close = Some(idx);
+                }
+                ui.separator();
+            }
+            new = ui.small_button("+").on_hover_text("New tab (Ctrl+T)").clicked();
+        });
+
+        if let Some(idx) = switch {
+            self.switch_document(idx);
+        }
+        if let Some(idx) = close {
+            self.request_close_document(idx);
+        }
+        if new {
+            self.new_document();
+        }
+    }
+
+    /// Close tab `idx`, asking first if it has unsaved byte edits
+    fn request_close_document(&mut self, idx: usize) {
+        if self.document(idx).binary_data.is_modified() {
+            self.close_tab_confirm = Some(idx);
+        } else {
+            self.close_document(idx);
+        }
+    }
+
+    /// Ask whether to discard the unsaved byte edits of the tab being closed
+    fn show_close_tab_confirm_window(&mut self, ctx: &egui::Context) {
+        let Some(idx) = self.close_tab_confirm.filter(|&idx| idx < self.document_count()) else {
+            self.close_tab_confirm = None;
+            return;
+        };
+
+        egui::Window::new("Close Tab")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("{} has unsaved edits.", self.document_title(idx)));
+                ui.horizontal(|ui| {
+                    if ui.button("Discard and Close").clicked() {
+                        self.close_tab_confirm = None;
+                        self.close_document(idx);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.close_tab_confirm = None;
+                    }
+                });
+            });
+    }
+
+    /// Pick a second file and show it next to the hex view, marking the bytes that differ
+    fn compare_with_file(&mut self) {
+        let Some(path) = rfd::FileDialog::new().pick_file() else {
+            return;
+        };
+        match CompareView::open(path.clone()) {
+            Ok((comparison, map_error)) => {
+                self.notifications.info(format!("Comparing with: {}", path.display()));
+                self.report_map_error(map_error);
+                self.doc.comparison = Some(comparison);
+            }
+            Err(e) => self.notifications.error(format!("Error loading {} to compare: {}", path.display(), e)),
+        }
+    }
+
+    /// Stop comparing with the second file
+    fn close_comparison(&mut self) {
+        self.doc.comparison = None;
+        self.doc.hex_view.clear_diff();
+    }
+
+    /// Render the recent binaries and schemas submenus, dropping entries that no longer exist
+    fn show_recent_menus(&mut self, ui: &mut egui::Ui) {
+        let mut open = None;
+        let mut load = None;
+        ui.menu_button("Open Recent", |ui| {
+            self.recent.prune();
+            if self.recent.files.is_empty() {
+                ui.label(egui::RichText::new("No recent files").weak());
+            }
+            for path in &self.recent.files {
+                if ui.button(path.display().to_string()).clicked() {
+                    open = Some(path.clone());
+                    ui.close_menu();
+                }
+            }
+        });
+        ui.menu_button("Recent Schemas", |ui| {
+            self.recent.prune();
+            if self.recent.schemas.is_empty() {
+                ui.label(egui::RichText::new("No recent schemas").weak());
+            }
+            for path in &self.recent.schemas {
+                if ui.button(path.display().to_string()).clicked() {
+                    load = Some(path.clone());
                     ui.close_menu();
                 }
+            }
+        });
+
+        if let Some(path) = open {
+            self.open_path(path);
+        }
+        if let Some(path) = load {
+            self.load_schema_from_path(path);
+        }
+    }
+
+    /// Reload the current file from disk, keeping the hex view at the same position
+    fn reload_file(&mut self) {
+        let Some(path) = self.doc.binary_data.file_path().cloned() else {
+            return;
+        };
+
+        let top_offset = self.doc.hex_view.top_offset();
+        let old_size = self.doc.binary_data.size();
+        let was_out_of_bounds = self.out_of_bounds_field_indices();
+        match self.doc.binary_data.load_from_file(path.clone()) {
+            Ok(map_error) => {
+                self.notifications.info(format!("Reloaded file: {}", path.display()));
+                self.report_map_error(map_error);
+            }
+            Err(e) => {
+                self.notifications.error(format!("Error reloading {}: {}", path.display(), e));
+                return;
+            }
+        }
+
+        self.doc.relative_base = None;
+        self.analysis_view.invalidate();
+        self.strings_view.invalidate();
+        self.doc.hex_view.restore_top_offset(top_offset);
+
+        let new_size = self.doc.binary_data.size();
+        if new_size != old_size {
+            self.notifications.info(format!("File size changed from {} to {} bytes", old_size, new_size));
+        }
+        let newly_out_of_bounds: Vec<String> = self
+            .out_of_bounds_field_indices()
+            .into_iter()
+            .filter(|idx| !was_out_of_bounds.contains(idx))
+            .map(|idx| self.doc.fields[idx].name.clone())
+            .collect();
+        if !newly_out_of_bounds.is_empty() {
+            self.notifications.warning(format!(
+                "{} field(s) no longer fit in the file: {}",
+                newly_out_of_bounds.len(),
+                newly_out_of_bounds.join(", ")
+            ));
+        }
+    }
+
+    /// Write the edited bytes back to the file they were loaded from
+    fn save_binary(&mut self) {
+        match self.doc.binary_data.save() {
+            Ok(()) => {
+                let path = self.doc.binary_data.file_path().map(|p| p.display().to_string()).unwrap_or_default();
+                self.notifications.info(format!("Saved binary: {}", path));
+            }
+            Err(e) => self.io_error = Some(format!("Error saving binary: {}", e)),
+        }
+    }
+
+    /// Write the edited bytes of every tab back to their files
+    fn save_all_binaries(&mut self) {
+        if self.doc.binary_data.is_modified() {
+            self.save_binary();
+        }
+        for doc in &mut self.documents {
+            if doc.binary_data.is_modified() {
+                match doc.binary_data.save() {
+                    Ok(()) => {
+                        let path = doc.binary_data.file_path().map(|p| p.display().to_string()).unwrap_or_default();
+                        self.notifications.info(format!("Saved binary: {}", path));
+                    }
+                    Err(e) => self.io_error = Some(format!("Error saving binary: {}", e)),
+                }
+            }
+        }
+    }
+
+    /// Write the bytes to a new file chosen in a save dialog, which then becomes the current file
+    fn save_binary_as(&mut self) {
+        let mut dialog = rfd::FileDialog::new();
+        if let Some(name) = self.doc.binary_data.file_path().and_then(|p| p.file_name()) {
+            dialog = dialog.set_file_name(name.to_string_lossy());
+        }
+        if let Some(path) = dialog.save_file() {
+            match self.doc.binary_data.save_as(path.clone()) {
+                Ok(()) => self.notifications.info(format!("Saved binary: {}", path.display())),
+                Err(e) => self.io_error = Some(format!("Error saving binary: {}", e)),
+            }
+        }
+    }
+
+    /// Ask before overwriting the original file with the edited bytes
+    fn show_save_binary_confirm_window(&mut self, ctx: &egui::Context) {
+        if !self.save_binary_confirm_open {
+            return;
+        }
+        let Some(path) = self.doc.binary_data.file_path().cloned() else {
+            self.save_binary_confirm_open = false;
+            return;
+        };
+
+        egui::Window::new("Save Binary")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("Overwrite {} with the edited bytes?", path.display()));
+                ui.horizontal(|ui| {
+                    if ui.button("Overwrite").clicked() {
+                        self.save_binary();
+                        self.save_binary_confirm_open = false;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.save_binary_confirm_open = false;
+                    }
+                });
+            });
+    }
+
+    /// Hold back closing the window while there are unsaved byte edits, until confirmed
+    fn handle_close_request(&mut self, ctx: &egui::Context) {
+        if ctx.input(|i| i.viewport().close_requested()) && self.any_document_modified() && !self.quit_confirmed {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.quit_confirm_open = true;
+        }
+    }
+
+    /// Ask whether to save, discard or keep unsaved byte edits when quitting
+    fn show_quit_confirm_window(&mut self, ctx: &egui::Context) {
+        if !self.quit_confirm_open {
+            return;
+        }
+
+        egui::Window::new("Unsaved Changes")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("The binary has unsaved edits.");
+                ui.horizontal(|ui| {
+                    let can_save = std::iter::once(&self.doc)
+                        .chain(&self.documents)
+                        .all(|doc| !doc.binary_data.is_modified() || doc.binary_data.file_path().is_some());
+                    if ui.add_enabled(can_save, egui::Button::new("Save and Quit")).clicked() {
+                        self.quit_confirm_open = false;
+                        self.save_all_binaries();
+                        if !self.any_document_modified() {
+                            self.quit_confirmed = true;
+                            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                        }
+                    }
+                    if ui.button("Discard and Quit").clicked() {
+                        self.quit_confirm_open = false;
+                        self.quit_confirmed = true;
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.quit_confirm_open = false;
+                    }
+                });
+            });
+    }
+
+    /// Show the byte order detection result, with the custom magics it also checks
+    fn show_detect_endianness_window(&mut self, ctx: &egui::Context) {
+        if !self.detect_endianness_window_open {
+            return;
+        }
+
+        let mut window_open = self.detect_endianness_window_open;
+        egui::Window::new("Detect Endianness")
+            .open(&mut window_open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let guess = analysis::detect_endianness_with(self.doc.binary_data.bytes(), &self.preferences.endian_magics);
+                match &guess {
+                    Some(guess) => {
+                        let name = match guess.endianness {
+                            Endianness::Little => "Little endian",
+                            Endianness::Big => "Big endian",
+                        };
+                        ui.strong(format!("{} ({} confidence)", name, guess.confidence.name()));
+                        ui.label(egui::RichText::new(format!("Found: {}", guess.reason)).weak());
+                    }
+                    None => {
+                        ui.label("No known byte order marker at the start of the file.");
+                    }
+                }
+
+                ui.horizontal(|ui| {
+                    let detected = guess.as_ref().map(|guess| guess.endianness);
+                    if ui
+                        .add_enabled(
+                            detected.is_some() && !self.doc.fields.is_empty() && !self.doc.schema_locked,
+                            egui::Button::new("Apply to All Fields"),
+                        )
+                        .clicked()
+                        && let Some(endianness) = detected
+                        && self.check_schema_unlocked()
+                    {
+                        self.record_undo();
+                        set_endianness(&mut self.doc.fields, endianness);
+                    }
+                    if ui
+                        .add_enabled(detected.is_some(), egui::Button::new("Use for New Fields"))
+                        .clicked()
+                        && let Some(endianness) = detected
+                    {
+                        self.preferences.default_endianness = endianness;
+                    }
+                });
 
                 ui.separator();
+                ui.label("Custom 16-bit magics, matched in either byte order:");
+                let mut removed = None;
+                for (idx, magic) in self.preferences.endian_magics.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.monospace(format!("0x{:04X}", magic));
+                        if ui.small_button("✖").on_hover_text("Remove").clicked() {
+                            removed = Some(idx);
+                        }
+                    });
+                }
+                if let Some(idx) = removed {
+                    self.preferences.endian_magics.remove(idx);
+                }
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.endian_magic_input)
+                            .hint_text("0x4D42")
+                            .desired_width(80.0),
+                    );
+                    let magic = parse_offset(&self.endian_magic_input).and_then(|value| u16::try_from(value).ok());
+                    if ui.add_enabled(magic.is_some(), egui::Button::new("Add")).clicked()
+                        && let Some(magic) = magic
+                    {
+                        if !self.preferences.endian_magics.contains(&magic) {
+                            self.preferences.endian_magics.push(magic);
+                        }
+                        self.endian_magic_input.clear();
+                    }
+                });
+            });
+        self.detect_endianness_window_open = window_open;
+    }
 
-                if ui.button("Quit").clicked() {
-                    ui.ctx().send_viewport_cmd(egui::ViewportCommand::Close);
+    /// Show the "Go to Offset" dialog, which jumps the hex view to an absolute file offset
+    fn show_goto_window(&mut self, ctx: &egui::Context) {
+        if !self.goto_window_open {
+            return;
+        }
+
+        let mut window_open = self.goto_window_open;
+        egui::Window::new("Go to Offset")
+            .open(&mut window_open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let response = ui.horizontal(|ui| {
+                    ui.label("Offset:");
+                    let response = ui.add(egui::TextEdit::singleline(&mut self.goto_input).hint_text("0x... or decimal"));
+                    response.request_focus();
+                    response
+                });
+                let submitted = response.inner.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+
+                if let Some(error) = &self.goto_error {
+                    ui.colored_label(egui::Color32::from_rgb(200, 80, 80), error);
+                }
+
+                if ui.button("Go").clicked() || submitted {
+                    match parse_offset(&self.goto_input) {
+                        Some(offset) if offset < self.doc.binary_data.size() => {
+                            let now = ui.input(|i| i.time);
+                            self.doc.hex_view.go_to_offset(offset, now);
+                            self.view_focus = ViewFocus::HexView;
+                            self.goto_error = None;
+                            self.goto_window_open = false;
+                        }
+                        Some(offset) => {
+                            self.goto_error = Some(format!(
+                                "0x{:X} is past the end of the file (0x{:X} bytes)",
+                                offset,
+                                self.doc.binary_data.size()
+                            ));
+                        }
+                        None => self.goto_error = Some("Enter a hex (0x...) or decimal offset".to_string()),
+                    }
                 }
             });
 
-            ui.menu_button("Schema", |ui| {
-                if ui.button("Add Field...").clicked() {
-                    self.add_field_window_open = true;
-                    ui.close_menu();
-                }
+        // Closing with the window's button
+        if !window_open {
+            self.goto_window_open = false;
+        }
+    }
+
+    /// Show the last file I/O error until dismissed
+    fn show_io_error_window(&mut self, ctx: &egui::Context) {
+        let Some(error) = self.io_error.clone() else {
+            return;
+        };
+
+        egui::Window::new("Error")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.colored_label(egui::Color32::from_rgb(200, 80, 80), error);
+                if ui.button("OK").clicked() {
+                    self.io_error = None;
+                }
+            });
+    }
+
+    /// Offer to load the schema last used with this binary, if it isn't already loaded
+    fn offer_associated_schema(&mut self, binary_path: &Path) {
+        self.doc.pending_schema_offer = self
+            .schema_associations
+            .schema_for(binary_path)
+            .filter(|schema| self.doc.schema_file_path.as_ref() != Some(*schema))
+            .cloned();
+    }
+
+    /// Remember the current schema file as the one used with the loaded binary
+    fn record_schema_association(&mut self) {
+        if let (Some(binary), Some(schema)) = (self.doc.binary_data.file_path(), &self.doc.schema_file_path) {
+            self.schema_associations.record(binary, schema);
+        }
+    }
+
+    /// Render the controls defining the string pool region
+    fn show_string_pool_menu(&mut self, ui: &mut egui::Ui) {
+        let mut enabled = self.doc.string_pool.is_some();
+        if ui.checkbox(&mut enabled, "Define string pool").changed() {
+            self.doc.string_pool = enabled.then(|| StringPool::new(0, self.doc.binary_data.size()));
+        }
+        if let Some(pool) = &mut self.doc.string_pool {
+            ui.horizontal(|ui| {
+                ui.label("Offset:");
+                ui.add(egui::DragValue::new(&mut pool.offset).hexadecimal(1, false, true).prefix("0x"));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Length:");
+                ui.add(egui::DragValue::new(&mut pool.length).suffix(" bytes"));
+            });
+        }
+
+        // The pool is part of the schema, so it's stored relative to the schema base
+        let selection = self.doc.hex_view.selection();
+        if ui
+            .add_enabled(selection.is_some(), egui::Button::new("Set from Hex Selection"))
+            .clicked()
+            && let Some(selection) = selection
+        {
+            let offset = selection.start.saturating_sub(self.doc.schema_base);
+            self.doc.string_pool = Some(StringPool::new(offset, selection.len()));
+            ui.close_menu();
+        }
+    }
+
+    /// Render the top menu bar
+    fn show_menu(&mut self, ui: &mut egui::Ui) {
+        egui::menu::bar(ui, |ui| {
+            ui.menu_button("File", |ui| {
+                if ui.button("Open...").clicked() {
+                    self.open_file();
+                    ui.close_menu();
+                }
+
+                if ui.button("New Tab (Ctrl+T)").clicked() {
+                    self.new_document();
+                    ui.close_menu();
+                }
+
+                if ui.button("Close Tab (Ctrl+W)").clicked() {
+                    self.request_close_document(self.active_document);
+                    ui.close_menu();
+                }
+
+                self.show_recent_menus(ui);
+
+                if ui
+                    .add_enabled(self.doc.binary_data.file_path().is_some(), egui::Button::new("Reload (F5)"))
+                    .clicked()
+                {
+                    self.reload_file();
+                    ui.close_menu();
+                }
+
+                ui.separator();
+
+                if ui.add_enabled(self.doc.binary_data.is_loaded(), egui::Button::new("Compare With...")).clicked() {
+                    self.compare_with_file();
+                    ui.close_menu();
+                }
+
+                if ui.add_enabled(self.doc.comparison.is_some(), egui::Button::new("Close Comparison")).clicked() {
+                    self.close_comparison();
+                    ui.close_menu();
+                }
+
+                ui.separator();
+
+                let modified = self.doc.binary_data.is_modified();
+                if ui
+                    .add_enabled(
+                        modified && self.doc.binary_data.file_path().is_some(),
+                        egui::Button::new("Save Binary"),
+                    )
+                    .clicked()
+                {
+                    self.save_binary_confirm_open = true;
+                    ui.close_menu();
+                }
+
+                if ui.add_enabled(self.doc.binary_data.is_loaded(), egui::Button::new("Save Binary As...")).clicked() {
+                    self.save_binary_as();
+                    ui.close_menu();
+                }
+
+                ui.separator();
+
+                if ui.button("Quit").clicked() {
+                    ui.ctx().send_viewport_cmd(egui::ViewportCommand::Close);
+                }
+            });
+
+            ui.menu_button("Edit", |ui| {
+                if ui
+                    .add_enabled(!self.doc.undo_stack.is_empty(), egui::Button::new("Undo").shortcut_text("Ctrl+Z"))
+                    .clicked()
+                {
+                    self.undo();
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(!self.doc.redo_stack.is_empty(), egui::Button::new("Redo").shortcut_text("Ctrl+Shift+Z"))
+                    .clicked()
+                {
+                    self.redo();
+                    ui.close_menu();
+                }
+            });
+
+            ui.menu_button("Schema", |ui| {
+                ui.checkbox(&mut self.doc.schema_locked, "🔒 Lock Schema")
+                    .on_hover_text("Prevent changes to the fields while exploring the binary");
+                ui.separator();
+
+                if ui.add_enabled(!self.doc.schema_locked, egui::Button::new("Add Field...")).clicked() {
+                    self.open_add_field();
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(
+                        self.last_added_field.is_some() && !self.doc.schema_locked,
+                        egui::Button::new("Repeat Last Add").shortcut_text("."),
+                    )
+                    .on_hover_text("Add a field like the last one, right after it")
+                    .clicked()
+                {
+                    self.repeat_last_add();
+                    ui.close_menu();
+                }
+
+                if ui.add_enabled(!self.doc.schema_locked, egui::Button::new("Define from Text...")).clicked() {
+                    self.define_text_window_open = true;
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(self.doc.hex_view.selection().is_some(), egui::Button::new("Fill Selection with Fields..."))
+                    .on_disabled_hover_text("Shift+click in the hex view to select a byte range")
+                    .clicked()
+                {
+                    self.fill_error = None;
+                    self.fill_window_open = true;
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(!self.doc.selected_fields.is_empty(), egui::Button::new("Group into Struct"))
+                    .on_hover_text("Replace the selected fields with a struct containing them")
+                    .clicked()
+                {
+                    self.group_selected_fields();
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(!self.doc.selected_fields.is_empty(), egui::Button::new("Shift Selected Fields..."))
+                    .on_hover_text("Move the selected fields by the same number of bytes")
+                    .clicked()
+                {
+                    self.shift_error = None;
+                    self.shift_window_open = true;
+                    ui.close_menu();
+                }
+
+                let ungroupable = self
+                    .primary_selected_field()
+                    .and_then(|idx| self.doc.fields.get(idx))
+                    .is_some_and(|field| field.data_type.is_struct() && !field.is_array());
+                if ui
+                    .add_enabled(ungroupable, egui::Button::new("Ungroup Struct"))
+                    .on_hover_text("Replace the selected struct with its members")
+                    .on_disabled_hover_text("Select a single (non-array) struct field")
+                    .clicked()
+                    && let Some(idx) = self.primary_selected_field()
+                {
+                    self.ungroup_struct(idx);
+                    ui.close_menu();
+                }
+
+                ui.separator();
+
+                if ui.button("Save Schema...").clicked() {
+                    self.save_schema();
+                    ui.close_menu();
+                }
+
+                if ui.button("Save Schema as JSON...").clicked() {
+                    self.save_schema_dialog("schema.json");
+                    ui.close_menu();
+                }
+
+                if ui.button("Load Schema...").clicked() {
+                    self.load_schema();
+                    ui.close_menu();
+                }
+
+                ui.add_enabled_ui(!self.doc.schema_locked, |ui| {
+                    ui.menu_button("New from Template", |ui| {
+                        for template in TEMPLATES {
+                            if ui.button(template.name).on_hover_text(template.description).clicked() {
+                                self.new_schema_from_template(template);
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                });
+
+                if ui.add_enabled(!self.doc.schema_locked, egui::Button::new("Import ImHex Pattern...")).clicked() {
+                    self.import_imhex_pattern();
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(!self.doc.schema_locked, egui::Button::new("Import C Struct..."))
+                    .on_hover_text("Fields are placed from the hex cursor, or the start of the schema")
+                    .clicked()
+                {
+                    self.import_c_struct();
+                    ui.close_menu();
+                }
+
+                ui.separator();
+
+                if ui.button("Export C Struct...").clicked() {
+                    self.export_struct(StructLanguage::C);
+                    ui.close_menu();
+                }
+
+                if ui.button("Export Rust Struct...").clicked() {
+                    self.export_struct(StructLanguage::Rust);
+                    ui.close_menu();
+                }
+
+                if ui.button("Export to Kaitai (.ksy)...").clicked() {
+                    self.export_struct(StructLanguage::Kaitai);
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(self.doc.binary_data.is_loaded(), egui::Button::new("Export Values..."))
+                    .on_hover_text("Write the decoded field values to CSV or JSON")
+                    .clicked()
+                {
+                    self.export_values();
+                    ui.close_menu();
+                }
+
+                ui.separator();
+
+                ui.menu_button("Name Validation", |ui| {
+                    ui.checkbox(
+                        &mut self.preferences.warn_invalid_identifiers,
+                        "Warn on names that aren't valid identifiers",
+                    );
+                    ui.checkbox(
+                        &mut self.preferences.block_invalid_names,
+                        "Block duplicate/invalid names instead of warning",
+                    );
+                    ui.checkbox(
+                        &mut self.preferences.require_field_names,
+                        "Require names (otherwise derived from the offset)",
+                    );
+                });
+
+                ui.menu_button("Default Endianness", |ui| {
+                    for endianness in [Endianness::Little, Endianness::Big] {
+                        ui.radio_value(&mut self.preferences.default_endianness, endianness, endianness.name());
+                    }
+                    ui.label(egui::RichText::new("Applies to new fields only").weak());
+                });
+
+                if ui
+                    .add_enabled(self.doc.binary_data.is_loaded(), egui::Button::new("Detect Endianness..."))
+                    .on_hover_text("Guess the byte order from a marker at the start of the file")
+                    .clicked()
+                {
+                    self.detect_endianness_window_open = true;
+                    ui.close_menu();
+                }
+
+                ui.separator();
+
+                let mut fixed_size = self.doc.schema_expected_size.is_some();
+                if ui.checkbox(&mut fixed_size, "Fixed expected file size").changed() {
+                    self.doc.schema_expected_size = fixed_size.then(|| schema::fields_end(&self.doc.fields));
+                }
+                if let Some(size) = &mut self.doc.schema_expected_size {
+                    ui.add(egui::DragValue::new(size).suffix(" bytes"));
+                }
+
+                ui.menu_button("String Pool", |ui| {
+                    self.show_string_pool_menu(ui);
+                });
+
+                ui.separator();
+
+                if ui.add_enabled(!self.doc.schema_locked, egui::Button::new("Clear All Fields")).clicked() {
+                    self.record_undo();
+                    self.doc.fields.clear();
+                    self.doc.schema_expected_size = None;
+                    self.doc.string_pool = None;
+                    self.doc.field_watches.clear();
+                    ui.close_menu();
+                }
+            });
+
+            ui.menu_button("View", |ui| {
+                if ui.button("Analysis...").clicked() {
+                    self.analysis_window_open = true;
+                    ui.close_menu();
+                }
+
+                if ui.button("Strings...").clicked() {
+                    self.strings_window_open = true;
+                    ui.close_menu();
+                }
+
+                if ui.button("Message Log...").clicked() {
+                    self.log_window_open = true;
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(self.doc.binary_data.is_loaded(), egui::Button::new("Go to Offset...").shortcut_text("Ctrl+G"))
+                    .clicked()
+                {
+                    self.goto_error = None;
+                    self.goto_window_open = true;
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(
+                        self.doc.hex_view.cursor_offset().is_some(),
+                        egui::Button::new("Offsets Relative to Cursor").shortcut_text("Ctrl+B"),
+                    )
+                    .on_hover_text("Show offsets in the offset columns as distances from the cursor byte")
+                    .clicked()
+                {
+                    self.set_relative_base_at_cursor();
+                    ui.close_menu();
+                }
+                if ui
+                    .add_enabled(self.doc.relative_base.is_some(), egui::Button::new("Absolute Offsets"))
+                    .clicked()
+                {
+                    self.doc.relative_base = None;
+                    ui.close_menu();
+                }
+
+                ui.separator();
+
+                ui.menu_button("Theme", |ui| {
+                    for (theme, name) in [
+                        (egui::ThemePreference::System, "Follow System"),
+                        (egui::ThemePreference::Dark, "Dark"),
+                        (egui::ThemePreference::Light, "Light"),
+                    ] {
+                        if ui.radio_value(&mut self.theme, theme, name).clicked() {
+                            ui.ctx().set_theme(theme);
+                            ui.close_menu();
+                        }
+                    }
+                });
+
+                ui.menu_button("Offset Format", |ui| {
+                    for &format in OffsetFormat::all() {
+                        if ui
+                            .radio_value(&mut self.view_settings.offset_format, format, format.name())
+                            .clicked()
+                        {
+                            ui.close_menu();
+                        }
+                    }
+                });
+
+                ui.menu_button("Number Format", |ui| {
+                    for &format in NumberFormat::all() {
+                        if ui
+                            .radio_value(&mut self.view_settings.number_format, format, format.name())
+                            .clicked()
+                        {
+                            ui.close_menu();
+                        }
+                    }
+                });
+
+                ui.menu_button("Bytes per Row", |ui| {
+                    for bytes in BYTES_PER_ROW_CHOICES {
+                        if ui
+                            .radio_value(&mut self.view_settings.bytes_per_row, bytes, bytes.to_string())
+                            .clicked()
+                        {
+                            // Keep the same bytes in view after the rows reflow
+                            self.doc.hex_view.restore_top_offset(self.doc.hex_view.top_offset());
+                            ui.close_menu();
+                        }
+                    }
+                });
+
+                if ui.button("Field Colors...").clicked() {
+                    self.palette_window_open = true;
+                    ui.close_menu();
+                }
+
+                ui.checkbox(&mut self.view_settings.lowercase_hex, "Lowercase Hex");
+                ui.checkbox(&mut self.view_settings.group_thousands, "Thousands Separators");
+                ui.checkbox(&mut self.view_settings.show_inspector, "Data Inspector");
+                ui.checkbox(
+                    &mut self.view_settings.highlight_field_matches,
+                    "Highlight Matches of Selected Field",
+                );
+                ui.horizontal(|ui| {
+                    ui.label("Flag floats above:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.view_settings.float_warning_magnitude)
+                            .range(1.0..=f64::MAX)
+                            .speed(1e6)
+                            .custom_formatter(|v, _| format!("{:e}", v)),
+                    );
+                });
+            });
+        });
+    }
+
+    /// Show the "Add Field" dialog window
+    fn show_add_field_window(&mut self, ctx: &egui::Context) {
+        // Locking the schema closes the field dialogs
+        if self.doc.schema_locked {
+            self.add_field_window_open = false;
+        }
+        if !self.add_field_window_open {
+            return;
+        }
+
+        let mut window_open = self.add_field_window_open;
+        egui::Window::new("Add Field")
+            .open(&mut window_open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Name:");
+                    let hint = if self.preferences.require_field_names { "" } else { "(derived from offset)" };
+                    ui.add(egui::TextEdit::singleline(&mut self.new_field_name).hint_text(hint));
+                });
+
+                let name_problems = self.name_problems(&self.new_field_name, None);
+                self.show_name_problems(ui, &name_problems);
+
+                ui.horizontal(|ui| {
+                    ui.label("Label:");
+                    ui.text_edit_singleline(&mut self.new_field_label);
+                    ui.label("(optional, shown instead of the name)");
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Offset:");
+                    ui.text_edit_singleline(&mut self.new_field_offset);
+                    ui.label("(hex or decimal)");
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Type:");
+                    egui::ComboBox::from_id_salt("field_type")
+                        .selected_text(DataType::all()[self.new_field_type_idx].name())
+                        .show_ui(ui, |ui| {
+                            for (idx, dt) in DataType::all().iter().enumerate() {
+                                ui.selectable_value(&mut self.new_field_type_idx, idx, dt.name());
+                            }
+                        });
+
+                    // Byte order doesn't matter for single-byte types
+                    let multi_byte = DataType::all()[self.new_field_type_idx].size() > 1;
+                    ui.add_enabled_ui(multi_byte, |ui| {
+                        egui::ComboBox::from_id_salt("field_endianness")
+                            .selected_text(self.new_field_endianness.name())
+                            .show_ui(ui, |ui| {
+                                for endianness in [Endianness::Little, Endianness::Big] {
+                                    ui.selectable_value(&mut self.new_field_endianness, endianness, endianness.name());
+                                }
+                            });
+                    });
+                });
+
+                if DataType::all()[self.new_field_type_idx].is_byte_string() {
+                    ui.horizontal(|ui| {
+                        ui.label("Length:");
+                        ui.add(egui::DragValue::new(&mut self.new_field_string_len).range(1..=1_000_000));
+                        ui.label("bytes");
+                    });
+                }
+
+                if let DataType::SliceDescriptor { .. } = DataType::all()[self.new_field_type_idx] {
+                    show_slice_type_controls(ui, "new_slice", &mut self.new_field_slice_types);
+                }
+
+                if DataType::all()[self.new_field_type_idx].is_checksum() {
+                    let selection = self.schema_selection();
+                    show_checksum_controls(ui, "new_checksum", &mut self.new_field_checksum, selection);
+                }
+
+                if let DataType::Bits { .. } = DataType::all()[self.new_field_type_idx] {
+                    ui.horizontal(|ui| {
+                        ui.label("Bit offset:");
+                        ui.add(egui::DragValue::new(&mut self.new_field_bit_offset).range(0..=63));
+                        ui.label("Width:");
+                        ui.add(egui::DragValue::new(&mut self.new_field_bit_width).range(1..=64));
+                        egui::ComboBox::from_id_salt("bit_order")
+                            .selected_text(self.new_field_bit_order.name())
+                            .show_ui(ui, |ui| {
+                                for order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+                                    ui.selectable_value(&mut self.new_field_bit_order, order, order.name());
+                                }
+                            });
+                    });
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label("Count:");
+                    ui.add(egui::DragValue::new(&mut self.new_field_count).range(1..=1_000_000));
+                    ui.label("(1 for a single value)");
+                });
+
+                let is_integer = DataType::all()[self.new_field_type_idx].is_integer();
+                ui.horizontal(|ui| {
+                    ui.add_enabled(
+                        is_integer,
+                        egui::Checkbox::new(&mut self.new_field_is_pointer, "Pointer table"),
+                    )
+                    .on_disabled_hover_text("Only integer types can be pointers");
+                    if self.new_field_is_pointer && is_integer {
+                        ui.label("Base:");
+                        ui.text_edit_singleline(&mut self.new_field_pointer_base);
+                    }
+                });
+
+                ui.add_enabled(
+                    is_integer && !self.new_field_is_pointer,
+                    egui::Checkbox::new(&mut self.new_field_is_string_index, "String pool index"),
+                )
+                .on_disabled_hover_text("Only integer, non-pointer fields can index the string pool");
+
+                ui.horizontal(|ui| {
+                    ui.label("Comment:");
+                    ui.text_edit_singleline(&mut self.new_field_comment);
+                });
+
+                let new_field = self.create_field_from_input();
+                self.show_add_field_preview(ui, &new_field);
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(new_field.is_ok(), egui::Button::new("Add")).clicked()
+                        && let Ok(field) = new_field
+                    {
+                        self.last_added_field = Some(field.clone());
+                        self.record_undo();
+                        self.doc.fields.push(field);
+                        self.reset_add_field_form();
+                        self.add_field_window_open = false;
+                    }
+
+                    if ui.button("Cancel").clicked() {
+                        self.reset_add_field_form();
+                        self.add_field_window_open = false;
+                    }
+                });
+            });
+
+        self.add_field_window_open = window_open;
+    }
+
+    /// Describe problems with a field name: duplicates and (optionally) invalid identifiers
+    /// `editing` is the index of the field being edited, which doesn't count as a duplicate
+    fn name_problems(&self, name: &str, editing: Option<usize>) -> Vec<String> {
+        let mut problems = Vec::new();
+        if name.is_empty() {
+            if self.preferences.require_field_names {
+                problems.push(String::from("A name is required"));
+            }
+            return problems;
+        }
+
+        let duplicate = self
+            .doc
+            .fields
+            .iter()
+            .enumerate()
+            .any(|(idx, field)| Some(idx) != editing && field.name == name);
+        if duplicate {
+            problems.push(format!("Another field is already named '{}'", name));
+        }
+
+        if self.preferences.warn_invalid_identifiers && !export::is_valid_identifier(name) {
+            problems.push(format!(
+                "Not a valid identifier; exporters will use '{}'",
+                export::sanitize_identifier(name)
+            ));
+        }
+
+        problems
+    }
+
+    /// Whether a field with these name problems may be added/saved
+    fn names_acceptable(&self, problems: &[String]) -> bool {
+        problems.is_empty() || !self.preferences.block_invalid_names
+    }
+
+    /// Show name problems inline in a field dialog
+    fn show_name_problems(&self, ui: &mut egui::Ui, problems: &[String]) {
+        let color = if self.preferences.block_invalid_names {
+            egui::Color32::from_rgb(200, 80, 80)
+        } else {
+            egui::Color32::from_rgb(220, 160, 60)
+        };
+        for problem in problems {
+            ui.colored_label(color, format!("⚠ {}", problem));
+        }
+    }
+
+    /// Create a field from the current input values, or describe what's wrong with them
+    fn create_field_from_input(&self) -> Result<Field, String> {
+        if self.preferences.require_field_names && self.new_field_name.is_empty() {
+            return Err(String::from("Name required"));
+        }
+
+        let name_problems = self.name_problems(&self.new_field_name, None);
+        if !self.names_acceptable(&name_problems) {
+            return Err(name_problems.join("; "));
+        }
+
+        let offset = parse_offset(&self.new_field_offset).ok_or("Offset is not a number")?;
+        let data_type = resolve_data_type(
+            DataType::all()[self.new_field_type_idx],
+            self.new_field_bit_offset,
+            self.new_field_bit_width,
+            self.new_field_bit_order,
+            self.new_field_slice_types,
+            self.new_field_checksum,
+            self.new_field_string_len,
+        );
+
+        let mut field = Field::new(self.new_field_name.clone(), offset, data_type);
+        field.label = non_empty(&self.new_field_label);
+        field.endianness = self.new_field_endianness;
+        field.comment = self.new_field_comment.clone();
+        field.count = self.new_field_count.max(1);
+        field.is_pointer = self.new_field_is_pointer && data_type.is_integer();
+        field.is_string_index = self.new_field_is_string_index && data_type.is_integer() && !field.is_pointer;
+        if field.is_pointer {
+            field.pointer_base =
+                parse_signed_offset(&self.new_field_pointer_base).ok_or("Pointer base is not a number")?;
+        }
+
+        Ok(field)
+    }
+
+    /// Show what the field being added would decode to, or why it can't be added yet
+    fn show_add_field_preview(&self, ui: &mut egui::Ui, field: &Result<Field, String>) {
+        let error_color = egui::Color32::from_rgb(200, 80, 80);
+        let field = match field {
+            Ok(field) => field,
+            Err(e) => {
+                ui.colored_label(error_color, format!("✖ {}", e));
+                return;
+            }
+        };
+        if !self.doc.binary_data.is_loaded() {
+            return;
+        }
+
+        let data = self.doc.binary_data.bytes();
+        let placed = schema::place_fields(std::slice::from_ref(field), self.doc.schema_base);
+        let field = &placed[0];
+        ui.horizontal(|ui| {
+            ui.label("Preview:");
+            match field.read_value_as(data, self.view_settings.number_format, self.view_settings.float_format) {
+                Some(value) => {
+                    ui.monospace(value);
+                }
+                None => {
+                    // Not an error: a schema may describe files larger than this one
+                    ui.colored_label(
+                        egui::Color32::from_rgb(220, 160, 60),
+                        format!(
+                            "⚠ Field exceeds file size (ends at {}, file is {} bytes)",
+                            self.view_settings.format_offset_prefixed(field.offset + field.size(), data.len()),
+                            data.len()
+                        ),
+                    );
+                }
+            }
+        });
+    }
+
+    /// Reset the add field form to default values
+    fn reset_add_field_form(&mut self) {
+        self.new_field_name.clear();
+        self.new_field_label.clear();
+        self.new_field_offset = String::from("0");
+        self.new_field_type_idx = 0;
+        self.new_field_comment.clear();
+        self.new_field_count = 1;
+        self.new_field_bit_offset = 0;
+        self.new_field_bit_width = 1;
+        self.new_field_bit_order = BitOrder::MsbFirst;
+        self.new_field_slice_types = (IntType::U32, IntType::U32);
+        self.new_field_checksum = (ChecksumAlgorithm::Crc32, 0, 0);
+        self.new_field_string_len = 16;
+        self.new_field_is_pointer = false;
+        self.new_field_is_string_index = false;
+        self.new_field_pointer_base = String::from("0");
+    }
+
+    /// Show the "Define from Text" window for bulk entry of fields
+    fn show_define_text_window(&mut self, ctx: &egui::Context) {
+        // Locking the schema closes the field dialogs
+        if self.doc.schema_locked {
+            self.define_text_window_open = false;
+        }
+        if !self.define_text_window_open {
+            return;
+        }
+
+        let mut window_open = self.define_text_window_open;
+        egui::Window::new("Define from Text")
+            .open(&mut window_open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label("One field per line: name: type[count] [le|be] [@offset] [# comment]");
+                ui.label(
+                    egui::RichText::new("e.g. `magic: u32 @0x0 # file signature` or `version u16`")
+                        .color(egui::Color32::from_rgb(120, 120, 120))
+                        .italics(),
+                );
+
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.define_text)
+                        .code_editor()
+                        .desired_rows(12)
+                        .desired_width(f32::INFINITY),
+                );
+
+                if let Some(error) = &self.define_text_error {
+                    ui.colored_label(egui::Color32::from_rgb(200, 80, 80), error);
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("Add Fields").clicked() && self.check_schema_unlocked() {
+                        // Fields without an explicit offset continue after the existing schema
+                        let start_offset = schema::fields_end(&self.doc.fields);
+                        match parse_field_text(&self.define_text, start_offset, self.preferences.default_endianness) {
+                            Ok(fields) => {
+                                self.record_undo();
+                                self.doc.fields.extend(fields);
+                                self.define_text.clear();
+                                self.define_text_error = None;
+                                self.define_text_window_open = false;
+                            }
+                            Err(e) => self.define_text_error = Some(e.to_string()),
+                        }
+                    }
+
+                    if ui.button("Cancel").clicked() {
+                        self.define_text_error = None;
+                        self.define_text_window_open = false;
+                    }
+                });
+            });
+
+        self.define_text_window_open &= window_open;
+    }
+
+    /// Show the "Fill Selection" window that carves the hex selection into fields
+    fn show_fill_selection_window(&mut self, ctx: &egui::Context) {
+        if !self.fill_window_open {
+            return;
+        }
+
+        let Some(selection) = self.doc.hex_view.selection() else {
+            self.fill_window_open = false;
+            return;
+        };
+
+        let mut window_open = self.fill_window_open;
+        egui::Window::new("Fill Selection with Fields")
+            .open(&mut window_open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let len = selection.len();
+                ui.label(format!(
+                    "{} bytes @ 0x{:X}..0x{:X}",
+                    len, selection.start, selection.end
+                ));
+
+                ui.horizontal(|ui| {
+                    ui.label("Type:");
+                    egui::ComboBox::from_id_salt("fill_field_type")
+                        .selected_text(DataType::all()[self.fill_type_idx].name())
+                        .show_ui(ui, |ui| {
+                            for (idx, dt) in DataType::all().iter().enumerate() {
+                                if !dt.is_parameterized() {
+                                    ui.selectable_value(&mut self.fill_type_idx, idx, dt.name());
+                                }
+                            }
+                        });
+                });
+
+                ui.checkbox(&mut self.fill_allow_remainder, "Pad leftover bytes with a padding field");
+
+                let size = DataType::all()[self.fill_type_idx].size();
+                let remainder = len % size;
+                let mut summary = format!("{} field(s)", len / size);
+                if remainder > 0 {
+                    summary.push_str(&format!(", {} leftover byte(s)", remainder));
+                }
+                ui.label(summary);
+
+                if let Some(error) = &self.fill_error {
+                    ui.colored_label(egui::Color32::from_rgb(200, 80, 80), error);
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("Add Fields").clicked() {
+                        match self.fill_selection(selection.clone()) {
+                            Ok(()) => self.fill_window_open = false,
+                            Err(e) => self.fill_error = Some(e),
+                        }
+                    }
+
+                    if ui.button("Cancel").clicked() {
+                        self.fill_window_open = false;
+                    }
+                });
+            });
+
+        self.fill_window_open &= window_open;
+    }
+
+    /// Carve `selection` into consecutive fields of the chosen type, added in one batch
+    fn fill_selection(&mut self, selection: std::ops::Range<usize>) -> Result<(), String> {
+        if self.doc.schema_locked {
+            return Err("The schema is locked".to_string());
+        }
+        let data_type = DataType::all()[self.fill_type_idx];
+        let size = data_type.size();
+        let count = selection.len() / size;
+        let remainder = selection.len() % size;
+
+        if count == 0 {
+            return Err(format!("Selection is smaller than a {}", data_type.name()));
+        }
+        if remainder > 0 && !self.fill_allow_remainder {
+            return Err(format!(
+                "{} bytes don't divide evenly into {} fields",
+                selection.len(),
+                data_type.name()
+            ));
+        }
+        // Field offsets are relative to the schema base
+        let Some(start) = selection.start.checked_sub(self.doc.schema_base) else {
+            return Err("Selection starts before the schema base".to_string());
+        };
+
+        let mut used: HashSet<String> = self.doc.fields.iter().map(|f| f.name.clone()).collect();
+        let mut next = 0;
+        let mut batch = Vec::with_capacity(count + 1);
+        for i in 0..count {
+            let name = take_auto_name("field", &mut next, &mut used);
+            let mut field = Field::new(name, start + i * size, data_type);
+            field.endianness = self.preferences.default_endianness;
+            batch.push(field);
+        }
+        if remainder > 0 {
+            let name = take_auto_name("padding", &mut 0, &mut used);
+            let mut padding = Field::new(name, start + count * size, DataType::U8);
+            padding.count = remainder;
+            batch.push(padding);
+        }
+
+        self.record_undo();
+        self.doc.fields.extend(batch);
+        Ok(())
+    }
+
+    /// Get the hex selection relative to the schema base, if it starts at or after the base
+    fn schema_selection(&self) -> Option<Range<usize>> {
+        let selection = self.doc.hex_view.selection()?;
+        let start = selection.start.checked_sub(self.doc.schema_base)?;
+        Some(start..start + selection.len())
+    }
+
+    /// Define a fixed-length string field over `len` bytes at file offset `offset`
+    fn add_string_field(&mut self, offset: usize, len: usize) {
+        if !self.check_schema_unlocked() {
+            return;
+        }
+        // Field offsets are relative to the schema base
+        let Some(start) = offset.checked_sub(self.doc.schema_base) else {
+            self.notifications.error(format!("String at 0x{:X} starts before the schema base", offset));
+            return;
+        };
+
+        let mut used: HashSet<String> = self.doc.fields.iter().map(|f| f.name.clone()).collect();
+        let name = take_auto_name("string", &mut 0, &mut used);
+        self.record_undo();
+        self.doc.fields.push(Field::new(name, start, DataType::FixedString { len }));
+        self.doc.hex_view.scroll_to_offset(offset);
+    }
+
+    /// Open the Add Field dialog, covering the hex selection or snapping the offset to the cursor
+    fn open_add_field(&mut self) {
+        if !self.check_schema_unlocked() {
+            return;
+        }
+        if let Some(selection) = self.doc.hex_view.selection() {
+            self.start_add_field_for_range(selection);
+            return;
+        }
+        match self.doc.hex_view.cursor_offset() {
+            Some(cursor) => self.start_add_field_at(cursor),
+            None => {
+                self.new_field_endianness = self.preferences.default_endianness;
+                self.add_field_window_open = true;
+            }
+        }
+    }
+
+    /// Open the Add Field dialog pre-filled with the absolute file `offset`
+    fn start_add_field_at(&mut self, offset: usize) {
+        if !self.check_schema_unlocked() {
+            return;
+        }
+        // Field offsets are relative to the schema base
+        let offset = offset.saturating_sub(self.doc.schema_base);
+        self.new_field_offset = format!("0x{:X}", offset);
+        self.new_field_endianness = self.preferences.default_endianness;
+        self.add_field_window_open = true;
+    }
+
+    /// Open the Add Field dialog covering the absolute file `range`
+    /// The type is guessed from the length: 1, 2, 4 and 8 bytes become unsigned integers, anything else raw bytes
+    fn start_add_field_for_range(&mut self, range: Range<usize>) {
+        if !self.check_schema_unlocked() {
+            return;
+        }
+        self.start_add_field_at(range.start);
+        let guess = match range.len() {
+            1 => DataType::U8,
+            2 => DataType::U16,
+            4 => DataType::U32,
+            8 => DataType::U64,
+            len => {
+                self.new_field_string_len = len;
+                DataType::Bytes { len }
+            }
+        };
+        self.new_field_type_idx = DataType::all()
+            .iter()
+            .position(|dt| std::mem::discriminant(dt) == std::mem::discriminant(&guess))
+            .unwrap_or(0);
+    }
+
+    /// Open the Add Field dialog at the absolute file `offset` with the type and byte order picked
+    fn start_add_field_as(&mut self, offset: usize, data_type: DataType, endianness: Endianness) {
+        if !self.check_schema_unlocked() {
+            return;
+        }
+        self.start_add_field_at(offset);
+        self.new_field_type_idx = DataType::all().iter().position(|dt| *dt == data_type).unwrap_or(0);
+        self.new_field_endianness = endianness;
+        if let Some(len) = data_type.byte_len() {
+            self.new_field_string_len = len;
+        }
+    }
+
+    /// Add a field like the last added one at the offset just after it
+    fn repeat_last_add(&mut self) {
+        if !self.check_schema_unlocked() {
+            return;
+        }
+        let Some(last) = &self.last_added_field else {
+            return;
+        };
+
+        let mut field = last.clone();
+        field.offset = last.offset + last.size();
+        // Named fields continue their numbering (`entry_1` → `entry_2`); unnamed ones stay unnamed
+        if !field.name.is_empty() {
+            let mut used: HashSet<String> = self.doc.fields.iter().map(|f| f.name.clone()).collect();
+            let (prefix, mut next) = split_name_number(&field.name);
+            field.name = take_auto_name(prefix, &mut next, &mut used);
+        }
+
+        self.record_undo();
+        self.doc.fields.push(field.clone());
+        let idx = self.doc.fields.len() - 1;
+        self.doc.selected_fields.clear();
+        self.doc.selected_fields.insert(idx);
+        self.doc.last_selected_field = Some(idx);
+        let placed = schema::place_fields_in(&self.doc.fields, self.doc.schema_base, self.doc.binary_data.bytes())[idx].offset;
+        self.doc.hex_view.scroll_to_offset(placed);
+        self.last_added_field = Some(field);
+    }
+
+    /// Capture the schema state for undo
+    fn snapshot(&self) -> SchemaSnapshot {
+        SchemaSnapshot {
+            fields: self.doc.fields.clone(),
+            selected_fields: self.doc.selected_fields.clone(),
+            last_selected_field: self.doc.last_selected_field,
+            schema_expected_size: self.doc.schema_expected_size,
+            string_pool: self.doc.string_pool,
+        }
+    }
+
+    /// Save the schema state before an edit, dropping the oldest step past the depth cap
+    fn record_undo(&mut self) {
+        self.doc.undo_stack.push(self.snapshot());
+        if self.doc.undo_stack.len() > MAX_UNDO_DEPTH {
+            self.doc.undo_stack.remove(0);
+        }
+        self.doc.redo_stack.clear();
+        self.doc.comment_undo_field = None;
+    }
+
+    /// Replace the schema state with a snapshot, returning the state it replaced
+    fn restore_snapshot(&mut self, snapshot: SchemaSnapshot) -> SchemaSnapshot {
+        let previous = self.snapshot();
+        let len = snapshot.fields.len();
+        self.doc.fields = snapshot.fields;
+        self.doc.selected_fields = snapshot.selected_fields;
+        self.doc.selected_fields.retain(|&idx| idx < len);
+        self.doc.last_selected_field = snapshot.last_selected_field.filter(|&idx| idx < len);
+        self.doc.schema_expected_size = snapshot.schema_expected_size;
+        self.doc.string_pool = snapshot.string_pool;
+        self.doc.field_watches.retain(|&idx, _| idx < len);
+        self.doc.comment_undo_field = None;
+        previous
+    }
+
+    /// Revert the most recent schema edit
+    fn undo(&mut self) {
+        if !self.check_schema_unlocked() {
+            return;
+        }
+        if let Some(snapshot) = self.doc.undo_stack.pop() {
+            let current = self.restore_snapshot(snapshot);
+            self.doc.redo_stack.push(current);
+        }
+    }
+
+    /// Reapply the most recently undone schema edit
+    fn redo(&mut self) {
+        if !self.check_schema_unlocked() {
+            return;
+        }
+        if let Some(snapshot) = self.doc.redo_stack.pop() {
+            let current = self.restore_snapshot(snapshot);
+            self.doc.undo_stack.push(current);
+        }
+    }
+
+    /// Replace the selected fields with a struct containing them, at the position of the first
+    fn group_selected_fields(&mut self) {
+        if !self.check_schema_unlocked() {
+            return;
+        }
+        let mut indices: Vec<usize> = self.doc.selected_fields.iter().copied().filter(|&idx| idx < self.doc.fields.len()).collect();
+        if indices.is_empty() {
+            return;
+        }
+        indices.sort_unstable();
+
+        self.record_undo();
+        // Members are laid out by absolute offset, so anchors are resolved first
+        let placed = schema::place_fields(&self.doc.fields, 0);
+        let members: Vec<Field> = indices.iter().map(|&idx| placed[idx].clone()).collect();
+        let names: HashSet<String> = self.doc.fields.iter().map(|field| field.name.clone()).collect();
+        let name = (1..).map(|n| format!("struct_{}", n)).find(|name| !names.contains(name)).unwrap_or_default();
+        let group = Field::new_struct(name, members);
+
+        // Remove from the back so the earlier indices stay valid
+        let position = indices[0];
+        for &idx in indices.iter().rev() {
+            self.doc.fields.remove(idx);
+        }
+        self.doc.fields.insert(position, group);
+
+        // Indices shifted arbitrarily, so start over with just the new struct selected
+        self.doc.selected_fields.clear();
+        self.doc.selected_fields.insert(position);
+        self.doc.last_selected_field = Some(position);
+        self.doc.field_watches.clear();
+    }
+
+    /// Replace a struct field with its members, placed at absolute offsets
+    fn ungroup_struct(&mut self, idx: usize) {
+        if !self.check_schema_unlocked() {
+            return;
+        }
+        let Some(group) = self.doc.fields.get(idx).filter(|field| field.data_type.is_struct() && !field.is_array()) else {
+            return;
+        };
+
+        let members = group.struct_members(0);
+        let count = members.len();
+        self.record_undo();
+        self.doc.fields.splice(idx..=idx, members);
+        self.doc.selected_fields = (idx..idx + count).collect();
+        self.doc.last_selected_field = (count > 0).then_some(idx);
+        self.doc.field_watches.clear();
+    }
+
+    /// Swap two fields, remapping the selection, watches and edited field along with them
+    fn swap_fields(&mut self, a: usize, b: usize) {
+        if !self.check_schema_unlocked() {
+            return;
+        }
+        if a == b || a >= self.doc.fields.len() || b >= self.doc.fields.len() {
+            return;
+        }
+
+        self.record_undo();
+        self.doc.fields.swap(a, b);
+        let remap = |idx: usize| {
+            if idx == a {
+                b
+            } else if idx == b {
+                a
+            } else {
+                idx
+            }
+        };
+        self.doc.selected_fields = self.doc.selected_fields.iter().map(|&idx| remap(idx)).collect();
+        self.doc.last_selected_field = self.doc.last_selected_field.map(remap);
+        self.doc.field_watches = std::mem::take(&mut self.doc.field_watches)
+            .into_iter()
+            .map(|(idx, history)| (remap(idx), history))
+            .collect();
+        self.edit_field_idx = self.edit_field_idx.map(remap);
+    }
+
+    /// Delete a field, remapping the selection and watches of the fields after it
+    fn delete_field(&mut self, idx: usize) {
+        if !self.check_schema_unlocked() {
+            return;
+        }
+        if idx >= self.doc.fields.len() {
+            return;
+        }
 
-                ui.separator();
+        self.record_undo();
+        self.doc.fields.remove(idx);
+        // Remove deleted field from selection
+        self.doc.selected_fields.remove(&idx);
+        // Adjust all remaining selection indices
+        let old_selections: Vec<usize> = self.doc.selected_fields.iter().copied().collect();
+        self.doc.selected_fields.clear();
+        for &field_idx in &old_selections {
+            if field_idx > idx {
+                self.doc.selected_fields.insert(field_idx - 1);
+            } else if field_idx < idx {
+                self.doc.selected_fields.insert(field_idx);
+            }
+            // field_idx == idx was already removed above
+        }
+        // Drop the deleted field's watch and shift the others down
+        self.doc.field_watches = std::mem::take(&mut self.doc.field_watches)
+            .into_iter()
+            .filter(|&(field_idx, _)| field_idx != idx)
+            .map(|(field_idx, history)| {
+                (if field_idx > idx { field_idx - 1 } else { field_idx }, history)
+            })
+            .collect();
+        // Adjust last_selected_field
+        if let Some(last) = self.doc.last_selected_field {
+            if last == idx {
+                self.doc.last_selected_field = None;
+            } else if last > idx {
+                self.doc.last_selected_field = Some(last - 1);
+            }
+        }
+    }
 
-                if ui.button("Save Schema...").clicked() {
-                    self.save_schema();
-                    ui.close_menu();
-                }
+    /// Check that the schema may be edited, reporting the attempt if it's locked
+    fn check_schema_unlocked(&mut self) -> bool {
+        if self.doc.schema_locked {
+            self.notifications.warning("The schema is locked (Schema → Lock Schema)");
+        }
+        !self.doc.schema_locked
+    }
 
-                if ui.button("Load Schema...").clicked() {
-                    self.load_schema();
-                    ui.close_menu();
-                }
+    /// Insert a copy of field `idx` right after it, placed after its bytes and selected
+    /// Indices of the fields after it move up by one, like `delete_field` moves them down
+    fn duplicate_field(&mut self, idx: usize) {
+        if !self.check_schema_unlocked() {
+            return;
+        }
+        let Some(source) = self.doc.fields.get(idx) else {
+            return;
+        };
 
-                ui.separator();
+        let mut field = source.clone();
+        field.offset = source.offset + source.size();
+        // Named fields continue their numbering (`entry_1` → `entry_2`); unnamed ones stay unnamed
+        if !field.name.is_empty() {
+            let mut used: HashSet<String> = self.doc.fields.iter().map(|f| f.name.clone()).collect();
+            let (prefix, mut next) = split_name_number(&field.name);
+            field.name = take_auto_name(prefix, &mut next, &mut used);
+        }
 
-                if ui.button("Clear All Fields").clicked() {
-                    self.fields.clear();
-                    ui.close_menu();
-                }
-            });
-        });
+        self.record_undo();
+        let new_idx = idx + 1;
+        self.doc.fields.insert(new_idx, field);
+        let remap = |field_idx: usize| if field_idx >= new_idx { field_idx + 1 } else { field_idx };
+        self.doc.field_watches = std::mem::take(&mut self.doc.field_watches)
+            .into_iter()
+            .map(|(field_idx, history)| (remap(field_idx), history))
+            .collect();
+        self.edit_field_idx = self.edit_field_idx.map(remap);
+        self.doc.selected_fields.clear();
+        self.doc.selected_fields.insert(new_idx);
+        self.doc.last_selected_field = Some(new_idx);
     }
 
-    /// Show the "Add Field" dialog window
-    fn show_add_field_window(&mut self, ctx: &egui::Context) {
-        if !self.add_field_window_open {
+    /// Show the dialog moving the selected fields by a number of bytes
+    fn show_shift_fields_window(&mut self, ctx: &egui::Context) {
+        if !self.shift_window_open {
             return;
         }
 
-        let mut window_open = self.add_field_window_open;
-        egui::Window::new("Add Field")
+        let mut window_open = self.shift_window_open;
+        egui::Window::new("Shift Selected Fields")
             .open(&mut window_open)
             .resizable(false)
             .show(ctx, |ui| {
+                ui.label(format!("{} field(s) selected", self.doc.selected_fields.len()));
                 ui.horizontal(|ui| {
-                    ui.label("Name:");
-                    ui.text_edit_singleline(&mut self.new_field_name);
-                });
-
-                ui.horizontal(|ui| {
-                    ui.label("Offset:");
-                    ui.text_edit_singleline(&mut self.new_field_offset);
-                    ui.label("(hex or decimal)");
-                });
-
-                ui.horizontal(|ui| {
-                    ui.label("Type:");
-                    egui::ComboBox::from_id_salt("field_type")
-                        .selected_text(DataType::all()[self.new_field_type_idx].name())
-                        .show_ui(ui, |ui| {
-                            for (idx, dt) in DataType::all().iter().enumerate() {
-                                ui.selectable_value(&mut self.new_field_type_idx, idx, dt.name());
-                            }
-                        });
+                    ui.label("Shift by:");
+                    ui.add(egui::TextEdit::singleline(&mut self.shift_delta).hint_text("0x10 or -4"));
+                    ui.label("bytes");
                 });
 
-                ui.horizontal(|ui| {
-                    ui.label("Comment:");
-                    ui.text_edit_singleline(&mut self.new_field_comment);
-                });
+                if let Some(error) = &self.shift_error {
+                    ui.colored_label(egui::Color32::from_rgb(200, 80, 80), error);
+                }
 
                 ui.separator();
 
                 ui.horizontal(|ui| {
-                    if ui.button("Add").clicked() {
-                        if let Some(field) = self.create_field_from_input() {
-                            self.fields.push(field);
-                            self.reset_add_field_form();
-                            self.add_field_window_open = false;
+                    if ui.button("Shift").clicked() {
+                        match self.shift_selected_fields() {
+                            Ok(()) => self.shift_window_open = false,
+                            Err(e) => self.shift_error = Some(e),
                         }
                     }
 
                     if ui.button("Cancel").clicked() {
-                        self.reset_add_field_form();
-                        self.add_field_window_open = false;
+                        self.shift_window_open = false;
                     }
                 });
             });
 
-        self.add_field_window_open = window_open;
+        self.shift_window_open &= window_open;
     }
 
-    /// Create a field from the current input values
-    fn create_field_from_input(&self) -> Option<Field> {
-        if self.new_field_name.is_empty() {
-            return None;
+    /// Add the entered delta to the offsets of the selected fields, all or none of them
+    /// A field anchored to another selected field already moves with it, so it keeps its offset
+    fn shift_selected_fields(&mut self) -> Result<(), String> {
+        if self.doc.schema_locked {
+            return Err("The schema is locked".to_string());
+        }
+        let delta = parse_signed_offset(&self.shift_delta).ok_or("Shift is not a number")?;
+        if self.doc.selected_fields.is_empty() {
+            return Err("No fields selected".to_string());
         }
 
-        // Parse offset (support both hex with 0x prefix and decimal)
-        let offset = if let Some(hex_str) = self.new_field_offset.strip_prefix("0x") {
-            usize::from_str_radix(hex_str, 16).ok()?
-        } else {
-            self.new_field_offset.parse::<usize>().ok()?
-        };
-
-        let data_type = DataType::all()[self.new_field_type_idx];
-
-        let mut field = Field::new(self.new_field_name.clone(), offset, data_type);
-        field.comment = self.new_field_comment.clone();
-
-        Some(field)
-    }
+        let selected_names: HashSet<&str> = self
+            .doc
+            .selected_fields
+            .iter()
+            .filter_map(|&idx| self.doc.fields.get(idx))
+            .map(|f| f.name.as_str())
+            .filter(|name| !name.is_empty())
+            .collect();
+        let mut shifted = Vec::new();
+        for &idx in &self.doc.selected_fields {
+            let Some(field) = self.doc.fields.get(idx) else {
+                continue;
+            };
+            if field.anchor.as_deref().is_some_and(|anchor| selected_names.contains(anchor)) {
+                continue;
+            }
+            let offset = (field.offset as i64)
+                .checked_add(delta)
+                .filter(|&offset| offset >= 0)
+                .ok_or_else(|| format!("'{}' would start before its origin", field.display_name()))?;
+            shifted.push((idx, offset as usize));
+        }
 
-    /// Reset the add field form to default values
-    fn reset_add_field_form(&mut self) {
-        self.new_field_name.clear();
-        self.new_field_offset = String::from("0");
-        self.new_field_type_idx = 0;
-        self.new_field_comment.clear();
+        self.record_undo();
+        for (idx, offset) in shifted {
+            self.doc.fields[idx].offset = offset;
+        }
+        Ok(())
     }
 
     /// Start editing a field by populating the edit form
     fn start_edit_field(&mut self, idx: usize) {
-        if let Some(field) = self.fields.get(idx) {
+        if !self.check_schema_unlocked() {
+            return;
+        }
+        if let Some(field) = self.doc.fields.get(idx) {
             self.edit_field_idx = Some(idx);
             self.edit_field_name = field.name.clone();
+            self.edit_field_label = field.label.clone().unwrap_or_default();
             self.edit_field_offset = format!("0x{:X}", field.offset);
             self.edit_field_type_idx = DataType::all()
                 .iter()
-                .position(|&dt| dt == field.data_type)
+                .position(|dt| dt.same_kind(&field.data_type))
                 .unwrap_or(0);
+            if let DataType::Bits { bit_offset, bit_width, bit_order } = field.data_type {
+                self.edit_field_bit_offset = bit_offset;
+                self.edit_field_bit_width = bit_width;
+                self.edit_field_bit_order = bit_order;
+            }
+            if let DataType::SliceDescriptor { ptr_type, len_type } = field.data_type {
+                self.edit_field_slice_types = (ptr_type, len_type);
+            }
+            if let DataType::Checksum { algorithm, start, len } = field.data_type {
+                self.edit_field_checksum = (algorithm, start, len);
+            }
+            if let Some(len) = field.data_type.byte_len() {
+                self.edit_field_string_len = len;
+            }
+            self.edit_field_endianness = field.endianness;
             self.edit_field_comment = field.comment.clone();
+            self.edit_field_count = field.count;
+            self.edit_field_is_pointer = field.is_pointer;
+            self.edit_field_is_string_index = field.is_string_index;
+            self.edit_field_pointer_base = format_signed_offset(field.pointer_base);
+            self.edit_field_enum_labels = field.enum_labels.clone();
+            self.edit_field_color = field.color;
+            self.edit_field_anchor = field.anchor.clone();
+            self.edit_field_length_field = field.length_field.clone();
+            self.edit_field_struct = match field.data_type {
+                DataType::Struct { size } => Some((size, field.children.clone())),
+                _ => None,
+            };
             self.edit_field_window_open = true;
         }
     }
 
     /// Show the "Edit Field" dialog window
     fn show_edit_field_window(&mut self, ctx: &egui::Context) {
+        // Locking the schema closes the field dialogs
+        if self.doc.schema_locked {
+            self.edit_field_window_open = false;
+        }
         if !self.edit_field_window_open {
             return;
         }
@@ -247,7 +2359,17 @@ impl SchematicApp {
             .show(ctx, |ui| {
                 ui.horizontal(|ui| {
                     ui.label("Name:");
-                    ui.text_edit_singleline(&mut self.edit_field_name);
+                    let hint = if self.preferences.require_field_names { "" } else { "(derived from offset)" };
+                    ui.add(egui::TextEdit::singleline(&mut self.edit_field_name).hint_text(hint));
+                });
+
+                let name_problems = self.name_problems(&self.edit_field_name, self.edit_field_idx);
+                self.show_name_problems(ui, &name_problems);
+
+                ui.horizontal(|ui| {
+                    ui.label("Label:");
+                    ui.text_edit_singleline(&mut self.edit_field_label);
+                    ui.label("(optional, shown instead of the name)");
                 });
 
                 ui.horizontal(|ui| {
@@ -256,8 +2378,35 @@ impl SchematicApp {
                     ui.label("(hex or decimal)");
                 });
 
+                ui.horizontal(|ui| {
+                    ui.label("Relative to:");
+                    let selected = match &self.edit_field_anchor {
+                        Some(name) => format!("end of {}", name),
+                        None => "start of file".to_string(),
+                    };
+                    egui::ComboBox::from_id_salt("edit_anchor")
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.edit_field_anchor, None, "start of file");
+                            for (idx, field) in self.doc.fields.iter().enumerate() {
+                                if Some(idx) == self.edit_field_idx || field.name.is_empty() {
+                                    continue;
+                                }
+                                let anchor = Some(field.name.clone());
+                                ui.selectable_value(&mut self.edit_field_anchor, anchor, format!("end of {}", field.name));
+                            }
+                        });
+                    if let Some(offset) = self.edit_field_absolute_offset() {
+                        ui.label(format!("(at 0x{:X})", offset));
+                    }
+                });
+
                 ui.horizontal(|ui| {
                     ui.label("Type:");
+                    if let Some((size, children)) = &self.edit_field_struct {
+                        ui.label(format!("struct of {} members, {} bytes", children.len(), size));
+                        return;
+                    }
                     egui::ComboBox::from_id_salt("edit_field_type")
                         .selected_text(DataType::all()[self.edit_field_type_idx].name())
                         .show_ui(ui, |ui| {
@@ -267,18 +2416,129 @@ impl SchematicApp {
                         });
                 });
 
+                if DataType::all()[self.edit_field_type_idx].is_byte_string() {
+                    ui.horizontal(|ui| {
+                        ui.label("Length:");
+                        ui.add_enabled(
+                            self.edit_field_length_field.is_none(),
+                            egui::DragValue::new(&mut self.edit_field_string_len).range(1..=1_000_000),
+                        );
+                        ui.label("bytes");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Length from:");
+                        let selected = self.edit_field_length_field.as_deref().unwrap_or("fixed length").to_string();
+                        egui::ComboBox::from_id_salt("edit_length_field")
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.edit_field_length_field, None, "fixed length");
+                                for (idx, field) in self.doc.fields.iter().enumerate() {
+                                    let is_length = field.data_type.is_integer() && !field.is_array();
+                                    if Some(idx) == self.edit_field_idx || field.name.is_empty() || !is_length {
+                                        continue;
+                                    }
+                                    let name = Some(field.name.clone());
+                                    ui.selectable_value(&mut self.edit_field_length_field, name, &field.name);
+                                }
+                            });
+                    });
+                }
+
+                if let DataType::SliceDescriptor { .. } = DataType::all()[self.edit_field_type_idx] {
+                    show_slice_type_controls(ui, "edit_slice", &mut self.edit_field_slice_types);
+                }
+
+                if DataType::all()[self.edit_field_type_idx].is_checksum() {
+                    let selection = self.schema_selection();
+                    show_checksum_controls(ui, "edit_checksum", &mut self.edit_field_checksum, selection);
+                }
+
+                if let DataType::Bits { .. } = DataType::all()[self.edit_field_type_idx] {
+                    ui.horizontal(|ui| {
+                        ui.label("Bit offset:");
+                        ui.add(egui::DragValue::new(&mut self.edit_field_bit_offset).range(0..=63));
+                        ui.label("Width:");
+                        ui.add(egui::DragValue::new(&mut self.edit_field_bit_width).range(1..=64));
+                        egui::ComboBox::from_id_salt("edit_bit_order")
+                            .selected_text(self.edit_field_bit_order.name())
+                            .show_ui(ui, |ui| {
+                                for order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+                                    ui.selectable_value(&mut self.edit_field_bit_order, order, order.name());
+                                }
+                            });
+                    });
+                }
+
+                self.show_endianness_picker(ui);
+
+                ui.horizontal(|ui| {
+                    ui.label("Count:");
+                    ui.add(egui::DragValue::new(&mut self.edit_field_count).range(1..=1_000_000));
+                    ui.label("(1 for a single value)");
+                });
+
+                let is_integer = self.edit_field_data_type().is_integer();
+                ui.horizontal(|ui| {
+                    ui.add_enabled(
+                        is_integer,
+                        egui::Checkbox::new(&mut self.edit_field_is_pointer, "Pointer table"),
+                    )
+                    .on_disabled_hover_text("Only integer types can be pointers");
+                    if self.edit_field_is_pointer && is_integer {
+                        ui.label("Base:");
+                        ui.text_edit_singleline(&mut self.edit_field_pointer_base);
+                    }
+                });
+
+                ui.add_enabled(
+                    is_integer && !self.edit_field_is_pointer,
+                    egui::Checkbox::new(&mut self.edit_field_is_string_index, "String pool index"),
+                )
+                .on_disabled_hover_text("Only integer, non-pointer fields can index the string pool");
+
+                if is_integer && !self.edit_field_is_pointer && !self.edit_field_is_string_index {
+                    show_enum_label_editor(ui, &mut self.edit_field_enum_labels);
+                }
+
                 ui.horizontal(|ui| {
                     ui.label("Comment:");
                     ui.text_edit_singleline(&mut self.edit_field_comment);
                 });
 
+                ui.horizontal(|ui| {
+                    ui.label("Color:");
+                    let mut custom = self.edit_field_color.is_some();
+                    if ui.checkbox(&mut custom, "Custom").on_hover_text("Otherwise picked from the palette").changed() {
+                        let palette = self.palettes.active_colors();
+                        self.edit_field_color = custom.then(|| palette[self.edit_field_idx.unwrap_or(0) % palette.len()]);
+                    }
+                    if let Some(color) = &mut self.edit_field_color {
+                        ui.color_edit_button_srgb(color);
+                    }
+                });
+
+                if let Some(end) = self.edit_field_end()
+                    && self.doc.binary_data.is_loaded()
+                    && end > self.doc.binary_data.size()
+                {
+                    // Not an error: a schema may describe files larger than this one
+                    ui.colored_label(
+                        egui::Color32::from_rgb(220, 160, 60),
+                        format!(
+                            "⚠ Field exceeds file size (ends at {}, file is {} bytes)",
+                            self.view_settings.format_offset_prefixed(end, self.doc.binary_data.size()),
+                            self.doc.binary_data.size()
+                        ),
+                    );
+                }
+
                 ui.separator();
 
                 ui.horizontal(|ui| {
-                    if ui.button("Save").clicked() {
-                        if self.update_field_from_input() {
-                            self.edit_field_window_open = false;
-                        }
+                    if ui.add_enabled(self.names_acceptable(&name_problems), egui::Button::new("Save")).clicked()
+                        && self.update_field_from_input()
+                    {
+                        self.edit_field_window_open = false;
                     }
 
                     if ui.button("Cancel").clicked() {
@@ -290,9 +2550,65 @@ impl SchematicApp {
         self.edit_field_window_open = window_open;
     }
 
+    /// Show the edited field's value decoded both ways so the plausible byte order can be picked
+    fn show_endianness_picker(&mut self, ui: &mut egui::Ui) {
+        let data_type = self.edit_field_data_type();
+        if data_type.size() < 2 || data_type.is_struct() {
+            return;
+        }
+
+        let offset = self.edit_field_absolute_offset();
+        ui.horizontal(|ui| {
+            ui.label("Byte order:");
+            for endianness in [Endianness::Little, Endianness::Big] {
+                let value = offset
+                    .and_then(|offset| data_type.read_value(self.doc.binary_data.bytes(), offset, endianness))
+                    .unwrap_or_else(|| "N/A".to_string());
+                ui.selectable_value(
+                    &mut self.edit_field_endianness,
+                    endianness,
+                    format!("{}: {}", endianness.name(), value),
+                );
+            }
+        });
+    }
+
+    /// Get the file offset entered in the Edit Field dialog, following its anchor
+    fn edit_field_absolute_offset(&self) -> Option<usize> {
+        let offset = parse_offset(&self.edit_field_offset)?;
+        let Some(anchor) = &self.edit_field_anchor else {
+            return Some(offset);
+        };
+        let placed = schema::place_fields(&self.doc.fields, 0);
+        let target = placed.iter().find(|f| &f.name == anchor)?;
+        Some(target.offset.saturating_add(target.size()).saturating_add(offset))
+    }
+
+    /// Get the file offset just past the field being edited, once placed at the schema base
+    fn edit_field_end(&self) -> Option<usize> {
+        let size = self.edit_field_data_type().size() * self.edit_field_count.max(1);
+        Some(self.edit_field_absolute_offset()?.saturating_add(self.doc.schema_base).saturating_add(size))
+    }
+
+    /// Get the type chosen in the Edit Field dialog
+    fn edit_field_data_type(&self) -> DataType {
+        if let Some((size, _)) = self.edit_field_struct {
+            return DataType::Struct { size };
+        }
+        resolve_data_type(
+            DataType::all()[self.edit_field_type_idx],
+            self.edit_field_bit_offset,
+            self.edit_field_bit_width,
+            self.edit_field_bit_order,
+            self.edit_field_slice_types,
+            self.edit_field_checksum,
+            self.edit_field_string_len,
+        )
+    }
+
     /// Update the field being edited with the current input values
     fn update_field_from_input(&mut self) -> bool {
-        if self.edit_field_name.is_empty() {
+        if self.preferences.require_field_names && self.edit_field_name.is_empty() {
             return false;
         }
 
@@ -300,67 +2616,358 @@ impl SchematicApp {
             return false;
         };
 
-        // Parse offset (support both hex with 0x prefix and decimal)
-        let offset = if let Some(hex_str) = self.edit_field_offset.strip_prefix("0x") {
-            if let Ok(val) = usize::from_str_radix(hex_str, 16) {
-                val
-            } else {
-                return false;
-            }
-        } else {
-            if let Ok(val) = self.edit_field_offset.parse::<usize>() {
-                val
-            } else {
-                return false;
-            }
+        if !self.names_acceptable(&self.name_problems(&self.edit_field_name, Some(idx))) {
+            return false;
+        }
+
+        let Some(offset) = parse_offset(&self.edit_field_offset) else {
+            return false;
         };
 
-        let data_type = DataType::all()[self.edit_field_type_idx];
+        let data_type = self.edit_field_data_type();
 
         let mut field = Field::new(self.edit_field_name.clone(), offset, data_type);
+        if let Some((_, children)) = &self.edit_field_struct {
+            field.children = children.clone();
+        }
+        field.label = non_empty(&self.edit_field_label);
+        field.endianness = self.edit_field_endianness;
         field.comment = self.edit_field_comment.clone();
+        field.count = self.edit_field_count.max(1);
+        field.is_pointer = self.edit_field_is_pointer && data_type.is_integer();
+        field.is_string_index = self.edit_field_is_string_index && data_type.is_integer() && !field.is_pointer;
+        field.enum_labels = self.edit_field_enum_labels.clone();
+        field.color = self.edit_field_color;
+        field.anchor = self.edit_field_anchor.clone();
+        field.length_field = self.edit_field_length_field.clone().filter(|_| data_type.is_byte_string());
+        if field.is_pointer {
+            let Some(base) = parse_signed_offset(&self.edit_field_pointer_base) else {
+                return false;
+            };
+            field.pointer_base = base;
+        }
 
         // Update the field in the vector
-        if let Some(existing_field) = self.fields.get_mut(idx) {
-            *existing_field = field;
+        if idx < self.doc.fields.len() {
+            self.record_undo();
+            // Fields following this one, or taking their length from it, keep doing so under its new name
+            let old_name = std::mem::take(&mut self.doc.fields[idx].name);
+            if !old_name.is_empty() && old_name != field.name {
+                for other in &mut self.doc.fields {
+                    if other.anchor.as_ref() == Some(&old_name) {
+                        other.anchor = non_empty(&field.name);
+                    }
+                    if other.length_field.as_ref() == Some(&old_name) {
+                        other.length_field = non_empty(&field.name);
+                    }
+                }
+            }
+            self.doc.fields[idx] = field;
         }
 
         true
     }
 
+    /// Update the field selection after a click, honoring Ctrl (toggle) and Shift (range)
+    fn select_field(&mut self, idx: usize, modifiers: egui::Modifiers) {
+        if modifiers.ctrl {
+            // Ctrl+Click: Toggle field in selection
+            if self.doc.selected_fields.contains(&idx) {
+                self.doc.selected_fields.remove(&idx);
+            } else {
+                self.doc.selected_fields.insert(idx);
+            }
+            self.doc.last_selected_field = Some(idx);
+        } else if modifiers.shift {
+            // Shift+Click: Select range from last selected to clicked
+            if let Some(last) = self.doc.last_selected_field {
+                let start = last.min(idx);
+                let end = last.max(idx);
+                for i in start..=end {
+                    self.doc.selected_fields.insert(i);
+                }
+            } else {
+                self.doc.selected_fields.clear();
+                self.doc.selected_fields.insert(idx);
+            }
+            self.doc.last_selected_field = Some(idx);
+        } else {
+            // Normal click: Select only this field (clear others)
+            if self.doc.selected_fields.len() == 1 && self.doc.selected_fields.contains(&idx) {
+                // Toggle if already the only selected field
+                self.doc.selected_fields.clear();
+                self.doc.last_selected_field = None;
+            } else {
+                self.doc.selected_fields.clear();
+                self.doc.selected_fields.insert(idx);
+                self.doc.last_selected_field = Some(idx);
+            }
+        }
+    }
+
+    /// Start or stop watching a field's value
+    fn toggle_watch(&mut self, idx: usize) {
+        if self.doc.field_watches.remove(&idx).is_none() {
+            let mut history = ValueHistory::new();
+            let field = schema::place_fields_in(&self.doc.fields, self.doc.schema_base, self.doc.binary_data.bytes()).get(idx).cloned();
+            history.record(field.and_then(|f| f.read_value(self.doc.binary_data.bytes())));
+            self.doc.field_watches.insert(idx, history);
+        }
+    }
+
+    /// Record the current value of every watched field if the data changed since the last record
+    fn record_watches(&mut self) {
+        if self.doc.binary_data.generation() == self.doc.watched_generation {
+            return;
+        }
+        self.doc.watched_generation = self.doc.binary_data.generation();
+
+        let data = self.doc.binary_data.bytes();
+        let fields = schema::place_fields_in(&self.doc.fields, self.doc.schema_base, self.doc.binary_data.bytes());
+        for (&idx, history) in &mut self.doc.field_watches {
+            history.record(fields.get(idx).and_then(|f| f.read_value(data)));
+        }
+    }
+
+    /// Pass the bytes changed since loading to the hex view when the data changed
+    fn update_dirty_bytes(&mut self) {
+        if self.doc.binary_data.generation() == self.doc.dirty_generation {
+            return;
+        }
+        self.doc.dirty_generation = self.doc.binary_data.generation();
+        self.doc.hex_view.set_dirty_offsets(self.doc.binary_data.dirty_offsets());
+    }
+
+    /// Recompute the overlap and out-of-file warnings when the fields or the file size changed
+    fn update_schema_warnings(&mut self) {
+        let fields = schema::place_fields_in(&self.doc.fields, self.doc.schema_base, self.doc.binary_data.bytes());
+        // Names are part of the key since they appear in the warnings
+        let spans = fields.iter().map(|f| (f.bit_span(), f.display_name().into_owned())).collect();
+        let key = Some((spans, self.doc.binary_data.size()));
+        if key == self.doc.schema_warnings_key {
+            return;
+        }
+        self.doc.schema_warnings_key = key;
+
+        let mut warnings = schema::validate(&fields, self.doc.binary_data.size());
+        warnings.extend(schema::length_warnings(&self.doc.fields, self.doc.schema_base, self.doc.binary_data.bytes()));
+        self.doc.data_view.set_warnings(&fields, &warnings);
+    }
+
+    /// Recompute the highlighted occurrences of the selected field's bytes when it or the data changed
+    fn update_field_matches(&mut self) {
+        let selected = self
+            .primary_selected_field()
+            .filter(|_| self.view_settings.highlight_field_matches)
+            .and_then(|idx| {
+                let field = schema::place_fields_in(&self.doc.fields, self.doc.schema_base, self.doc.binary_data.bytes()).get(idx)?.clone();
+                Some((idx, field.offset, field.size()))
+            })
+            .filter(|&(_, offset, size)| size <= MAX_MATCH_NEEDLE && offset.saturating_add(size) <= self.doc.binary_data.size());
+
+        let key = selected.map(|(idx, offset, size)| (idx, offset, size, self.doc.binary_data.generation()));
+        if key == self.doc.field_matches_key {
+            return;
+        }
+        self.doc.field_matches_key = key;
+
+        match selected {
+            Some((idx, offset, size)) => {
+                let data = self.doc.binary_data.bytes();
+                let matches = analysis::find_all(data, &data[offset..offset + size], MAX_FIELD_MATCHES);
+                // The field's own bytes aren't an "other" occurrence
+                let others = matches.iter().filter(|&&m| m != offset).count();
+                self.doc.data_view.set_match_count(Some((idx, others)));
+                self.doc.hex_view.set_matches(matches, size);
+            }
+            None => {
+                self.doc.data_view.set_match_count(None);
+                self.doc.hex_view.set_matches(Vec::new(), 0);
+            }
+        }
+    }
+
+    /// Get the field shown in single-field widgets: the last clicked one if still selected,
+    /// otherwise the lowest selected index
+    fn primary_selected_field(&self) -> Option<usize> {
+        self.doc.last_selected_field
+            .filter(|idx| self.doc.selected_fields.contains(idx))
+            .or_else(|| self.doc.selected_fields.iter().min().copied())
+    }
+
+    /// Select a field from the outline and scroll both views to it
+    fn select_field_from_outline(&mut self, idx: usize, modifiers: egui::Modifiers) {
+        self.select_field(idx, modifiers);
+        if let Some(field) = schema::place_fields_in(&self.doc.fields, self.doc.schema_base, self.doc.binary_data.bytes()).get(idx) {
+            self.doc.hex_view.scroll_to_offset(field.offset);
+            self.doc.data_view.scroll_to_field(idx);
+        }
+    }
+
     /// Show file information panel
-    fn show_file_info(&self, ui: &mut egui::Ui) {
+    fn show_file_info(&mut self, ui: &mut egui::Ui) {
         ui.group(|ui| {
             ui.horizontal(|ui| {
                 ui.label("File:");
-                if let Some(path) = self.binary_data.file_path() {
+                if let Some(path) = self.doc.binary_data.file_path() {
                     ui.label(path.display().to_string());
+                } else if self.doc.binary_data.is_loaded() {
+                    ui.label("<stdin>");
                 } else {
                     ui.label("No file loaded");
                 }
+                if self.doc.binary_data.is_modified() {
+                    ui.colored_label(egui::Color32::from_rgb(220, 160, 60), "● modified");
+                }
             });
 
-            if self.binary_data.is_loaded() {
+            if self.doc.binary_data.is_loaded() {
                 ui.horizontal(|ui| {
                     ui.label("Size:");
-                    ui.label(format!("{} bytes", self.binary_data.size()));
+                    ui.label(format!("{} bytes", self.doc.binary_data.size()));
+
+                    if let Some(warning) = self.size_mismatch_warning() {
+                        ui.colored_label(egui::Color32::from_rgb(220, 160, 60), format!("⚠ {}", warning));
+                    }
+
+                    ui.separator();
+                    ui.label("Schema base:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.doc.schema_base)
+                            .range(0..=self.doc.binary_data.size())
+                            .hexadecimal(1, false, true)
+                            .prefix("0x"),
+                    )
+                    .on_hover_text("Offset added to every field, to slide the schema over the file");
+                    let out_of_bounds = self.out_of_bounds_fields();
+                    if self.doc.schema_base > 0 && out_of_bounds > 0 {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(220, 160, 60),
+                            format!("⚠ {} field(s) past the end at this base", out_of_bounds),
+                        );
+                    }
                 });
             }
         });
     }
 
+    /// Count the offset columns from the hex cursor, or go back to absolute offsets if they already are
+    fn set_relative_base_at_cursor(&mut self) {
+        let cursor = self.doc.hex_view.cursor_offset();
+        self.doc.relative_base = if cursor == self.doc.relative_base { None } else { cursor };
+    }
+
+    /// Render the status line: cursor offset, selection length, position in the file and field count
+    fn show_status_bar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let size = self.doc.binary_data.size();
+            match self.doc.hex_view.cursor_offset() {
+                Some(cursor) if self.doc.binary_data.is_loaded() => {
+                    ui.label(format!(
+                        "Offset: {} ({})",
+                        self.view_settings.format_offset_prefixed(cursor, size),
+                        cursor
+                    ));
+                    if let Some(base) = self.doc.relative_base {
+                        ui.label(format!("Relative: {}", self.view_settings.format_row_offset_prefixed(cursor, Some(base), size)))
+                            .on_hover_text(format!(
+                                "Offset from the base at {}",
+                                self.view_settings.format_offset_prefixed(base, size)
+                            ));
+                    }
+                    ui.separator();
+                    ui.label(format!("{:.1}%", (cursor + 1) as f64 / size as f64 * 100.0))
+                        .on_hover_text("Position of the cursor in the file");
+                }
+                _ => {
+                    ui.label("Offset: -");
+                }
+            }
+
+            if let Some(selection) = self.doc.hex_view.selection() {
+                ui.separator();
+                ui.label(format!(
+                    "Selected: {} byte(s), {}..{}",
+                    selection.len(),
+                    self.view_settings.format_offset_prefixed(selection.start, size),
+                    self.view_settings.format_offset_prefixed(selection.end, size)
+                ));
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let errors = self.notifications.unacknowledged_errors();
+                if errors > 0 {
+                    let text = egui::RichText::new(format!("⛔ {} error(s)", errors)).color(ui.visuals().error_fg_color);
+                    if ui.link(text).on_hover_text("Show the message log").clicked() {
+                        self.log_window_open = true;
+                    }
+                    ui.separator();
+                }
+                ui.label(format!("{} field(s)", self.doc.fields.len()));
+                let out_of_bounds = if self.doc.binary_data.is_loaded() { self.out_of_bounds_fields() } else { 0 };
+                if out_of_bounds > 0 {
+                    ui.separator();
+                    ui.colored_label(
+                        egui::Color32::from_rgb(220, 160, 60),
+                        format!("⚠ {} out of bounds", out_of_bounds),
+                    )
+                    .on_hover_text("Fields extending past the end of the file");
+                }
+            });
+        });
+    }
+
+    /// Count the fields that don't fit in the file once shifted by the schema base
+    fn out_of_bounds_fields(&self) -> usize {
+        self.out_of_bounds_field_indices().len()
+    }
+
+    /// Indices of the fields that don't fit in the file once shifted by the schema base
+    fn out_of_bounds_field_indices(&self) -> Vec<usize> {
+        let size = self.doc.binary_data.size();
+        schema::place_fields_in(&self.doc.fields, self.doc.schema_base, self.doc.binary_data.bytes())
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.offset.saturating_add(f.size()) > size)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Describe a mismatch between the file size and the size the schema expects
+    /// Without an explicit size, only files too short for the fields are reported
+    fn size_mismatch_warning(&self) -> Option<String> {
+        let actual = self.doc.binary_data.size();
+        let expected = match self.doc.schema_expected_size {
+            Some(expected) if expected != actual => expected,
+            None if !self.doc.fields.is_empty() && schema::fields_end(&self.doc.fields) > actual => {
+                schema::fields_end(&self.doc.fields)
+            }
+            _ => return None,
+        };
+
+        Some(format!("expected {} bytes, got {}", expected, actual))
+    }
+
     /// Save the current schema to a TOML file
     fn save_schema(&mut self) {
-        if self.fields.is_empty() {
-            eprintln!("No fields to save");
+        self.save_schema_dialog("schema.toml");
+    }
+
+    /// Save the current schema to a file chosen in a dialog, its extension picking TOML or JSON
+    fn save_schema_dialog(&mut self, default_name: &str) {
+        if self.doc.fields.is_empty() {
+            self.notifications.warning("No fields to save");
             return;
         }
 
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("TOML Schema", &["toml"])
-            .set_file_name("schema.toml")
-            .save_file()
-        {
+        let json_first = default_name.ends_with(".json");
+        let dialog = rfd::FileDialog::new();
+        let dialog = if json_first {
+            dialog.add_filter("JSON Schema", &["json"]).add_filter("TOML Schema", &["toml"])
+        } else {
+            dialog.add_filter("TOML Schema", &["toml"]).add_filter("JSON Schema", &["json"])
+        };
+        if let Some(path) = dialog.set_file_name(default_name).save_file() {
             self.save_schema_to_path(path);
         }
     }
@@ -368,48 +2975,50 @@ impl SchematicApp {
     /// Save schema to a specific path
     fn save_schema_to_path(&mut self, path: PathBuf) {
         let schema = Schema {
-            fields: self.fields.clone(),
+            fields: self.doc.fields.clone(),
+            expected_size: self.doc.schema_expected_size,
+            string_pool: self.doc.string_pool,
         };
 
-        match toml::to_string_pretty(&schema) {
-            Ok(toml_string) => {
-                if let Err(e) = fs::write(&path, toml_string) {
-                    eprintln!("Error saving schema: {}", e);
+        let duplicates = schema.duplicate_names();
+        if !duplicates.is_empty() {
+            self.notifications
+                .warning(format!("Duplicate field names: {}", duplicates.join(", ")));
+        }
+
+        for error in schema.check_references() {
+            self.notifications.warning(format!("Dangling reference in {}", error));
+        }
+
+        match schema.serialize(SchemaFormat::from_path(&path)) {
+            Ok(text) => {
+                if let Err(e) = fs::write(&path, text) {
+                    self.notifications.error(format!("Error saving schema to {}: {}", path.display(), e));
                 } else {
-                    println!("Schema saved to: {:?}", path);
-                    self.schema_file_path = Some(path);
+                    self.notifications.info(format!("Schema saved to: {}", path.display()));
+                    self.doc.schema_file_path = Some(path);
+                    self.record_schema_association();
                 }
             }
             Err(e) => {
-                eprintln!("Error serializing schema: {}", e);
+                self.notifications.error(format!("Error serializing schema: {}", e));
             }
         }
     }
 
     /// Save schema with save-as dialog (always prompt for location)
     fn save_schema_as(&mut self) {
-        if self.fields.is_empty() {
-            eprintln!("No fields to save");
-            return;
-        }
-
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("TOML Schema", &["toml"])
-            .set_file_name("schema.toml")
-            .save_file()
-        {
-            self.save_schema_to_path(path);
-        }
+        self.save_schema_dialog("schema.toml");
     }
 
     /// Save schema (save-as if new, overwrite if existing)
     fn save_schema_smart(&mut self) {
-        if self.fields.is_empty() {
-            eprintln!("No fields to save");
+        if self.doc.fields.is_empty() {
+            self.notifications.warning("No fields to save");
             return;
         }
 
-        if let Some(path) = self.schema_file_path.clone() {
+        if let Some(path) = self.doc.schema_file_path.clone() {
             // Overwrite existing file
             self.save_schema_to_path(path);
         } else {
@@ -418,34 +3027,456 @@ impl SchematicApp {
         }
     }
 
-    /// Load a schema from a TOML file
+    /// Load a schema from a TOML or JSON file
     fn load_schema(&mut self) {
         if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Schema", &["toml", "json"])
             .add_filter("TOML Schema", &["toml"])
+            .add_filter("JSON Schema", &["json"])
             .pick_file()
         {
-            match fs::read_to_string(&path) {
-                Ok(toml_string) => match toml::from_str::<Schema>(&toml_string) {
-                    Ok(schema) => {
-                        self.fields = schema.fields;
-                        self.schema_file_path = Some(path.clone());
-                        println!("Schema loaded from: {:?}", path);
-                    }
-                    Err(e) => {
-                        eprintln!("Error parsing schema: {}", e);
+            self.load_schema_from_path(path);
+        }
+    }
+
+    /// Add the fields placed by an ImHex pattern file
+    fn import_imhex_pattern(&mut self) {
+        if !self.check_schema_unlocked() {
+            return;
+        }
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("ImHex Pattern", &["hexpat", "pat"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(source) => match parse_imhex_pattern(&source) {
+                Ok(fields) => {
+                    self.notifications.info(format!("Imported {} fields from: {}", fields.len(), path.display()));
+                    self.record_undo();
+                    self.doc.fields.extend(fields);
+                }
+                Err(e) => {
+                    self.notifications.error(format!("Error importing pattern: {}", e));
+                }
+            },
+            Err(e) => {
+                self.notifications.error(format!("Error reading pattern file: {}", e));
+            }
+        }
+    }
+
+    /// Add the members of the last struct defined in a C header, starting at the hex cursor
+    fn import_c_struct(&mut self) {
+        if !self.check_schema_unlocked() {
+            return;
+        }
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("C Header", &["h", "hpp", "c"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(source) => match parse_c_struct(&source, self.preferences.default_endianness) {
+                Ok(mut fields) => {
+                    // Field offsets are relative to the schema base
+                    let base = self.doc.hex_view.cursor_offset().map_or(0, |c| c.saturating_sub(self.doc.schema_base));
+                    for field in &mut fields {
+                        field.offset += base;
                     }
-                },
+                    self.notifications.info(format!("Imported {} fields at 0x{:X} from: {}", fields.len(), base, path.display()));
+                    self.record_undo();
+                    self.doc.fields.extend(fields);
+                }
+                Err(e) => {
+                    self.notifications.error(format!("Error importing C struct: {}", e));
+                }
+            },
+            Err(e) => {
+                self.notifications.error(format!("Error reading header file: {}", e));
+            }
+        }
+    }
+
+    /// Replace the fields with those of `schema`, keeping the hex view where it is
+    fn apply_schema(&mut self, schema: Schema) {
+        if self.doc.binary_data.is_loaded() && self.doc.binary_data.size() < schema.expected_size() {
+            self.notifications.warning(format!(
+                "Schema expects {} bytes, but the file has {}",
+                schema.expected_size(),
+                self.doc.binary_data.size()
+            ));
+        }
+        let top_offset = self.doc.hex_view.top_offset();
+        self.record_undo();
+        self.doc.schema_expected_size = schema.expected_size;
+        self.doc.string_pool = schema.string_pool;
+        self.doc.fields = schema.fields;
+        self.doc.hex_view.restore_top_offset(top_offset);
+        self.doc.selected_fields.clear();
+        self.doc.last_selected_field = None;
+        self.doc.field_watches.clear();
+    }
+
+    /// Start a new, unsaved schema from a built-in template
+    fn new_schema_from_template(&mut self, template: &Template) {
+        if !self.check_schema_unlocked() {
+            return;
+        }
+        match template.schema() {
+            Ok(schema) => {
+                self.apply_schema(schema);
+                self.doc.schema_file_path = None;
+                self.notifications.info(format!("Schema created from the {} template", template.name));
+            }
+            Err(e) => {
+                self.notifications.error(format!("Error parsing template {}: {}", template.name, e));
+            }
+        }
+    }
+
+    /// Load a schema from a specific path
+    fn load_schema_from_path(&mut self, path: PathBuf) {
+        match fs::read_to_string(&path) {
+            Ok(text) => match Schema::parse(&text, SchemaFormat::from_path(&path)) {
+                Ok(schema) => {
+                    self.apply_schema(schema);
+                    self.doc.schema_file_path = Some(path.clone());
+                    self.record_schema_association();
+                    self.recent.add_schema(&path);
+                    self.notifications.info(format!("Schema loaded from: {}", path.display()));
+                }
                 Err(e) => {
-                    eprintln!("Error reading schema file: {}", e);
+                    self.notifications.error(format!("Error parsing schema {}: {}", path.display(), e));
                 }
+            },
+            Err(e) => {
+                self.notifications.error(format!("Error reading schema file {}: {}", path.display(), e));
+            }
+        }
+    }
+
+    /// Export the fields as a C or Rust struct definition
+    fn export_struct(&mut self, language: StructLanguage) {
+        if self.doc.fields.is_empty() {
+            self.notifications.warning("No fields to export");
+            return;
+        }
+
+        let struct_name = self
+            .doc
+            .schema_file_path
+            .as_ref()
+            .and_then(|path| path.file_stem())
+            .map(|stem| export::sanitize_identifier(&stem.to_string_lossy()))
+            .unwrap_or_else(|| String::from("Schema"));
+        // The struct layout is computed from absolute offsets
+        let fields = schema::place_fields(&self.doc.fields, 0);
+
+        let (filter_name, extension, export) = match language {
+            StructLanguage::C => ("C Header", "h", export_c_struct(&fields, &struct_name)),
+            StructLanguage::Rust => ("Rust Source", "rs", export_rust_struct(&fields, &struct_name)),
+            StructLanguage::Kaitai => ("Kaitai Struct", "ksy", export_kaitai(&fields, &struct_name)),
+        };
+
+        for warning in &export.warnings {
+            self.notifications.warning(format!("Struct export: {}", warning));
+        }
+
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter(filter_name, &[extension])
+            .set_file_name(format!("{}.{}", struct_name, extension))
+            .save_file()
+        {
+            if let Err(e) = fs::write(&path, export.source) {
+                self.notifications.error(format!("Error exporting struct: {}", e));
+            } else {
+                self.notifications.info(format!("Struct exported to: {}", path.display()));
             }
         }
     }
+
+    /// Export the decoded field values to CSV or JSON, chosen by the file extension
+    fn export_values(&mut self) {
+        if self.doc.fields.is_empty() {
+            self.notifications.warning("No fields to export");
+            return;
+        }
+
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .add_filter("JSON", &["json"])
+            .set_file_name("values.csv")
+            .save_file()
+        else {
+            return;
+        };
+
+        let fields = schema::place_fields_in(&self.doc.fields, self.doc.schema_base, self.doc.binary_data.bytes());
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let output = if is_json {
+            export::export_values_json(&fields, self.doc.binary_data.bytes())
+        } else {
+            export::export_values_csv(&fields, self.doc.binary_data.bytes(), self.view_settings.lowercase_hex)
+        };
+
+        if let Err(e) = fs::write(&path, output) {
+            self.notifications.error(format!("Error exporting values: {}", e));
+        } else {
+            self.notifications.info(format!("Values exported to: {}", path.display()));
+        }
+    }
+
+    /// Show the prompt offering to load the schema associated with the opened binary
+    fn show_schema_offer_window(&mut self, ctx: &egui::Context) {
+        let Some(schema_path) = self.doc.pending_schema_offer.clone() else {
+            return;
+        };
+
+        let mut window_open = true;
+        egui::Window::new("Load Associated Schema")
+            .open(&mut window_open)
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label("This file was last used with the schema:");
+                ui.monospace(schema_path.display().to_string());
+
+                if !self.doc.fields.is_empty() {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(220, 160, 60),
+                        format!("Loading it will replace the {} current field(s).", self.doc.fields.len()),
+                    );
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("Load").clicked() {
+                        self.load_schema_from_path(schema_path.clone());
+                        self.doc.pending_schema_offer = None;
+                    }
+
+                    if ui.button("Dismiss").clicked() {
+                        self.doc.pending_schema_offer = None;
+                    }
+                });
+            });
+
+        if !window_open {
+            self.doc.pending_schema_offer = None;
+        }
+    }
+}
+
+/// Fill in the parameters of a type chosen in a field dialog
+fn resolve_data_type(
+    kind: DataType,
+    bit_offset: usize,
+    bit_width: usize,
+    bit_order: BitOrder,
+    slice_types: (IntType, IntType),
+    checksum: (ChecksumAlgorithm, usize, usize),
+    string_len: usize,
+) -> DataType {
+    let string_len = string_len.max(1);
+    match kind {
+        DataType::FixedString { .. } => DataType::FixedString { len: string_len },
+        DataType::CString { .. } => DataType::CString { max_len: string_len },
+        DataType::Bytes { .. } => DataType::Bytes { len: string_len },
+        DataType::SliceDescriptor { .. } => DataType::SliceDescriptor {
+            ptr_type: slice_types.0,
+            len_type: slice_types.1,
+        },
+        DataType::Bits { .. } => DataType::Bits {
+            bit_offset,
+            bit_width: bit_width.clamp(1, 64),
+            bit_order,
+        },
+        DataType::Checksum { .. } => DataType::Checksum {
+            algorithm: checksum.0,
+            start: checksum.1,
+            len: checksum.2,
+        },
+        data_type => data_type,
+    }
+}
+
+/// Show pickers for the offset and length types of a slice descriptor
+fn show_slice_type_controls(ui: &mut egui::Ui, id: &str, slice_types: &mut (IntType, IntType)) {
+    ui.horizontal(|ui| {
+        ui.label("Offset type:");
+        egui::ComboBox::from_id_salt((id, "ptr"))
+            .selected_text(slice_types.0.name())
+            .show_ui(ui, |ui| {
+                for &int_type in IntType::all() {
+                    ui.selectable_value(&mut slice_types.0, int_type, int_type.name());
+                }
+            });
+        ui.label("Length type:");
+        egui::ComboBox::from_id_salt((id, "len"))
+            .selected_text(slice_types.1.name())
+            .show_ui(ui, |ui| {
+                for &int_type in IntType::all() {
+                    ui.selectable_value(&mut slice_types.1, int_type, int_type.name());
+                }
+            });
+    });
+}
+
+/// Show the algorithm and covered region of a checksum, which can be taken from `selection`
+fn show_checksum_controls(
+    ui: &mut egui::Ui,
+    id: &str,
+    checksum: &mut (ChecksumAlgorithm, usize, usize),
+    selection: Option<Range<usize>>,
+) {
+    ui.horizontal(|ui| {
+        ui.label("Algorithm:");
+        egui::ComboBox::from_id_salt((id, "algorithm"))
+            .selected_text(checksum.0.name())
+            .show_ui(ui, |ui| {
+                for &algorithm in ChecksumAlgorithm::all() {
+                    ui.selectable_value(&mut checksum.0, algorithm, algorithm.name());
+                }
+            });
+    });
+    ui.horizontal(|ui| {
+        ui.label("Covers:");
+        ui.add(egui::DragValue::new(&mut checksum.1).hexadecimal(1, false, true).prefix("0x"));
+        ui.label("length");
+        ui.add(egui::DragValue::new(&mut checksum.2));
+        if ui
+            .add_enabled(selection.is_some(), egui::Button::new("Use Selection"))
+            .on_disabled_hover_text("Select the covered bytes in the hex view")
+            .clicked()
+            && let Some(selection) = selection
+        {
+            checksum.1 = selection.start;
+            checksum.2 = selection.len();
+        }
+    });
+}
+
+/// Set the byte order of every field, struct members included
+fn set_endianness(fields: &mut [Field], endianness: Endianness) {
+    for field in fields {
+        field.endianness = endianness;
+        set_endianness(&mut field.children, endianness);
+    }
+}
+
+/// Convert a text input to `None` if it's blank
+fn non_empty(input: &str) -> Option<String> {
+    let input = input.trim();
+    (!input.is_empty()).then(|| input.to_string())
+}
+
+/// Take the first unused `prefix_N` name with N >= `next`, marking it used
+fn take_auto_name(prefix: &str, next: &mut usize, used: &mut HashSet<String>) -> String {
+    loop {
+        let name = format!("{}_{}", prefix, next);
+        *next += 1;
+        if used.insert(name.clone()) {
+            return name;
+        }
+    }
+}
+
+/// Show the editor for an integer field's value labels
+fn show_enum_label_editor(ui: &mut egui::Ui, labels: &mut Vec<EnumLabel>) {
+    egui::CollapsingHeader::new(format!("Value Labels ({})", labels.len()))
+        .id_salt("enum_labels")
+        .show(ui, |ui| {
+            let mut removed = None;
+            for (idx, entry) in labels.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut entry.value));
+                    ui.label("=");
+                    ui.add(egui::TextEdit::singleline(&mut entry.label).desired_width(140.0));
+                    if ui.button("✖").on_hover_text("Remove").clicked() {
+                        removed = Some(idx);
+                    }
+                });
+            }
+            if let Some(idx) = removed {
+                labels.remove(idx);
+            }
+            if ui.button("Add Label").clicked() {
+                let value = labels.iter().map(|entry| entry.value).max().map_or(0, |max| max.saturating_add(1));
+                labels.push(EnumLabel { value, label: String::new() });
+            }
+            ui.label(egui::RichText::new("Values without a label are shown as (unknown)").small().weak());
+        });
+}
+
+/// Split a trailing `_N` off a name, returning the prefix and N + 1 (or 1 if there's no number)
+fn split_name_number(name: &str) -> (&str, usize) {
+    if let Some((prefix, number)) = name.rsplit_once('_')
+        && let Ok(number) = number.parse::<usize>()
+    {
+        return (prefix, number + 1);
+    }
+    (name, 1)
+}
+
+/// Parse an offset entered by the user (hex with `0x` prefix, or decimal)
+fn parse_offset(input: &str) -> Option<usize> {
+    let input = input.trim();
+    if let Some(hex_str) = input.strip_prefix("0x") {
+        usize::from_str_radix(hex_str, 16).ok()
+    } else {
+        input.parse::<usize>().ok()
+    }
+}
+
+/// Parse a signed offset entered by the user (e.g. `-0x400000` or `16`)
+fn parse_signed_offset(input: &str) -> Option<i64> {
+    let input = input.trim();
+    let (negative, magnitude) = match input.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, input),
+    };
+    let value = i64::try_from(parse_offset(magnitude)?).ok()?;
+    Some(if negative { -value } else { value })
+}
+
+/// Format a signed offset the way `parse_signed_offset` accepts it
+fn format_signed_offset(value: i64) -> String {
+    if value < 0 {
+        format!("-0x{:X}", value.unsigned_abs())
+    } else {
+        format!("0x{:X}", value)
+    }
 }
 
 impl eframe::App for SchematicApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.schema_associations.save(storage);
+        self.view_settings.save(storage);
+        self.palettes.save(storage);
+        self.preferences.save(storage);
+        self.outline_view.save(storage);
+        AppState {
+            last_file: self.doc.binary_data.file_path().cloned(),
+            last_schema: self.doc.schema_file_path.clone(),
+            view_focus: self.view_focus,
+            theme: self.theme,
+            recent: self.recent.clone(),
+            data_view_columns: self.doc.data_view.column_widths().to_vec(),
+        }
+        .save(storage);
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Handle keyboard shortcuts
+        let typing = ctx.wants_keyboard_input();
         ctx.input(|i| {
             // Focus switching
             if i.key_pressed(egui::Key::Num1) && i.modifiers.ctrl {
@@ -454,6 +3485,30 @@ impl eframe::App for SchematicApp {
             if i.key_pressed(egui::Key::Num2) && i.modifiers.ctrl {
                 self.view_focus = ViewFocus::DataView;
             }
+            if i.key_pressed(egui::Key::Num3) && i.modifiers.ctrl {
+                self.view_focus = ViewFocus::Outline;
+            }
+
+            // Ctrl+T: New tab, Ctrl+W: Close tab, Ctrl+Tab: Next tab
+            if i.key_pressed(egui::Key::T) && i.modifiers.ctrl {
+                self.new_document();
+            }
+            if i.key_pressed(egui::Key::W) && i.modifiers.ctrl {
+                self.request_close_document(self.active_document);
+            }
+            if i.key_pressed(egui::Key::Tab) && i.modifiers.ctrl {
+                self.switch_document((self.active_document + 1) % self.document_count());
+            }
+
+            // Ctrl+B: Count the offset columns from the cursor
+            if i.key_pressed(egui::Key::B) && i.modifiers.ctrl && !typing {
+                self.set_relative_base_at_cursor();
+            }
+
+            // F5: Reload the file from disk
+            if i.key_pressed(egui::Key::F5) {
+                self.reload_file();
+            }
 
             // Ctrl+Q: Quit
             if i.key_pressed(egui::Key::Q) && i.modifiers.ctrl {
@@ -464,56 +3519,227 @@ impl eframe::App for SchematicApp {
             if i.key_pressed(egui::Key::O) && i.modifiers.ctrl {
                 match self.view_focus {
                     ViewFocus::HexView => self.open_file(),
-                    ViewFocus::DataView => self.load_schema(),
+                    ViewFocus::DataView | ViewFocus::Outline => self.load_schema(),
                 }
             }
 
             // Ctrl+S: Save schema (smart save)
-            if i.key_pressed(egui::Key::S) && i.modifiers.ctrl && !i.modifiers.shift {
-                if self.view_focus == ViewFocus::DataView {
-                    self.save_schema_smart();
-                }
+            if i.key_pressed(egui::Key::S)
+                && i.modifiers.ctrl
+                && !i.modifiers.shift
+                && self.view_focus == ViewFocus::DataView
+            {
+                self.save_schema_smart();
             }
 
             // Ctrl+Shift+S: Save schema as (always prompt)
-            if i.key_pressed(egui::Key::S) && i.modifiers.ctrl && i.modifiers.shift {
-                if self.view_focus == ViewFocus::DataView {
-                    self.save_schema_as();
-                }
+            if i.key_pressed(egui::Key::S)
+                && i.modifiers.ctrl
+                && i.modifiers.shift
+                && self.view_focus == ViewFocus::DataView
+            {
+                self.save_schema_as();
+            }
+
+            // Ctrl+N: Add new field, at the hex cursor if there is one
+            if i.key_pressed(egui::Key::N)
+                && i.modifiers.ctrl
+                && matches!(self.view_focus, ViewFocus::DataView | ViewFocus::HexView)
+            {
+                self.open_add_field();
+            }
+
+            // Ctrl+G: Go to offset
+            if i.key_pressed(egui::Key::G) && i.modifiers.ctrl && self.doc.binary_data.is_loaded() {
+                self.goto_error = None;
+                self.goto_window_open = true;
+            }
+
+            // Ctrl+F: Find bytes in the hex view
+            if i.key_pressed(egui::Key::F) && i.modifiers.ctrl {
+                self.view_focus = ViewFocus::HexView;
+                self.doc.search_bar.focus();
             }
 
-            // Ctrl+N: Add new field
-            if i.key_pressed(egui::Key::N) && i.modifiers.ctrl {
-                if self.view_focus == ViewFocus::DataView {
-                    self.add_field_window_open = true;
+            // Ctrl+Z: Undo, Ctrl+Shift+Z: Redo (text boxes handle their own undo)
+            if i.key_pressed(egui::Key::Z) && i.modifiers.command && !typing {
+                if i.modifiers.shift {
+                    self.redo();
+                } else {
+                    self.undo();
                 }
             }
+
+            // `.`: Repeat the last add at the next offset
+            if i.key_pressed(egui::Key::Period)
+                && i.modifiers.is_none()
+                && !typing
+                && matches!(self.view_focus, ViewFocus::DataView | ViewFocus::HexView)
+            {
+                self.repeat_last_add();
+            }
         });
 
+        self.handle_close_request(ctx);
+
+        self.record_watches();
+        self.update_dirty_bytes();
+        if let Some(comparison) = &mut self.doc.comparison {
+            comparison.update_diff(&self.doc.binary_data, &mut self.doc.hex_view);
+        }
+        self.update_field_matches();
+        self.update_schema_warnings();
+
         // Menu bar
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
             self.show_menu(ui);
         });
 
+        // Tab strip, once more than one file is open
+        if !self.documents.is_empty() {
+            egui::TopBottomPanel::top("tabs").show(ctx, |ui| {
+                self.show_tabs(ui);
+            });
+        }
+
         // File info panel
         egui::TopBottomPanel::top("file_info").show(ctx, |ui| {
             self.show_file_info(ui);
         });
 
+        // Status bar, added before the side and bottom panels so it spans the whole window
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            self.show_status_bar(ui);
+        });
+
         // Show add field window if open
         self.show_add_field_window(ctx);
 
         // Show edit field window if open
         self.show_edit_field_window(ctx);
 
+        // Show define from text window if open
+        self.show_define_text_window(ctx);
+
+        // Show fill selection window if open
+        self.show_fill_selection_window(ctx);
+        self.show_shift_fields_window(ctx);
+
+        // Binary saving and quitting confirmations, and file errors
+        self.show_save_binary_confirm_window(ctx);
+        self.show_quit_confirm_window(ctx);
+        self.show_close_tab_confirm_window(ctx);
+        self.show_io_error_window(ctx);
+        self.show_goto_window(ctx);
+        self.show_detect_endianness_window(ctx);
+
+        // Show analysis window if open
+        egui::Window::new("Analysis")
+            .open(&mut self.analysis_window_open)
+            .default_width(400.0)
+            .show(ctx, |ui| {
+                if let Some(offset) = self.analysis_view.show(ui, &self.doc.binary_data, self.doc.hex_view.selection()) {
+                    let now = ui.input(|i| i.time);
+                    self.doc.hex_view.go_to_offset(offset, now);
+                    self.view_focus = ViewFocus::HexView;
+                }
+            });
+
+        // Show strings window if open
+        let mut strings_window_open = self.strings_window_open;
+        egui::Window::new("Strings")
+            .open(&mut strings_window_open)
+            .default_width(400.0)
+            .default_height(400.0)
+            .show(ctx, |ui| match self.strings_view.show(ui, &self.doc.binary_data) {
+                Some(StringsAction::GoTo(offset)) => {
+                    let now = ui.input(|i| i.time);
+                    self.doc.hex_view.go_to_offset(offset, now);
+                    self.view_focus = ViewFocus::HexView;
+                }
+                Some(StringsAction::AddField { offset, len }) => self.add_string_field(offset, len),
+                None => {}
+            });
+        self.strings_window_open = strings_window_open;
+
+        // Show the message log if open, and the toasts over everything
+        egui::Window::new("Message Log")
+            .open(&mut self.log_window_open)
+            .default_width(480.0)
+            .default_height(300.0)
+            .show(ctx, |ui| self.notifications.show_log(ui));
+        self.notifications.show_toasts(ctx);
+
+        // Show palette editor if open
+        egui::Window::new("Field Colors")
+            .open(&mut self.palette_window_open)
+            .default_width(320.0)
+            .show(ctx, |ui| {
+                self.palettes.show_editor(ui);
+            });
+        self.doc.hex_view.set_palette(self.palettes.active_colors());
+
+        // Offer the associated schema after opening a known binary
+        self.show_schema_offer_window(ctx);
+
+        // Outline panel on the left
+        egui::SidePanel::left("outline_panel")
+            .resizable(true)
+            .default_width(220.0)
+            .show(ctx, |ui| {
+                let outline_focused = self.view_focus == ViewFocus::Outline;
+                ui.horizontal(|ui| {
+                    ui.heading("Outline");
+                    if outline_focused {
+                        ui.label(egui::RichText::new("●").color(egui::Color32::from_rgb(100, 150, 255)));
+                    }
+                });
+                ui.separator();
+                let fields = schema::place_fields_in(&self.doc.fields, self.doc.schema_base, self.doc.binary_data.bytes());
+                if let Some(idx) = self.outline_view.show(
+                    ui,
+                    &fields,
+                    self.doc.binary_data.size(),
+                    &self.doc.selected_fields,
+                    &self.view_settings,
+                ) {
+                    let modifiers = ui.input(|i| i.modifiers);
+                    self.select_field_from_outline(idx, modifiers);
+                }
+            });
+
+        // Data inspector at the bottom, following the pointer and falling back to the cursor
+        if self.view_settings.show_inspector && self.doc.binary_data.is_loaded() {
+            egui::TopBottomPanel::bottom("inspector_panel")
+                .resizable(true)
+                .default_height(200.0)
+                .show(ctx, |ui| {
+                    let offset = self.doc.hex_view.hovered_offset().or(self.doc.hex_view.cursor_offset());
+                    self.inspector_view
+                        .show(ui, self.doc.binary_data.bytes(), offset, &self.view_settings);
+                });
+        }
+
         // Main content area
         egui::CentralPanel::default().show(ctx, |ui| {
-            if !self.binary_data.is_loaded() {
+            if !self.doc.binary_data.is_loaded() {
                 ui.centered_and_justified(|ui| {
                     ui.label("Open a file to get started (File → Open...)");
                 });
                 return;
             }
+            if self.doc.binary_data.is_empty() {
+                ui.centered_and_justified(|ui| {
+                    ui.label("Empty file (0 bytes)");
+                });
+                return;
+            }
+
+            // A field hovered in either view is emphasized in both
+            let hovered_field = self.doc.data_view.hovered_field().or_else(|| {
+                self.doc.hex_view
+                    .hovered_field(&schema::place_fields_in(&self.doc.fields, self.doc.schema_base, self.doc.binary_data.bytes()))
+            });
 
             // Split view: hex on left, data on right
             let hex_focused = self.view_focus == ViewFocus::HexView;
@@ -535,13 +3761,78 @@ impl eframe::App for SchematicApp {
                             ui.label(egui::RichText::new("●").color(egui::Color32::from_rgb(100, 150, 255)));
                         }
                     });
+                    match self.doc.search_bar.show(ui, &self.doc.binary_data, self.doc.hex_view.selection()) {
+                        Some(SearchAction::Highlight(offsets, len)) => self.doc.hex_view.set_search_matches(offsets, len),
+                        Some(SearchAction::Jump(range)) => self.doc.hex_view.select_range(range),
+                        None => {}
+                    }
+                    if let Some(comparison) = &self.doc.comparison {
+                        match comparison.show_toolbar(ui, self.doc.hex_view.cursor_offset()) {
+                            Some(CompareAction::Jump(range)) => {
+                                self.doc.hex_view.select_range(range.start..range.end.min(self.doc.binary_data.size()));
+                                if let Some(comparison) = &mut self.doc.comparison {
+                                    comparison.select_range(range);
+                                }
+                            }
+                            Some(CompareAction::Close) => self.close_comparison(),
+                            None => {}
+                        }
+                    }
                     ui.separator();
-                    self.hex_view.show(
-                        ui,
-                        self.binary_data.bytes(),
-                        &self.fields,
-                        &self.selected_fields,
-                    );
+                    let fields = schema::place_fields_in(&self.doc.fields, self.doc.schema_base, self.doc.binary_data.bytes());
+                    if let Some(field) = self.primary_selected_field().and_then(|idx| fields.get(idx)) {
+                        egui::TopBottomPanel::bottom("field_bytes_panel").show_inside(ui, |ui| {
+                            self.field_bytes_view.show(ui, field, self.doc.binary_data.bytes(), &self.view_settings);
+                        });
+                    }
+                    let mut show_hex_view = |ui: &mut egui::Ui| {
+                        self.doc.hex_view.show(
+                            ui,
+                            self.doc.binary_data.bytes(),
+                            &fields,
+                            &self.doc.selected_fields,
+                            hovered_field,
+                            &self.view_settings,
+                            self.doc.relative_base,
+                            hex_focused,
+                        )
+                    };
+                    // The compared file goes side by side with the main one, scrolling along with it
+                    let hex_action = match &mut self.doc.comparison {
+                        Some(comparison) => {
+                            let mut hex_action = None;
+                            let mut compare_hovered = false;
+                            ui.columns(2, |columns| {
+                                hex_action = show_hex_view(&mut columns[0]);
+                                compare_hovered = comparison.show(&mut columns[1], &self.view_settings, self.doc.relative_base);
+                            });
+                            comparison.sync_scroll(&mut self.doc.hex_view, compare_hovered);
+                            hex_action
+                        }
+                        None => show_hex_view(ui),
+                    };
+                    match hex_action {
+                        Some(HexAction::WriteByte { offsets, value }) => {
+                            for offset in offsets {
+                                self.doc.binary_data.set_byte(offset, value);
+                            }
+                        }
+                        Some(HexAction::WriteBytes(writes)) => {
+                            for (offset, value) in writes {
+                                self.doc.binary_data.set_byte(offset, value);
+                            }
+                        }
+                        Some(HexAction::DefineField(offset)) => self.start_add_field_at(offset),
+                        Some(HexAction::DefineFieldFromSelection(range)) => self.start_add_field_for_range(range),
+                        Some(HexAction::DefineFieldAs {
+                            offset,
+                            data_type,
+                            endianness,
+                        }) => self.start_add_field_as(offset, data_type, endianness),
+                        Some(HexAction::EditField(idx)) => self.start_edit_field(idx),
+                        Some(HexAction::DeleteField(idx)) => self.delete_field(idx),
+                        None => {}
+                    }
                 });
 
                 // Data View with focus indicator
@@ -555,80 +3846,133 @@ impl eframe::App for SchematicApp {
                 data_frame.show(&mut columns[1], |ui| {
                     ui.horizontal(|ui| {
                         ui.heading("Data View");
+                        if self.doc.schema_locked {
+                            ui.label("🔒").on_hover_text("The schema is locked (Schema → Lock Schema)");
+                        }
                         if data_focused {
                             ui.label(egui::RichText::new("●").color(egui::Color32::from_rgb(100, 150, 255)));
                         }
+                        // Integer base, also under View → Number Format, and float notation
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            egui::ComboBox::from_id_salt("data_view_number_format")
+                                .selected_text(self.view_settings.number_format.name())
+                                .show_ui(ui, |ui| {
+                                    for &format in NumberFormat::all() {
+                                        ui.selectable_value(&mut self.view_settings.number_format, format, format.name());
+                                    }
+                                });
+                            ui.label("Integers:");
+
+                            let float_format = &mut self.view_settings.float_format;
+                            if float_format.notation != FloatNotation::Shortest {
+                                let hint = if float_format.notation == FloatNotation::Significant {
+                                    "Significant digits"
+                                } else {
+                                    "Decimals"
+                                };
+                                ui.add(egui::DragValue::new(&mut float_format.digits).range(0..=17))
+                                    .on_hover_text(hint);
+                            }
+                            egui::ComboBox::from_id_salt("data_view_float_format")
+                                .selected_text(float_format.notation.name())
+                                .show_ui(ui, |ui| {
+                                    for &notation in FloatNotation::all() {
+                                        ui.selectable_value(&mut float_format.notation, notation, notation.name());
+                                    }
+                                });
+                            ui.label("Floats:");
+                        });
                     });
                     ui.separator();
-                    if let Some(action) = self.data_view
-                        .show(ui, &self.fields, self.binary_data.bytes(), &self.selected_fields)
+                    if !self.doc.field_watches.is_empty() {
+                        egui::TopBottomPanel::bottom("watch_panel")
+                            .resizable(true)
+                            .show_inside(ui, |ui| {
+                                if let Some(idx) = self.watch_view.show(ui, &self.doc.fields, &self.doc.field_watches) {
+                                    self.doc.field_watches.remove(&idx);
+                                }
+                            });
+                    }
+                    let duplicate_names = schema::duplicate_names(&self.doc.fields);
+                    let fields = schema::place_fields_in(&self.doc.fields, self.doc.schema_base, self.doc.binary_data.bytes());
+                    let string_pool = self
+                        .doc
+                        .string_pool
+                        .map(|pool| StringPool::new(pool.offset.saturating_add(self.doc.schema_base), pool.length));
+                    let cursor_field = self
+                        .doc
+                        .hex_view
+                        .cursor_offset()
+                        .and_then(|offset| schema::field_at_offset(&fields, offset))
+                        .map(|(idx, _)| idx);
+                    self.doc.data_view.set_schema_locked(self.doc.schema_locked);
+                    if let Some(action) = self.doc.data_view
+                        .show(
+                            ui,
+                            &fields,
+                            self.doc.binary_data.bytes(),
+                            &self.doc.selected_fields,
+                            hovered_field,
+                            cursor_field,
+                            &self.view_settings,
+                            self.doc.relative_base,
+                            &self.doc.field_watches,
+                            &duplicate_names,
+                            string_pool.as_ref(),
+                        )
                     {
                         match action {
                             FieldAction::Select(idx) => {
-                                // Multi-selection with Ctrl/Shift support
                                 let modifiers = ui.input(|i| i.modifiers);
-
-                                if modifiers.ctrl {
-                                    // Ctrl+Click: Toggle field in selection
-                                    if self.selected_fields.contains(&idx) {
-                                        self.selected_fields.remove(&idx);
-                                    } else {
-                                        self.selected_fields.insert(idx);
-                                    }
-                                    self.last_selected_field = Some(idx);
-                                } else if modifiers.shift {
-                                    // Shift+Click: Select range from last selected to clicked
-                                    if let Some(last) = self.last_selected_field {
-                                        let start = last.min(idx);
-                                        let end = last.max(idx);
-                                        for i in start..=end {
-                                            self.selected_fields.insert(i);
-                                        }
-                                    } else {
-                                        self.selected_fields.clear();
-                                        self.selected_fields.insert(idx);
-                                    }
-                                    self.last_selected_field = Some(idx);
-                                } else {
-                                    // Normal click: Select only this field (clear others)
-                                    if self.selected_fields.len() == 1 && self.selected_fields.contains(&idx) {
-                                        // Toggle if already the only selected field
-                                        self.selected_fields.clear();
-                                        self.last_selected_field = None;
-                                    } else {
-                                        self.selected_fields.clear();
-                                        self.selected_fields.insert(idx);
-                                        self.last_selected_field = Some(idx);
-                                    }
+                                let offset = fields[idx].offset;
+                                self.select_field(idx, modifiers);
+                                if self.doc.selected_fields.contains(&idx) {
+                                    self.doc.hex_view.scroll_to_offset(offset);
+                                }
+                            }
+                            FieldAction::ToggleWatch(idx) => {
+                                self.toggle_watch(idx);
+                            }
+                            FieldAction::GoTo(offset) => {
+                                self.doc.hex_view.scroll_to_offset(offset);
+                            }
+                            FieldAction::SelectRange(range) => {
+                                self.doc.hex_view.select_range(range);
+                            }
+                            FieldAction::SetValue { offset, bytes } => {
+                                // Written like hex edits, so they show as modified bytes
+                                for (i, value) in bytes.into_iter().enumerate() {
+                                    self.doc.binary_data.set_byte(offset + i, value);
+                                }
+                            }
+                            FieldAction::SetComment(..) if self.doc.schema_locked => {}
+                            FieldAction::SetComment(idx, comment) => {
+                                // Typing into one comment box is a single undo step
+                                if self.doc.comment_undo_field != Some(idx) {
+                                    self.record_undo();
+                                    self.doc.comment_undo_field = Some(idx);
+                                }
+                                if let Some(field) = self.doc.fields.get_mut(idx) {
+                                    field.comment = comment;
                                 }
                             }
                             FieldAction::Edit(idx) => {
                                 self.start_edit_field(idx);
                             }
                             FieldAction::Delete(idx) => {
-                                self.fields.remove(idx);
-                                // Remove deleted field from selection
-                                self.selected_fields.remove(&idx);
-                                // Adjust all remaining selection indices
-                                let old_selections: Vec<usize> = self.selected_fields.iter().copied().collect();
-                                self.selected_fields.clear();
-                                for &field_idx in &old_selections {
-                                    if field_idx > idx {
-                                        self.selected_fields.insert(field_idx - 1);
-                                    } else if field_idx < idx {
-                                        self.selected_fields.insert(field_idx);
-                                    }
-                                    // field_idx == idx was already removed above
-                                }
-                                // Adjust last_selected_field
-                                if let Some(last) = self.last_selected_field {
-                                    if last == idx {
-                                        self.last_selected_field = None;
-                                    } else if last > idx {
-                                        self.last_selected_field = Some(last - 1);
-                                    }
+                                self.delete_field(idx);
+                            }
+                            FieldAction::Duplicate(idx) => {
+                                self.duplicate_field(idx);
+                            }
+                            FieldAction::MoveUp(idx) => {
+                                if idx > 0 {
+                                    self.swap_fields(idx - 1, idx);
                                 }
                             }
+                            FieldAction::MoveDown(idx) => {
+                                self.swap_fields(idx, idx + 1);
+                            }
                         }
                     }
                 });
@@ -0,0 +1,87 @@
+use crate::app::ViewFocus;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Maximum number of entries kept in each recent list
+const MAX_RECENT: usize = 10;
+
+/// Recently opened binaries and schemas, most recent first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecentFiles {
+    pub files: Vec<PathBuf>,
+    pub schemas: Vec<PathBuf>,
+}
+
+impl RecentFiles {
+    /// Move `path` to the front of the recent binaries
+    pub fn add_file(&mut self, path: &Path) {
+        push_recent(&mut self.files, path);
+    }
+
+    /// Move `path` to the front of the recent schemas
+    pub fn add_schema(&mut self, path: &Path) {
+        push_recent(&mut self.schemas, path);
+    }
+
+    /// Drop entries whose files no longer exist
+    pub fn prune(&mut self) {
+        self.files.retain(|path| path.exists());
+        self.schemas.retain(|path| path.exists());
+    }
+}
+
+/// Insert `path` at the front of `list`, removing an older copy and capping the length
+fn push_recent(list: &mut Vec<PathBuf>, path: &Path) {
+    list.retain(|p| p != path);
+    list.insert(0, path.to_path_buf());
+    list.truncate(MAX_RECENT);
+}
+
+/// Session state restored on the next launch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppState {
+    /// Binary file open when the app was closed
+    pub last_file: Option<PathBuf>,
+    /// Schema file loaded or saved last
+    pub last_schema: Option<PathBuf>,
+    /// View that had keyboard focus
+    pub view_focus: ViewFocus,
+    /// Recently opened binaries and schemas
+    pub recent: RecentFiles,
+    /// Widths of the Data View's resizable columns, empty for the defaults
+    pub data_view_columns: Vec<f32>,
+    /// Dark or light visuals, or whichever the OS prefers
+    pub theme: egui::ThemePreference,
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self {
+            last_file: None,
+            last_schema: None,
+            view_focus: ViewFocus::default(),
+            recent: RecentFiles::default(),
+            data_view_columns: Vec::new(),
+            theme: egui::ThemePreference::System,
+        }
+    }
+}
+
+impl AppState {
+    /// Key used in eframe storage
+    pub const STORAGE_KEY: &'static str = "app_state";
+
+    /// Restore the state from eframe storage, if any was saved
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        storage
+            .and_then(|s| eframe::get_value(s, Self::STORAGE_KEY))
+            .unwrap_or_default()
+    }
+
+    /// Write the state to eframe storage
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, Self::STORAGE_KEY, self);
+    }
+}
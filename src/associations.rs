@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Remembers which schema was last used with each binary file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SchemaAssociations {
+    /// Binary file path -> schema file path
+    schemas: HashMap<PathBuf, PathBuf>,
+}
+
+impl SchemaAssociations {
+    /// Key used in eframe storage
+    pub const STORAGE_KEY: &'static str = "schema_associations";
+
+    /// Restore the associations from eframe storage, if any were saved
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        storage
+            .and_then(|s| eframe::get_value(s, Self::STORAGE_KEY))
+            .unwrap_or_default()
+    }
+
+    /// Write the associations to eframe storage
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, Self::STORAGE_KEY, self);
+    }
+
+    /// Record that `schema` was used with `binary`
+    pub fn record(&mut self, binary: &Path, schema: &Path) {
+        self.schemas.insert(binary.to_path_buf(), schema.to_path_buf());
+    }
+
+    /// Get the last schema used with `binary`, if it still exists on disk
+    pub fn schema_for(&self, binary: &Path) -> Option<&PathBuf> {
+        self.schemas.get(binary).filter(|schema| schema.exists())
+    }
+}
@@ -1,16 +1,62 @@
-use std::fs::File;
+use memmap2::{MmapMut, MmapOptions};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
 use std::io::{self, Read};
-use std::path::PathBuf;
+use std::ops::{Deref, DerefMut, Range};
+use std::path::{Path, PathBuf};
+
+/// Files larger than this are memory mapped instead of read into memory
+pub const MMAP_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+/// Backing store of the loaded bytes
+enum Storage {
+    /// Bytes read into memory
+    Owned(Vec<u8>),
+    /// Copy-on-write mapping of a large file; edits stay private until saved
+    Mapped(MmapMut),
+}
+
+impl Default for Storage {
+    fn default() -> Self {
+        Storage::Owned(Vec::new())
+    }
+}
+
+impl Deref for Storage {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Storage::Owned(data) => data,
+            Storage::Mapped(map) => map,
+        }
+    }
+}
+
+impl DerefMut for Storage {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        match self {
+            Storage::Owned(data) => data,
+            Storage::Mapped(map) => map,
+        }
+    }
+}
 
 /// Represents a loaded binary file with its data and metadata
 #[derive(Default)]
 pub struct BinaryData {
     /// The raw bytes of the file
-    data: Vec<u8>,
+    data: Storage,
     /// Path to the loaded file
     file_path: Option<PathBuf>,
+    /// Whether a file (or stdin) has been loaded, even if it was empty
+    loaded: bool,
     /// Whether the data has been modified
     modified: bool,
+    /// Original values of the bytes changed since loading, keyed by offset
+    original_bytes: BTreeMap<usize, u8>,
+    /// Incremented every time the data changes, so views can cheaply detect changes
+    generation: u64,
 }
 
 impl BinaryData {
@@ -19,15 +65,88 @@ impl BinaryData {
     }
 
     /// Load a binary file from the given path
-    pub fn load_from_file(&mut self, path: PathBuf) -> io::Result<()> {
-        let mut file = File::open(&path)?;
+    /// Files above `MMAP_THRESHOLD` are memory mapped, falling back to reading them if that fails
+    ///
+    /// Returns the error mapping failed with when the file was read instead, for the caller to report.
+    pub fn load_from_file(&mut self, path: PathBuf) -> io::Result<Option<io::Error>> {
+        let file = File::open(&path)?;
+        let mut map_error = None;
+        let mapped = if file.metadata()?.len() > MMAP_THRESHOLD {
+            // SAFETY: the mapping is private, so our edits never reach the file; changes made
+            // to the file by other processes while it's open may still show through
+            unsafe { MmapOptions::new().map_copy(&file) }
+                .map_err(|e| map_error = Some(e))
+                .ok()
+        } else {
+            None
+        };
+
+        match mapped {
+            Some(map) => self.set_data(Storage::Mapped(map)),
+            None => self.load_from_reader(file)?,
+        }
+        self.file_path = Some(path);
+
+        Ok(map_error)
+    }
+
+    /// Load data from a reader such as stdin; the data has no file path afterwards
+    pub fn load_from_reader(&mut self, mut reader: impl Read) -> io::Result<()> {
         let mut data = Vec::new();
-        file.read_to_end(&mut data)?;
+        reader.read_to_end(&mut data)?;
+        self.set_data(Storage::Owned(data));
+
+        Ok(())
+    }
 
+    /// Replace the bytes, resetting the file path and modification state
+    fn set_data(&mut self, data: Storage) {
         self.data = data;
-        self.file_path = Some(path);
+        self.file_path = None;
+        self.loaded = true;
         self.modified = false;
+        self.original_bytes.clear();
+        self.generation += 1;
+    }
 
+    /// Check whether the data is memory mapped rather than held in memory
+    pub fn is_mapped(&self) -> bool {
+        matches!(self.data, Storage::Mapped(_))
+    }
+
+    /// Write the data to `path`, leaving the loaded state untouched
+    pub fn save_to_file(&self, path: &Path) -> io::Result<()> {
+        if !self.is_mapped() {
+            return fs::write(path, &*self.data);
+        }
+
+        // Truncating a file that's still mapped would pull the bytes out from under us,
+        // so write a sibling file and move it into place
+        let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+        temp_name.push(".tmp");
+        let temp_path = path.with_file_name(temp_name);
+        fs::write(&temp_path, &*self.data)?;
+        fs::rename(&temp_path, path).inspect_err(|_| {
+            let _ = fs::remove_file(&temp_path);
+        })
+    }
+
+    /// Write the data back to the file it was loaded from, marking it unmodified
+    pub fn save(&mut self) -> io::Result<()> {
+        let path = self
+            .file_path
+            .clone()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "the data has no file path"))?;
+        self.save_as(path)
+    }
+
+    /// Write the data to `path` and make it the current file, marking it unmodified
+    pub fn save_as(&mut self, path: PathBuf) -> io::Result<()> {
+        self.save_to_file(&path)?;
+        self.file_path = Some(path);
+        self.modified = false;
+        self.original_bytes.clear();
+        self.generation += 1;
         Ok(())
     }
 
@@ -41,9 +160,14 @@ impl BinaryData {
         self.file_path.as_ref()
     }
 
-    /// Check if the file is loaded
+    /// Check if a file is loaded, including a 0-byte one
     pub fn is_loaded(&self) -> bool {
-        !self.data.is_empty()
+        self.loaded
+    }
+
+    /// Check if the loaded data has no bytes
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
     }
 
     /// Get the size of the loaded data
@@ -56,10 +180,83 @@ impl BinaryData {
         self.modified
     }
 
+    /// Overwrite the byte at `offset`; a no-op past the end of the data
+    pub fn set_byte(&mut self, offset: usize, value: u8) {
+        if let Some(byte) = self.data.get_mut(offset)
+            && *byte != value
+        {
+            let original = *self.original_bytes.entry(offset).or_insert(*byte);
+            if original == value {
+                // Changed back to the loaded value
+                self.original_bytes.remove(&offset);
+            }
+            *byte = value;
+            self.modified = true;
+            self.generation += 1;
+        }
+    }
+
+    /// Find the first occurrence of `needle` at or after `start`
+    pub fn find(&self, needle: &[u8], start: usize) -> Option<usize> {
+        if needle.is_empty() {
+            return None;
+        }
+        self.data
+            .get(start..)?
+            .windows(needle.len())
+            .position(|window| window == needle)
+            .map(|pos| start + pos)
+    }
+
+    /// Find the start offsets of the occurrences of `needle`, overlapping ones included,
+    /// stopping after `limit` matches
+    pub fn find_all(&self, needle: &[u8], limit: usize) -> Vec<usize> {
+        crate::analysis::find_all(&self.data, needle, limit)
+    }
+
+    /// Count how often each byte value occurs
+    pub fn histogram(&self) -> [usize; 256] {
+        crate::analysis::byte_histogram(&self.data)
+    }
+
+    /// Get the entropy in bits per byte of each consecutive `window`-byte block
+    pub fn entropy_windows(&self, window: usize) -> Vec<f64> {
+        crate::analysis::entropy_windows(&self.data, window)
+    }
+
+    /// Find runs of at least `min_len` printable ASCII bytes, with their offsets
+    pub fn find_strings(&self, min_len: usize) -> Vec<(usize, String)> {
+        crate::analysis::find_strings(&self.data, min_len)
+    }
+
+    /// Find runs of at least `min_len` printable ASCII characters encoded as UTF-16LE
+    pub fn find_utf16_strings(&self, min_len: usize) -> Vec<(usize, String)> {
+        crate::analysis::find_utf16_strings(&self.data, min_len)
+    }
+
+    /// Get the ranges of bytes that differ from `other`, in ascending order
+    /// If the sizes differ, the tail of the longer file is part of the last range
+    pub fn diff(&self, other: &BinaryData) -> Vec<Range<usize>> {
+        crate::analysis::diff_ranges(&self.data, &other.data)
+    }
+
+    /// Get the offsets of the bytes that differ from the loaded data, in ascending order
+    pub fn dirty_offsets(&self) -> impl Iterator<Item = usize> + '_ {
+        self.original_bytes.keys().copied()
+    }
+
+    /// Get the change counter, incremented every time the data changes
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
     /// Clear the loaded data
     pub fn clear(&mut self) {
-        self.data.clear();
+        self.data = Storage::default();
         self.file_path = None;
+        self.loaded = false;
         self.modified = false;
+        self.original_bytes.clear();
+        self.generation += 1;
     }
 }
@@ -0,0 +1,63 @@
+//! Checksums that formats embed to protect a region of the file
+
+/// Lookup table for the reflected CRC-32 polynomial 0xEDB88320
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// CRC-32 as used by zip, gzip and PNG (IEEE 802.3, reflected, final XOR)
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &b| {
+        CRC32_TABLE[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+/// CRC-16/CCITT-FALSE: polynomial 0x1021, initial value 0xFFFF, no reflection
+pub fn crc16_ccitt(data: &[u8]) -> u16 {
+    data.iter().fold(0xFFFFu16, |mut crc, &b| {
+        crc ^= (b as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+        crc
+    })
+}
+
+/// Sum of the bytes, modulo 256
+pub fn sum8(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |sum, &b| sum.wrapping_add(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The standard check input of the CRC catalogue
+    const CHECK: &[u8] = b"123456789";
+
+    #[test]
+    fn known_check_values() {
+        assert_eq!(crc32(CHECK), 0xCBF4_3926);
+        assert_eq!(crc16_ccitt(CHECK), 0x29B1);
+        assert_eq!(sum8(CHECK), 0xDD);
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(crc32(&[]), 0);
+        assert_eq!(crc16_ccitt(&[]), 0xFFFF);
+        assert_eq!(sum8(&[]), 0);
+    }
+}
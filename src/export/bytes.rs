@@ -0,0 +1,39 @@
+/// Alphabet of standard base64 (RFC 4648)
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Format bytes as space-separated hex pairs, e.g. `DE AD BE EF`
+pub fn to_hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" ")
+}
+
+/// Format bytes as a C array definition named `name`, 12 bytes per line
+/// Bytes are written as `0xAB`, or `0xab` with `lowercase_hex`; the name is left as is
+pub fn to_c_array(bytes: &[u8], name: &str, lowercase_hex: bool) -> String {
+    let mut out = format!("const unsigned char {}[{}] = {{\n", name, bytes.len());
+    for line in bytes.chunks(12) {
+        let items: Vec<String> = line
+            .iter()
+            .map(|b| if lowercase_hex { format!("0x{:02x}", b) } else { format!("0x{:02X}", b) })
+            .collect();
+        out.push_str(&format!("    {},\n", items.join(", ")));
+    }
+    out.push_str("};\n");
+    out
+}
+
+/// Encode bytes as padded standard base64
+pub fn to_base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,292 @@
+use super::struct_export::StructExport;
+use crate::schema::{DataType, Endianness, Field};
+use std::collections::{HashMap, HashSet};
+
+/// Turn an arbitrary name into a Kaitai identifier (lowercase letters, digits and underscores, starting with a letter)
+fn kaitai_identifier(name: &str) -> String {
+    let mut ident = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            // CamelCase becomes snake_case
+            if c.is_ascii_uppercase() && ident.chars().last().is_some_and(|last| last.is_ascii_lowercase()) {
+                ident.push('_');
+            }
+            ident.push(c.to_ascii_lowercase());
+        } else if !ident.is_empty() && !ident.ends_with('_') {
+            ident.push('_');
+        }
+    }
+    let ident = ident.trim_end_matches('_');
+    if ident.starts_with(|c: char| c.is_ascii_lowercase()) {
+        ident.to_string()
+    } else {
+        format!("f_{}", ident)
+    }
+}
+
+/// Get a Kaitai identifier for `name` that hasn't been used yet in this type
+fn unique_identifier(used: &mut HashSet<String>, name: &str) -> String {
+    let base = kaitai_identifier(name);
+    let mut ident = base.clone();
+    let mut n = 2;
+    while !used.insert(ident.clone()) {
+        ident = format!("{}_{}", base, n);
+        n += 1;
+    }
+    ident
+}
+
+/// Get the Kaitai integer or float type for a data type (e.g. `u4le`), `None` for other types
+fn primitive_type(data_type: DataType, endianness: Endianness) -> Option<String> {
+    let (kind, size) = match data_type {
+        DataType::F32 | DataType::F64 => ('f', data_type.size()),
+        data_type if data_type.is_integer() => {
+            let signed = matches!(data_type, DataType::I8 | DataType::I16 | DataType::I32 | DataType::I64);
+            (if signed { 's' } else { 'u' }, data_type.size())
+        }
+        _ => return None,
+    };
+    // Single bytes have no byte order
+    let suffix = match (size, endianness) {
+        (1, _) => "",
+        (_, Endianness::Little) => "le",
+        (_, Endianness::Big) => "be",
+    };
+    Some(format!("{}{}{}", kind, size, suffix))
+}
+
+/// Get the attribute keys (other than `id`, `pos` and `doc`) describing one field
+/// Types Kaitai can't decode the same way are written as their raw bytes, with a warning
+/// `length_ident` is the emitted id of the field its length is read from, if any
+fn field_attributes(field: &Field, length_ident: Option<&str>, warnings: &mut Vec<String>) -> Vec<(&'static str, String)> {
+    let mut attributes = Vec::new();
+    let data_type = match field.data_type {
+        DataType::Checksum { algorithm, .. } => {
+            warnings.push(format!(
+                "Field '{}' is a {} checksum; Kaitai reads it but doesn't verify it",
+                field.identifier(),
+                algorithm.name()
+            ));
+            algorithm.int_type().data_type()
+        }
+        data_type => data_type.timestamp_int_type().unwrap_or(data_type),
+    };
+
+    match data_type {
+        DataType::FixedString { len } => {
+            attributes.push(("type", "str".to_string()));
+            attributes.push(("size", len.to_string()));
+            attributes.push(("encoding", "ASCII".to_string()));
+        }
+        DataType::CString { max_len } => {
+            attributes.push(("type", "strz".to_string()));
+            attributes.push(("size", max_len.to_string()));
+            attributes.push(("encoding", "ASCII".to_string()));
+        }
+        DataType::Bytes { len } => attributes.push(("size", len.to_string())),
+        DataType::MacAddr | DataType::Ipv4 | DataType::Ipv6 => {
+            attributes.push(("size", data_type.size().to_string()))
+        }
+        DataType::Bits { .. } | DataType::SliceDescriptor { .. } | DataType::Struct { .. } => {
+            let reason = match data_type {
+                DataType::Bits { .. } => "is a bit field, which Kaitai reads only in sequence",
+                DataType::SliceDescriptor { .. } => "is a slice descriptor",
+                _ => "is a nested struct",
+            };
+            warnings.push(format!("Field '{}' {}; it is exported as raw bytes", field.identifier(), reason));
+            attributes.push(("size", data_type.size().to_string()));
+        }
+        data_type => {
+            if let Some(ty) = primitive_type(data_type, field.endianness) {
+                attributes.push(("type", ty));
+            }
+        }
+    }
+
+    // A length read from another field becomes an expression naming it
+    if let Some(length_ident) = length_ident
+        && let Some((_, size)) = attributes.iter_mut().find(|(key, _)| *key == "size")
+    {
+        *size = length_ident.to_string();
+    }
+    if field.is_array() {
+        attributes.push(("repeat", "expr".to_string()));
+        attributes.push(("repeat-expr", field.count.to_string()));
+    }
+    if field.has_enum_labels() {
+        warnings.push(format!("Enum labels of field '{}' are not exported", field.identifier()));
+    }
+    attributes
+}
+
+/// Write one `seq` or `instances` entry
+fn push_entry(out: &mut String, ident: &str, pos: Option<usize>, attributes: &[(&str, String)], doc: &str) {
+    match pos {
+        Some(pos) => out.push_str(&format!("  {}:\n    pos: 0x{:X}\n", ident, pos)),
+        None => out.push_str(&format!("  - id: {}\n", ident)),
+    }
+    for (key, value) in attributes {
+        out.push_str(&format!("    {}: {}\n", key, value));
+    }
+    if !doc.is_empty() {
+        // A JSON string is also a valid double-quoted YAML scalar
+        let doc = serde_json::to_string(doc).unwrap_or_default();
+        out.push_str(&format!("    doc: {}\n", doc));
+    }
+}
+
+/// Export the fields as a Kaitai Struct (`.ksy`) definition
+///
+/// Fields are read in offset order as a `seq`, with gaps skipped as padding. Fields overlapping
+/// the previous one become `instances` read at their absolute position, as do the fields after
+/// one whose length is read from another field, since the data no longer follows the declared layout.
+pub fn export_kaitai(fields: &[Field], name: &str) -> StructExport {
+    let mut sorted: Vec<usize> = (0..fields.len()).collect();
+    sorted.sort_by_key(|&idx| fields[idx].offset);
+
+    // Ids are given in offset order, so a length field may be emitted under a deduplicated id
+    let mut used = HashSet::new();
+    let mut idents = vec![String::new(); fields.len()];
+    for &idx in &sorted {
+        idents[idx] = unique_identifier(&mut used, &fields[idx].identifier());
+    }
+    // Length fields are looked up by name, the first field with it winning
+    let mut idents_by_name: HashMap<&str, &str> = HashMap::new();
+    for (field, ident) in fields.iter().zip(&idents) {
+        idents_by_name.entry(field.name.as_str()).or_insert(ident);
+    }
+
+    let mut warnings = Vec::new();
+    let mut seq = String::new();
+    let mut instances = String::new();
+    let mut cursor = 0;
+    let mut pad_idx = 0;
+    let mut sized_at_runtime = false;
+
+    for idx in sorted {
+        let field = &fields[idx];
+        let ident = &idents[idx];
+        let length_ident = field.length_field.as_deref().map(|name| match idents_by_name.get(name) {
+            Some(ident) => ident.to_string(),
+            None => kaitai_identifier(name),
+        });
+        let attributes = field_attributes(field, length_ident.as_deref(), &mut warnings);
+        if sized_at_runtime || field.offset < cursor {
+            push_entry(&mut instances, ident, Some(field.offset), &attributes, &field.comment);
+            continue;
+        }
+        if field.offset > cursor {
+            let pad = unique_identifier(&mut used, &format!("pad{}", pad_idx));
+            push_entry(&mut seq, &pad, None, &[("size", (field.offset - cursor).to_string())], "");
+            pad_idx += 1;
+        }
+        push_entry(&mut seq, ident, None, &attributes, &field.comment);
+        cursor = field.offset.saturating_add(field.size());
+        sized_at_runtime = field.length_field.is_some() && field.data_type.is_byte_string();
+    }
+
+    let mut out = String::new();
+    for warning in &warnings {
+        out.push_str(&format!("# Warning: {}\n", warning));
+    }
+    out.push_str(&format!("meta:\n  id: {}\n", kaitai_identifier(name)));
+    if !seq.is_empty() {
+        out.push_str("seq:\n");
+        out.push_str(&seq);
+    }
+    if !instances.is_empty() {
+        out.push_str("instances:\n");
+        out.push_str(&instances);
+    }
+
+    StructExport { source: out, warnings }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, offset: usize, data_type: DataType) -> Field {
+        Field::new(name.to_string(), offset, data_type)
+    }
+
+    fn big_endian(mut field: Field) -> Field {
+        field.endianness = Endianness::Big;
+        field
+    }
+
+    #[test]
+    fn primitive_types_map_to_kaitai_types() {
+        let fields = vec![
+            field("a", 0, DataType::U8),
+            field("b", 1, DataType::U16),
+            big_endian(field("c", 3, DataType::I32)),
+            field("d", 7, DataType::F64),
+        ];
+        let source = export_kaitai(&fields, "types").source;
+
+        assert!(source.contains("  - id: a\n    type: u1\n"));
+        assert!(source.contains("  - id: b\n    type: u2le\n"));
+        assert!(source.contains("  - id: c\n    type: s4be\n"));
+        assert!(source.contains("  - id: d\n    type: f8le\n"));
+    }
+
+    #[test]
+    fn gaps_become_padding_entries() {
+        let fields = vec![field("magic", 0, DataType::U32), field("version", 8, DataType::U16)];
+        let source = export_kaitai(&fields, "header").source;
+
+        assert!(source.contains("  - id: magic\n    type: u4le\n  - id: pad0\n    size: 4\n  - id: version\n"));
+    }
+
+    #[test]
+    fn overlapping_fields_become_instances() {
+        let fields = vec![field("value", 0, DataType::U32), field("low", 0, DataType::U16)];
+        let source = export_kaitai(&fields, "overlay").source;
+
+        let (seq, instances) = source.split_once("instances:\n").unwrap();
+        assert!(seq.contains("  - id: value\n"));
+        assert!(instances.contains("  low:\n    pos: 0x0\n    type: u2le\n"));
+    }
+
+    #[test]
+    fn arrays_repeat_by_count() {
+        let mut values = field("values", 0, DataType::U16);
+        values.count = 3;
+        let source = export_kaitai(&[values], "table").source;
+
+        assert!(source.contains("  - id: values\n    type: u2le\n    repeat: expr\n    repeat-expr: 3\n"));
+    }
+
+    #[test]
+    fn docs_are_escaped() {
+        let mut flags = field("flags", 0, DataType::U8);
+        flags.comment = "bit 0: \"on\"\nbit 1: # not a comment".to_string();
+        let source = export_kaitai(&[flags], "flags").source;
+
+        assert!(source.contains("    doc: \"bit 0: \\\"on\\\"\\nbit 1: # not a comment\"\n"));
+    }
+
+    #[test]
+    fn length_expressions_use_the_emitted_id() {
+        let mut payload = field("payload", 4, DataType::Bytes { len: 8 });
+        payload.length_field = Some("len".to_string());
+        let fields = vec![field("Len", 0, DataType::U16), field("len", 2, DataType::U16), payload];
+        let source = export_kaitai(&fields, "packet").source;
+
+        assert!(source.contains("  - id: len\n    type: u2le\n  - id: len_2\n"));
+        assert!(source.contains("  - id: payload\n    size: len_2\n"));
+    }
+
+    #[test]
+    fn fields_after_a_runtime_length_are_read_at_their_position() {
+        let mut payload = field("payload", 2, DataType::Bytes { len: 8 });
+        payload.length_field = Some("len".to_string());
+        let fields = vec![field("len", 0, DataType::U16), payload, field("crc", 10, DataType::U32)];
+        let source = export_kaitai(&fields, "packet").source;
+
+        assert!(source.contains("  - id: payload\n    size: len\n"));
+        let instances = &source[source.find("instances:\n").unwrap()..];
+        assert!(instances.contains("  crc:\n    pos: 0xA\n    type: u4le\n"));
+    }
+}
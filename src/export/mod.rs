@@ -0,0 +1,48 @@
+pub mod bytes;
+pub mod kaitai;
+pub mod struct_export;
+pub mod values;
+
+pub use bytes::{to_base64, to_c_array, to_hex_string};
+pub use kaitai::export_kaitai;
+pub use struct_export::{export_c_struct, export_rust_struct};
+pub use values::{export_values_csv, export_values_json};
+
+/// Keywords that can't be used as identifiers in the generated C or Rust code
+const RESERVED_WORDS: &[&str] = &[
+    // C
+    "auto", "break", "case", "char", "const", "continue", "default", "do", "double", "else", "enum",
+    "extern", "float", "for", "goto", "if", "inline", "int", "long", "register", "restrict", "return",
+    "short", "signed", "sizeof", "static", "struct", "switch", "typedef", "union", "unsigned", "void",
+    "volatile", "while",
+    // Rust
+    "as", "async", "await", "crate", "dyn", "false", "fn", "impl", "in", "let", "loop", "match", "mod",
+    "move", "mut", "pub", "ref", "self", "Self", "super", "trait", "true", "type", "unsafe", "use",
+    "where",
+];
+
+/// Turn an arbitrary field name into a valid C/Rust identifier
+pub fn sanitize_identifier(name: &str) -> String {
+    let mut ident: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+
+    if ident.is_empty() {
+        ident.push_str("field");
+    }
+    if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    if RESERVED_WORDS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+
+    ident
+}
+
+/// Check whether a name can be used as-is as a C/Rust identifier
+pub fn is_valid_identifier(name: &str) -> bool {
+    sanitize_identifier(name) == name
+}
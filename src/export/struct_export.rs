@@ -0,0 +1,320 @@
+use super::sanitize_identifier;
+use crate::schema::{DataType, Field};
+use std::collections::HashSet;
+
+/// A member of a generated struct
+enum Member<'a> {
+    Field(&'a Field),
+    /// Explicit padding bytes filling a gap between declared offsets
+    Padding { size: usize },
+}
+
+/// Struct layout derived from the declared field offsets
+struct StructLayout<'a> {
+    members: Vec<Member<'a>>,
+    /// Whether the declared offsets require a packed struct
+    packed: bool,
+    /// Total size of the struct in bytes
+    size: usize,
+    /// Problems to report alongside the generated code
+    warnings: Vec<String>,
+}
+
+/// Generated struct source code along with any layout warnings
+pub struct StructExport {
+    pub source: String,
+    pub warnings: Vec<String>,
+}
+
+/// Compute a struct layout that reproduces the declared offsets
+///
+/// Offsets are made relative to the first field. Gaps become explicit padding members.
+/// If a field isn't naturally aligned, or the span isn't a multiple of the struct's alignment
+/// (which would add trailing padding), the struct must be packed to match the declared layout.
+fn compute_layout(fields: &[Field]) -> StructLayout<'_> {
+    let mut sorted: Vec<&Field> = fields.iter().collect();
+    sorted.sort_by_key(|field| field.offset);
+
+    let base = sorted.first().map_or(0, |field| field.offset);
+    let mut members = Vec::new();
+    let mut warnings = Vec::new();
+    let mut packed = false;
+    let mut max_align = 1;
+    let mut cursor = base;
+
+    for field in sorted {
+        if field.offset < cursor {
+            warnings.push(format!(
+                "Field '{}' at 0x{:X} overlaps the previous field and was skipped",
+                field.identifier(), field.offset
+            ));
+            continue;
+        }
+
+        let align = alignment(field.data_type);
+        max_align = max_align.max(align);
+        if !(field.offset - base).is_multiple_of(align) {
+            packed = true;
+            warnings.push(format!(
+                "Field '{}' at 0x{:X} is not aligned to {} bytes; the struct is packed",
+                field.identifier(), field.offset, align
+            ));
+        }
+
+        if field.offset > cursor {
+            members.push(Member::Padding { size: field.offset - cursor });
+        }
+        members.push(Member::Field(field));
+        cursor = field.offset + field.size();
+    }
+
+    let size = cursor - base;
+    if !packed && !size.is_multiple_of(max_align) {
+        packed = true;
+        warnings.push(format!(
+            "Size {} is not a multiple of the struct alignment ({}); the struct is packed to avoid trailing padding",
+            size, max_align
+        ));
+    }
+
+    if base != 0 {
+        warnings.push(format!("Offsets are relative to the first field at 0x{:X}", base));
+    }
+
+    StructLayout {
+        members,
+        packed,
+        size,
+        warnings,
+    }
+}
+
+/// Get a valid identifier for `name` that hasn't been used yet in this struct
+fn unique_identifier(used: &mut HashSet<String>, name: &str) -> String {
+    let base = sanitize_identifier(name);
+    let mut ident = base.clone();
+    let mut n = 2;
+    while !used.insert(ident.clone()) {
+        ident = format!("{}_{}", base, n);
+        n += 1;
+    }
+    ident
+}
+
+/// Get the natural alignment of a data type
+fn alignment(data_type: DataType) -> usize {
+    match data_type {
+        // Bit fields, addresses, slice descriptors, strings and nested structs are exported as their raw bytes
+        DataType::Bits { .. } => 1,
+        data_type if data_type.is_timestamp() || data_type.is_checksum() => data_type.size(),
+        data_type if !data_type.is_integer() && !data_type.is_float() => 1,
+        _ => data_type.size(),
+    }
+}
+
+/// Get the element type and array length used to export a field
+/// Bit fields, addresses, slice descriptors, strings and nested structs become a byte array
+/// covering their bytes,
+/// and timestamps and checksums the integer they're stored as
+fn element_type_and_count(field: &Field) -> (DataType, Option<usize>) {
+    let count = field.is_array().then_some(field.count);
+    match field.data_type {
+        DataType::Bits { .. } => (DataType::U8, Some(field.size())),
+        DataType::Checksum { algorithm, .. } => (algorithm.int_type().data_type(), count),
+        data_type if let Some(int_type) = data_type.timestamp_int_type() => (int_type, count),
+        data_type if !data_type.is_integer() && !data_type.is_float() => (DataType::U8, Some(field.size())),
+        data_type => (data_type, count),
+    }
+}
+
+/// Get the C type name for a data type
+fn c_type(data_type: DataType) -> &'static str {
+    match data_type {
+        DataType::U8 => "uint8_t",
+        DataType::U16 => "uint16_t",
+        DataType::U32 => "uint32_t",
+        DataType::U64 => "uint64_t",
+        DataType::I8 => "int8_t",
+        DataType::I16 => "int16_t",
+        DataType::I32 => "int32_t",
+        DataType::I64 => "int64_t",
+        DataType::F32 => "float",
+        DataType::F64 => "double",
+        DataType::Bits { .. }
+        | DataType::MacAddr
+        | DataType::Ipv4
+        | DataType::Ipv6
+        | DataType::SliceDescriptor { .. }
+        | DataType::FixedString { .. }
+        | DataType::CString { .. }
+        | DataType::Bytes { .. }
+        | DataType::Checksum { .. }
+        | DataType::Struct { .. } => "uint8_t",
+        DataType::UnixTimestamp32 => "uint32_t",
+        DataType::UnixTimestamp64 => "int64_t",
+        DataType::FileTime64 => "uint64_t",
+    }
+}
+
+/// Export the fields as a C struct definition
+pub fn export_c_struct(fields: &[Field], name: &str) -> StructExport {
+    let layout = compute_layout(fields);
+    let mut out = String::from("#include <stdint.h>\n\n");
+
+    for warning in &layout.warnings {
+        out.push_str(&format!("/* Warning: {} */\n", warning));
+    }
+    if layout.packed {
+        out.push_str("#pragma pack(push, 1)\n");
+    }
+
+    out.push_str("typedef struct {\n");
+    let mut used = HashSet::new();
+    let mut pad_idx = 0;
+    for member in &layout.members {
+        match member {
+            Member::Field(field) => {
+                let ident = unique_identifier(&mut used, &field.identifier());
+                let (element_type, count) = element_type_and_count(field);
+                let array = count.map(|count| format!("[{}]", count)).unwrap_or_default();
+                out.push_str(&format!("    {} {}{};", c_type(element_type), ident, array));
+                if !field.comment.is_empty() {
+                    out.push_str(&format!(" /* {} */", field.comment.replace("*/", "* /")));
+                }
+                out.push('\n');
+            }
+            Member::Padding { size } => {
+                out.push_str(&format!("    uint8_t _pad{}[{}];\n", pad_idx, size));
+                pad_idx += 1;
+            }
+        }
+    }
+    out.push_str(&format!("}} {};\n", sanitize_identifier(name)));
+
+    if layout.packed {
+        out.push_str("#pragma pack(pop)\n");
+    }
+    out.push_str(&format!("/* sizeof({}) == {} */\n", sanitize_identifier(name), layout.size));
+
+    StructExport {
+        source: out,
+        warnings: layout.warnings,
+    }
+}
+
+/// Export the fields as a Rust struct definition
+pub fn export_rust_struct(fields: &[Field], name: &str) -> StructExport {
+    let layout = compute_layout(fields);
+    let mut out = String::new();
+
+    for warning in &layout.warnings {
+        out.push_str(&format!("// Warning: {}\n", warning));
+    }
+    out.push_str(&format!("// size_of::<{}>() == {}\n", sanitize_identifier(name), layout.size));
+    out.push_str(if layout.packed { "#[repr(C, packed)]\n" } else { "#[repr(C)]\n" });
+    out.push_str("#[derive(Debug, Clone, Copy)]\n");
+    out.push_str(&format!("pub struct {} {{\n", sanitize_identifier(name)));
+
+    let mut used = HashSet::new();
+    let mut pad_idx = 0;
+    for member in &layout.members {
+        match member {
+            Member::Field(field) => {
+                if !field.comment.is_empty() {
+                    out.push_str(&format!("    /// {}\n", field.comment));
+                }
+                let (element_type, count) = element_type_and_count(field);
+                let ty = element_type.name();
+                let ty = match count {
+                    Some(count) => format!("[{}; {}]", ty, count),
+                    None => ty.to_string(),
+                };
+                out.push_str(&format!("    pub {}: {},\n", unique_identifier(&mut used, &field.identifier()), ty));
+            }
+            Member::Padding { size } => {
+                out.push_str(&format!("    pub _pad{}: [u8; {}],\n", pad_idx, size));
+                pad_idx += 1;
+            }
+        }
+    }
+    out.push_str("}\n");
+
+    StructExport {
+        source: out,
+        warnings: layout.warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, offset: usize, data_type: DataType) -> Field {
+        Field::new(name.to_string(), offset, data_type)
+    }
+
+    /// Get the sizes of the padding members, in order
+    fn padding(layout: &StructLayout) -> Vec<usize> {
+        layout
+            .members
+            .iter()
+            .filter_map(|member| match member {
+                Member::Padding { size } => Some(*size),
+                Member::Field(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn gaps_become_padding_and_size_matches_span() {
+        let fields = vec![field("tag", 0, DataType::U8), field("len", 4, DataType::U32), field("crc", 8, DataType::U64)];
+        let layout = compute_layout(&fields);
+        assert_eq!(padding(&layout), vec![3]);
+        assert_eq!(layout.size, 16);
+        assert!(!layout.packed);
+
+        let export = export_c_struct(&fields, "header");
+        assert!(export.source.contains("uint8_t _pad0[3];"));
+        assert!(export.source.contains("sizeof(header) == 16"));
+        assert!(!export.source.contains("#pragma pack"));
+    }
+
+    #[test]
+    fn unaligned_field_packs_the_struct() {
+        let fields = vec![field("tag", 0, DataType::U8), field("len", 1, DataType::U32)];
+        let layout = compute_layout(&fields);
+        assert!(layout.packed);
+        assert!(padding(&layout).is_empty());
+        assert_eq!(layout.size, 5);
+
+        assert!(export_c_struct(&fields, "header").source.contains("#pragma pack(push, 1)"));
+        assert!(export_rust_struct(&fields, "Header").source.contains("#[repr(C, packed)]"));
+    }
+
+    #[test]
+    fn trailing_padding_packs_the_struct() {
+        let fields = vec![field("len", 0, DataType::U32), field("tag", 4, DataType::U8)];
+        let layout = compute_layout(&fields);
+        assert!(layout.packed);
+        assert_eq!(layout.size, 5);
+    }
+
+    #[test]
+    fn offsets_are_relative_to_the_first_field() {
+        let fields = vec![field("a", 0x10, DataType::U16), field("b", 0x14, DataType::U16)];
+        let layout = compute_layout(&fields);
+        assert_eq!(padding(&layout), vec![2]);
+        assert_eq!(layout.size, 6);
+        assert!(layout.warnings.iter().any(|w| w.contains("relative to the first field")));
+    }
+
+    #[test]
+    fn overlapping_fields_are_skipped() {
+        let fields = vec![field("whole", 0, DataType::U32), field("low", 2, DataType::U16), field("next", 4, DataType::U32)];
+        let layout = compute_layout(&fields);
+        assert_eq!(layout.members.len(), 2);
+        assert_eq!(layout.size, 8);
+        assert!(layout.warnings.iter().any(|w| w.contains("'low'") && w.contains("overlaps")));
+        assert!(!export_rust_struct(&fields, "Header").source.contains("pub low"));
+    }
+}
@@ -0,0 +1,79 @@
+use crate::schema::Field;
+
+/// Decoded value of one field, as written to a values export
+struct ValueRow {
+    offset: usize,
+    name: String,
+    type_name: String,
+    /// `None` if the field runs past the end of the data
+    value: Option<String>,
+    comment: String,
+}
+
+/// Decode every field against `data`, in schema order
+fn value_rows(fields: &[Field], data: &[u8]) -> Vec<ValueRow> {
+    fields
+        .iter()
+        .map(|field| ValueRow {
+            offset: field.offset,
+            name: field.identifier().into_owned(),
+            type_name: field.type_name(),
+            value: field.read_value(data),
+            comment: field.comment.clone(),
+        })
+        .collect()
+}
+
+/// Quote a CSV cell if it contains a separator, quote or line break
+fn csv_cell(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Quote and escape a JSON string
+fn json_string(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+/// Export the decoded field values as CSV, leaving the value empty for out-of-bounds fields
+/// Offsets are written as `0x1A0`, or `0x1a0` with `lowercase_hex`
+pub fn export_values_csv(fields: &[Field], data: &[u8], lowercase_hex: bool) -> String {
+    let mut csv = String::from("offset,name,type,value,comment\n");
+    for row in value_rows(fields, data) {
+        let offset = if lowercase_hex { format!("0x{:x}", row.offset) } else { format!("0x{:X}", row.offset) };
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            offset,
+            csv_cell(&row.name),
+            csv_cell(&row.type_name),
+            csv_cell(row.value.as_deref().unwrap_or("")),
+            csv_cell(&row.comment)
+        ));
+    }
+    csv
+}
+
+/// Export the decoded field values as a JSON array, with `null` values for out-of-bounds fields
+pub fn export_values_json(fields: &[Field], data: &[u8]) -> String {
+    let objects: Vec<String> = value_rows(fields, data)
+        .iter()
+        .map(|row| {
+            format!(
+                "  {{\"offset\": {}, \"name\": {}, \"type\": {}, \"value\": {}, \"comment\": {}}}",
+                row.offset,
+                json_string(&row.name),
+                json_string(&row.type_name),
+                row.value.as_deref().map_or_else(|| "null".to_string(), json_string),
+                json_string(&row.comment)
+            )
+        })
+        .collect();
+
+    if objects.is_empty() {
+        return "[]\n".to_string();
+    }
+    format!("[\n{}\n]\n", objects.join(",\n"))
+}
@@ -0,0 +1,701 @@
+use super::ImportError;
+use crate::schema::{DataType, Endianness, Field};
+use std::collections::HashMap;
+
+/// Largest array count accepted, matching the limit of the field dialogs
+const MAX_ARRAY_COUNT: usize = 1_000_000;
+
+/// Qualifiers that don't change the layout of a member
+const IGNORED_QUALIFIERS: &[&str] = &["const", "volatile", "register", "static"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(u64),
+    Punct(char),
+    /// `#pragma pack(...)`
+    Pack(PackDirective),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Ident(ident) => ident.clone(),
+            Token::Number(n) => n.to_string(),
+            Token::Punct(c) => c.to_string(),
+            Token::Pack(_) => "#pragma pack".to_string(),
+        }
+    }
+}
+
+/// Forms of `#pragma pack`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PackDirective {
+    /// `pack(N)`, or `pack()` to restore natural alignment
+    Set(Option<usize>),
+    /// `pack(push)` or `pack(push, N)`
+    Push(Option<usize>),
+    /// `pack(pop)`
+    Pop,
+}
+
+/// Layout of a C type
+#[derive(Debug, Clone)]
+enum CType {
+    Scalar(DataType),
+    /// `char`, whose arrays become strings
+    Char,
+    /// A struct defined earlier, with its members at offsets relative to its start
+    Struct { members: Vec<Field>, size: usize, align: usize },
+    /// An array typedef such as `typedef uint8_t guid_t[16];`
+    Array(Box<CType>, usize),
+}
+
+impl CType {
+    /// Natural alignment, capped by the packing in effect
+    fn align(&self, pack: Option<usize>) -> usize {
+        let natural = match self {
+            CType::Scalar(data_type) => data_type.size(),
+            CType::Char => 1,
+            CType::Struct { align, .. } => *align,
+            CType::Array(element, _) => return element.align(pack),
+        };
+        pack.map_or(natural, |pack| natural.min(pack)).max(1)
+    }
+}
+
+fn error(line: usize, message: impl Into<String>) -> ImportError {
+    ImportError { line, message: message.into() }
+}
+
+/// Map a fixed-width or common platform typedef to a data type
+fn known_typedef(name: &str) -> Option<DataType> {
+    Some(match name {
+        "uint8_t" | "u8" | "BYTE" | "UCHAR" => DataType::U8,
+        "uint16_t" | "u16" | "WORD" | "USHORT" => DataType::U16,
+        "uint32_t" | "u32" | "DWORD" | "UINT" | "ULONG" => DataType::U32,
+        "uint64_t" | "u64" | "QWORD" | "ULONGLONG" | "size_t" | "uintptr_t" => DataType::U64,
+        "int8_t" | "i8" | "s8" | "CHAR" => DataType::I8,
+        "int16_t" | "i16" | "s16" | "SHORT" => DataType::I16,
+        "int32_t" | "i32" | "s32" | "INT" | "LONG" | "BOOL" => DataType::I32,
+        "int64_t" | "i64" | "s64" | "LONGLONG" | "ssize_t" | "intptr_t" | "ptrdiff_t" => DataType::I64,
+        "bool" | "_Bool" => DataType::U8,
+        "float" => DataType::F32,
+        "double" => DataType::F64,
+        _ => return None,
+    })
+}
+
+/// Split the source into tokens tagged with their 1-based line
+/// Preprocessor directives other than `#pragma pack` are skipped
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, ImportError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    let mut line = 1;
+
+    while let Some(&c) = chars.peek() {
+        if c == '\n' {
+            line += 1;
+            chars.next();
+        } else if c.is_whitespace() {
+            chars.next();
+        } else if c == '/' {
+            chars.next();
+            match chars.next() {
+                Some('/') => {
+                    while chars.next_if(|&c| c != '\n').is_some() {}
+                }
+                Some('*') => {
+                    let start = line;
+                    let mut prev = '\0';
+                    loop {
+                        match chars.next() {
+                            Some('/') if prev == '*' => break,
+                            Some(c) => {
+                                if c == '\n' {
+                                    line += 1;
+                                }
+                                prev = c;
+                            }
+                            None => return Err(error(start, "unterminated block comment")),
+                        }
+                    }
+                }
+                _ => return Err(error(line, "unexpected '/'")),
+            }
+        } else if c == '#' {
+            let mut directive = String::new();
+            while let Some(c) = chars.next_if(|&c| c != '\n') {
+                directive.push(c);
+            }
+            if let Some(pack) = parse_pack_directive(&directive, line)? {
+                tokens.push((Token::Pack(pack), line));
+            }
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut ident = String::new();
+            while let Some(c) = chars.next_if(|&c| c.is_ascii_alphanumeric() || c == '_') {
+                ident.push(c);
+            }
+            tokens.push((Token::Ident(ident), line));
+        } else if c.is_ascii_digit() {
+            let mut literal = String::new();
+            while let Some(c) = chars.next_if(|&c| c.is_ascii_alphanumeric()) {
+                literal.push(c);
+            }
+            // Integer suffixes such as `16u` or `4UL` don't change the value
+            let digits = literal.trim_end_matches(['u', 'U', 'l', 'L']);
+            let value = match digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+                Some(hex) => u64::from_str_radix(hex, 16).ok(),
+                None => digits.parse().ok(),
+            };
+            let value = value.ok_or_else(|| error(line, format!("invalid number '{}'", literal)))?;
+            tokens.push((Token::Number(value), line));
+        } else {
+            tokens.push((Token::Punct(c), line));
+            chars.next();
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parse a `#pragma pack(...)` line; other directives (includes, guards, defines) yield `None`
+fn parse_pack_directive(directive: &str, line: usize) -> Result<Option<PackDirective>, ImportError> {
+    let directive = directive.split("//").next().unwrap_or_default();
+    let words: String = directive.split_whitespace().collect();
+    let Some(args) = words.strip_prefix("#pragmapack") else {
+        return Ok(None);
+    };
+    let args = args
+        .strip_prefix('(')
+        .and_then(|args| args.strip_suffix(')'))
+        .ok_or_else(|| error(line, "expected '#pragma pack(...)'"))?;
+
+    let parse_value = |value: &str| {
+        value
+            .parse::<usize>()
+            .ok()
+            .filter(|n| n.is_power_of_two())
+            .ok_or_else(|| error(line, format!("invalid packing '{}'", value)))
+    };
+    let parts: Vec<&str> = args.split(',').collect();
+    let pack = match parts.as_slice() {
+        [""] => PackDirective::Set(None),
+        ["push"] => PackDirective::Push(None),
+        ["push", value] => PackDirective::Push(Some(parse_value(value)?)),
+        ["pop"] => PackDirective::Pop,
+        [value] => PackDirective::Set(Some(parse_value(value)?)),
+        _ => return Err(error(line, format!("unsupported '#pragma pack({})'", args))),
+    };
+    Ok(Some(pack))
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    /// Typedef names and `struct Tag` names defined so far
+    types: HashMap<String, CType>,
+    /// Maximum alignment set by `#pragma pack`, and the values saved by `push`
+    pack: Option<usize>,
+    pack_stack: Vec<Option<usize>>,
+    endianness: Endianness,
+    /// Members of the most recently defined struct
+    last_struct: Option<Vec<Field>>,
+}
+
+impl Parser {
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or_else(|| self.tokens.last())
+            .map(|(_, line)| *line)
+            .unwrap_or(1)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn peek_ident(&self) -> Option<&str> {
+        match self.peek() {
+            Some(Token::Ident(ident)) => Some(ident),
+            _ => None,
+        }
+    }
+
+    fn next(&mut self) -> Result<Token, ImportError> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .map(|(token, _)| token.clone())
+            .ok_or_else(|| error(self.line(), "unexpected end of input"))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(&Token::Punct(c)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_ident(&mut self, ident: &str) -> bool {
+        if self.peek_ident() == Some(ident) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ImportError> {
+        let line = self.line();
+        match self.next()? {
+            Token::Punct(p) if p == c => Ok(()),
+            token => Err(error(line, format!("expected '{}', found '{}'", c, token.describe()))),
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, ImportError> {
+        let line = self.line();
+        match self.next()? {
+            Token::Ident(ident) => Ok(ident),
+            token => Err(error(line, format!("expected a name, found '{}'", token.describe()))),
+        }
+    }
+
+    fn parse(&mut self) -> Result<(), ImportError> {
+        while let Some(token) = self.peek().cloned() {
+            let line = self.line();
+            match token {
+                Token::Pack(pack) => {
+                    self.pos += 1;
+                    self.apply_pack(pack, line)?;
+                }
+                Token::Punct(';') => self.pos += 1,
+                Token::Ident(ident) if ident == "typedef" => {
+                    self.pos += 1;
+                    self.parse_typedef()?;
+                }
+                Token::Ident(ident) if ident == "struct" => {
+                    self.pos += 1;
+                    // Forward declarations (`struct Tag;`) don't define anything
+                    if matches!(self.tokens.get(self.pos + 1), Some((Token::Punct(';'), _))) {
+                        self.pos += 2;
+                        continue;
+                    }
+                    self.parse_struct_specifier()?;
+                    // A variable declared along with the struct has no place in the schema
+                    if self.peek_ident().is_some() {
+                        self.pos += 1;
+                    }
+                    self.expect(';')?;
+                }
+                Token::Ident(ident) if matches!(ident.as_str(), "union" | "enum") => {
+                    return Err(error(line, format!("{} definitions are not supported", ident)));
+                }
+                token => {
+                    return Err(error(line, format!("expected a struct definition, found '{}'", token.describe())));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_pack(&mut self, pack: PackDirective, line: usize) -> Result<(), ImportError> {
+        match pack {
+            PackDirective::Set(value) => self.pack = value,
+            PackDirective::Push(value) => {
+                self.pack_stack.push(self.pack);
+                if value.is_some() {
+                    self.pack = value;
+                }
+            }
+            PackDirective::Pop => {
+                self.pack = self
+                    .pack_stack
+                    .pop()
+                    .ok_or_else(|| error(line, "'#pragma pack(pop)' without a matching push"))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// `typedef struct [Tag] { members } Name;` or `typedef type Name;`
+    fn parse_typedef(&mut self) -> Result<(), ImportError> {
+        let ctype = self.parse_type()?;
+        loop {
+            let line = self.line();
+            if self.eat('*') {
+                return Err(error(line, "pointer typedefs are not supported"));
+            }
+            let name = self.ident()?;
+            let count = self.parse_dimensions()?;
+            let ctype = if count == 1 { ctype.clone() } else { CType::Array(Box::new(ctype.clone()), count) };
+            self.types.insert(name, ctype);
+            if !self.eat(',') {
+                break;
+            }
+        }
+        self.expect(';')
+    }
+
+    /// `struct Tag`, `struct [Tag] { members }`; the `struct` keyword was already consumed
+    fn parse_struct_specifier(&mut self) -> Result<CType, ImportError> {
+        let line = self.line();
+        let tag = match self.peek() {
+            Some(Token::Ident(_)) => Some(self.ident()?),
+            _ => None,
+        };
+
+        if !self.eat('{') {
+            let tag = tag.ok_or_else(|| error(line, "expected a struct name or '{'"))?;
+            return self
+                .types
+                .get(&format!("struct {}", tag))
+                .cloned()
+                .ok_or_else(|| error(line, format!("struct '{}' is used before it's defined", tag)));
+        }
+
+        let mut members = Vec::new();
+        while !self.eat('}') {
+            if let Some(Token::Pack(pack)) = self.peek().cloned() {
+                let line = self.line();
+                self.pos += 1;
+                self.apply_pack(pack, line)?;
+                continue;
+            }
+            members.extend(self.parse_member()?);
+        }
+
+        let ctype = self.layout(members, line)?;
+        if let CType::Struct { members, .. } = &ctype {
+            self.last_struct = Some(members.clone());
+        }
+        if let Some(tag) = tag {
+            self.types.insert(format!("struct {}", tag), ctype.clone());
+        }
+        Ok(ctype)
+    }
+
+    /// Parse a type: qualifiers, `struct` types, builtin keyword combinations or typedef names
+    fn parse_type(&mut self) -> Result<CType, ImportError> {
+        while self.peek_ident().is_some_and(|ident| IGNORED_QUALIFIERS.contains(&ident)) {
+            self.pos += 1;
+        }
+
+        let line = self.line();
+        if self.eat_ident("struct") {
+            return self.parse_struct_specifier();
+        }
+        if let Some(keyword @ ("union" | "enum")) = self.peek_ident() {
+            return Err(error(line, format!("{} types are not supported", keyword)));
+        }
+
+        // Builtin types are combinations of keywords such as `unsigned long long int`
+        let mut keywords = Vec::new();
+        while let Some(ident) = self.peek_ident() {
+            if matches!(ident, "signed" | "unsigned" | "short" | "long" | "int" | "char" | "float" | "double" | "void") {
+                keywords.push(ident.to_string());
+                self.pos += 1;
+            } else if IGNORED_QUALIFIERS.contains(&ident) {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        if !keywords.is_empty() {
+            return builtin_type(&keywords).ok_or_else(|| error(line, format!("unsupported type '{}'", keywords.join(" "))));
+        }
+
+        let name = self.ident()?;
+        if let Some(ctype) = self.types.get(&name) {
+            return Ok(ctype.clone());
+        }
+        known_typedef(&name)
+            .map(CType::Scalar)
+            .ok_or_else(|| error(line, format!("unknown type '{}'", name)))
+    }
+
+    /// Parse any number of `[N]`, returning the total element count
+    fn parse_dimensions(&mut self) -> Result<usize, ImportError> {
+        let mut count = 1usize;
+        while self.eat('[') {
+            let line = self.line();
+            let n = match self.next()? {
+                Token::Number(n) => usize::try_from(n).map_err(|_| error(line, "number too large"))?,
+                token => {
+                    return Err(error(line, format!("expected a constant array size, found '{}'", token.describe())));
+                }
+            };
+            self.expect(']')?;
+            if n == 0 {
+                return Err(error(line, "array size must be at least 1"));
+            }
+            count = count
+                .checked_mul(n)
+                .filter(|&count| count <= MAX_ARRAY_COUNT)
+                .ok_or_else(|| error(line, format!("array size is limited to {} elements", MAX_ARRAY_COUNT)))?;
+        }
+        Ok(count)
+    }
+
+    /// Parse `type name[[N]...] [, name...];` inside a struct body
+    fn parse_member(&mut self) -> Result<Vec<(String, CType, usize, usize)>, ImportError> {
+        let ctype = self.parse_type()?;
+        let mut declarators = Vec::new();
+        loop {
+            let line = self.line();
+            if self.eat('*') {
+                return Err(error(line, "pointers are not supported"));
+            }
+            if self.eat('(') {
+                return Err(error(line, "function pointers are not supported"));
+            }
+            let name = self.ident()?;
+            let count = self.parse_dimensions()?;
+            if self.eat(':') {
+                return Err(error(line, "bit fields are not supported"));
+            }
+            declarators.push((name, ctype.clone(), count, line));
+            if !self.eat(',') {
+                break;
+            }
+        }
+        self.expect(';')?;
+        Ok(declarators)
+    }
+
+    /// Place members one after another with natural alignment, as a C compiler would
+    fn layout(&self, members: Vec<(String, CType, usize, usize)>, line: usize) -> Result<CType, ImportError> {
+        if members.is_empty() {
+            return Err(error(line, "struct has no members"));
+        }
+
+        let mut fields = Vec::new();
+        let mut cursor = 0usize;
+        let mut struct_align = 1;
+        for (name, mut ctype, mut count, member_line) in members {
+            let too_large = || error(member_line, format!("'{}' extends past the largest possible offset", name));
+            // Arrays of array typedefs are flattened into one array of the element type
+            while let CType::Array(element, n) = ctype {
+                count = count
+                    .checked_mul(n)
+                    .filter(|&count| count <= MAX_ARRAY_COUNT)
+                    .ok_or_else(|| error(line, format!("array size is limited to {} elements", MAX_ARRAY_COUNT)))?;
+                ctype = *element;
+            }
+            let align = ctype.align(self.pack);
+            struct_align = struct_align.max(align);
+            let offset = cursor.checked_next_multiple_of(align).ok_or_else(too_large)?;
+
+            let end = match &ctype {
+                CType::Char => Some(count),
+                CType::Scalar(data_type) => data_type.size().checked_mul(count),
+                CType::Struct { size, .. } => size.checked_mul(count),
+                CType::Array(..) => unreachable!("arrays are flattened above"),
+            };
+            let end = end.and_then(|size| offset.checked_add(size)).ok_or_else(too_large)?;
+
+            let mut field = match &ctype {
+                // Character arrays are almost always text
+                CType::Char if count > 1 => Field::new(name, offset, DataType::FixedString { len: count }),
+                CType::Char => Field::new(name, offset, DataType::U8),
+                CType::Scalar(data_type) => Field::new(name, offset, *data_type),
+                CType::Struct { members, size, .. } => {
+                    let mut field = Field::new(name, offset, DataType::Struct { size: *size });
+                    field.children = members.clone();
+                    field
+                }
+                CType::Array(..) => unreachable!("arrays are flattened above"),
+            };
+            if !matches!(ctype, CType::Char) || count == 1 {
+                field.count = count;
+            }
+            field.endianness = self.endianness;
+            cursor = end;
+            fields.push(field);
+        }
+
+        let size = cursor
+            .checked_next_multiple_of(struct_align)
+            .ok_or_else(|| error(line, "struct is larger than the largest possible offset"))?;
+        Ok(CType::Struct {
+            members: fields,
+            size,
+            align: struct_align,
+        })
+    }
+}
+
+/// Map a combination of builtin type keywords to a type, assuming an LP64 platform (`long` is 64-bit)
+fn builtin_type(keywords: &[String]) -> Option<CType> {
+    let count = |keyword: &str| keywords.iter().filter(|k| *k == keyword).count();
+    let unsigned = count("unsigned") > 0;
+    if count("signed") + count("unsigned") > 1 || count("void") > 0 {
+        return None;
+    }
+
+    let data_type = match (count("char"), count("short"), count("long"), count("float"), count("double")) {
+        (1, 0, 0, 0, 0) if count("int") == 0 => {
+            if count("signed") > 0 {
+                DataType::I8
+            } else if unsigned {
+                DataType::U8
+            } else {
+                return Some(CType::Char);
+            }
+        }
+        (0, 0, 0, 1, 0) if keywords.len() == 1 => DataType::F32,
+        (0, 0, 0, 0, 1) if keywords.len() == 1 => DataType::F64,
+        (0, 1, 0, 0, 0) => if unsigned { DataType::U16 } else { DataType::I16 },
+        (0, 0, 0, 0, 0) => if unsigned { DataType::U32 } else { DataType::I32 },
+        (0, 0, 1 | 2, 0, 0) => if unsigned { DataType::U64 } else { DataType::I64 },
+        _ => return None,
+    };
+    if count("int") > 1 {
+        return None;
+    }
+    Some(CType::Scalar(data_type))
+}
+
+/// Generate fields from a C struct definition, laid out with natural alignment and padding
+///
+/// Supports `struct` and `typedef struct` definitions, builtin integer and float types (with
+/// `long` taken as 64-bit), fixed-width and common Windows typedefs, simple typedefs, arrays
+/// (`char` arrays become strings), members of previously defined struct types, and
+/// `#pragma pack`. The members of the last struct defined in `source` become the fields,
+/// starting at offset 0. Pointers, unions, enums and bit fields are reported as errors.
+pub fn parse_c_struct(source: &str, endianness: Endianness) -> Result<Vec<Field>, ImportError> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        pos: 0,
+        types: HashMap::new(),
+        pack: None,
+        pack_stack: Vec::new(),
+        endianness,
+        last_struct: None,
+    };
+    parser.parse()?;
+    let line = parser.line();
+    parser.last_struct.ok_or_else(|| error(line, "no struct definition found"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Get the name, offset and type of each field
+    fn layout(source: &str) -> Vec<(String, usize, DataType, usize)> {
+        parse_c_struct(source, Endianness::Little)
+            .unwrap()
+            .iter()
+            .map(|f| (f.name.clone(), f.offset, f.data_type, f.count))
+            .collect()
+    }
+
+    fn field(name: &str, offset: usize, data_type: DataType, count: usize) -> (String, usize, DataType, usize) {
+        (name.to_string(), offset, data_type, count)
+    }
+
+    #[test]
+    fn members_are_naturally_aligned() {
+        let fields = layout("struct Header { uint8_t tag; uint32_t len; short s; double d; };");
+        assert_eq!(
+            fields,
+            vec![
+                field("tag", 0, DataType::U8, 1),
+                field("len", 4, DataType::U32, 1),
+                field("s", 8, DataType::I16, 1),
+                field("d", 16, DataType::F64, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn pragma_pack_limits_alignment() {
+        let source = "
+            #pragma pack(push, 1)
+            struct Packed { uint8_t tag; uint32_t len; };
+            #pragma pack(pop)
+            struct Natural { uint8_t tag; uint32_t len; };
+            struct Both { struct Packed p; struct Natural n; };
+        ";
+        let fields = parse_c_struct(source, Endianness::Little).unwrap();
+        assert_eq!(fields[0].data_type, DataType::Struct { size: 5 });
+        assert_eq!(fields[0].children[1].offset, 1);
+        assert_eq!((fields[1].offset, fields[1].data_type), (8, DataType::Struct { size: 8 }));
+
+        let error = parse_c_struct("#pragma pack(pop)\nstruct A { int a; };", Endianness::Little).unwrap_err();
+        assert_eq!(error.message, "'#pragma pack(pop)' without a matching push");
+    }
+
+    #[test]
+    fn typedefs_are_resolved() {
+        let source = "
+            typedef unsigned short word_t;
+            typedef uint8_t guid_t[16];
+            typedef struct { word_t a; guid_t ids[2]; DWORD flags; } Entry;
+            struct Table { Entry first; };
+        ";
+        let fields = parse_c_struct(source, Endianness::Big).unwrap();
+        let entry = &fields[0];
+        assert_eq!(entry.data_type, DataType::Struct { size: 40 });
+        let members: Vec<_> = entry.children.iter().map(|f| (f.offset, f.data_type, f.count)).collect();
+        assert_eq!(members, vec![(0, DataType::U16, 1), (2, DataType::U8, 32), (36, DataType::U32, 1)]);
+        assert!(entry.children.iter().all(|f| f.endianness == Endianness::Big));
+    }
+
+    #[test]
+    fn nested_struct_arrays_keep_the_element_layout() {
+        let fields = layout("struct Point { int x; int y; };\nstruct Shape { char kind; struct Point pts[3]; };");
+        assert_eq!(fields, vec![field("kind", 0, DataType::U8, 1), field("pts", 4, DataType::Struct { size: 8 }, 3)]);
+    }
+
+    #[test]
+    fn char_arrays_become_strings() {
+        let fields = layout("struct Name { char name[12]; signed char delta; unsigned char bytes[2]; };");
+        assert_eq!(
+            fields,
+            vec![
+                field("name", 0, DataType::FixedString { len: 12 }, 1),
+                field("delta", 12, DataType::I8, 1),
+                field("bytes", 13, DataType::U8, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn errors_report_their_line() {
+        let error = |source| parse_c_struct(source, Endianness::Little).unwrap_err();
+
+        let pointer = error("struct A {\n    int a;\n    char *name;\n};");
+        assert_eq!((pointer.line, pointer.message.as_str()), (3, "pointers are not supported"));
+
+        let unknown = error("struct A {\n    widget_t w;\n};");
+        assert_eq!((unknown.line, unknown.message.as_str()), (2, "unknown type 'widget_t'"));
+
+        assert_eq!(error("struct A { int a : 3; };").message, "bit fields are not supported");
+        assert_eq!(error("union U { int a; };").message, "union definitions are not supported");
+        assert_eq!(error("typedef int x;").message, "no struct definition found");
+    }
+
+    #[test]
+    fn structs_past_the_largest_offset_are_rejected() {
+        let source = "
+            struct A { uint64_t x[1000000]; };
+            struct B { struct A a[1000000]; };
+            struct C {
+                struct B p[1000000];
+                struct B q[1000000];
+                struct B r[1000000];
+            };
+        ";
+        let error = parse_c_struct(source, Endianness::Little).unwrap_err();
+        assert_eq!(error.line, 7);
+        assert_eq!(error.message, "'r' extends past the largest possible offset");
+    }
+}
@@ -0,0 +1,543 @@
+use super::ImportError;
+use crate::schema::{DataType, Endianness, Field};
+use std::collections::HashMap;
+
+/// Keywords of ImHex constructs this importer deliberately doesn't support
+const UNSUPPORTED_KEYWORDS: &[&str] = &[
+    "if", "else", "while", "for", "fn", "return", "break", "continue", "match", "union", "enum",
+    "bitfield", "using", "namespace", "import", "auto", "str", "const",
+];
+
+/// Largest array count accepted, matching the limit of the field dialogs
+const MAX_ARRAY_COUNT: usize = 1_000_000;
+
+/// Most fields and struct elements one pattern may place, since nested arrays multiply
+const MAX_PLACED: usize = 100_000;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(u64),
+    Punct(char),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Ident(ident) => ident.clone(),
+            Token::Number(n) => n.to_string(),
+            Token::Punct(c) => c.to_string(),
+        }
+    }
+}
+
+/// Type of a variable or struct member
+#[derive(Debug, Clone)]
+enum TypeRef {
+    Builtin(DataType),
+    Struct(String),
+}
+
+/// A member declared inside a struct
+#[derive(Debug, Clone)]
+struct Member {
+    name: String,
+    type_ref: TypeRef,
+    count: usize,
+    endianness: Option<Endianness>,
+    /// `padding[N]` skips bytes without producing a field
+    is_padding: bool,
+    line: usize,
+}
+
+fn error(line: usize, message: impl Into<String>) -> ImportError {
+    ImportError { line, message: message.into() }
+}
+
+/// Map an ImHex built-in type name to a data type
+fn builtin_type(name: &str) -> Option<DataType> {
+    Some(match name {
+        "u8" | "char" | "bool" => DataType::U8,
+        "u16" => DataType::U16,
+        "u32" => DataType::U32,
+        "u64" => DataType::U64,
+        "s8" => DataType::I8,
+        "s16" => DataType::I16,
+        "s32" => DataType::I32,
+        "s64" => DataType::I64,
+        "float" => DataType::F32,
+        "double" => DataType::F64,
+        _ => return None,
+    })
+}
+
+/// Split the source into tokens tagged with their 1-based line
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, ImportError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    let mut line = 1;
+
+    while let Some(&c) = chars.peek() {
+        if c == '\n' {
+            line += 1;
+            chars.next();
+        } else if c.is_whitespace() {
+            chars.next();
+        } else if c == '/' {
+            chars.next();
+            match chars.next() {
+                Some('/') => {
+                    while chars.next_if(|&c| c != '\n').is_some() {}
+                }
+                Some('*') => {
+                    let start = line;
+                    let mut prev = '\0';
+                    loop {
+                        match chars.next() {
+                            Some('/') if prev == '*' => break,
+                            Some(c) => {
+                                if c == '\n' {
+                                    line += 1;
+                                }
+                                prev = c;
+                            }
+                            None => return Err(error(start, "unterminated block comment")),
+                        }
+                    }
+                }
+                _ => return Err(error(line, "unexpected '/'")),
+            }
+        } else if c == '#' {
+            // Preprocessor directive: only `#pragma` is accepted, and handled by the parser
+            let mut directive = String::new();
+            while let Some(c) = chars.next_if(|&c| c != '\n') {
+                directive.push(c);
+            }
+            let words: Vec<&str> = directive.split_whitespace().collect();
+            match words.as_slice() {
+                ["#pragma", rest @ ..] => {
+                    tokens.push((Token::Punct('#'), line));
+                    tokens.extend(rest.iter().map(|w| (Token::Ident(w.to_string()), line)));
+                    tokens.push((Token::Punct(';'), line));
+                }
+                _ => return Err(error(line, format!("unsupported directive '{}'", directive.trim()))),
+            }
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut ident = String::new();
+            while let Some(c) = chars.next_if(|&c| c.is_ascii_alphanumeric() || c == '_') {
+                ident.push(c);
+            }
+            tokens.push((Token::Ident(ident), line));
+        } else if c.is_ascii_digit() {
+            let mut literal = String::new();
+            while let Some(c) = chars.next_if(|&c| c.is_ascii_alphanumeric() || c == '\'') {
+                // ImHex allows `'` as a digit separator
+                if c != '\'' {
+                    literal.push(c);
+                }
+            }
+            let value = match literal.strip_prefix("0x").or_else(|| literal.strip_prefix("0X")) {
+                Some(hex) => u64::from_str_radix(hex, 16).ok(),
+                None => literal.parse().ok(),
+            };
+            let value = value.ok_or_else(|| error(line, format!("invalid number '{}'", literal)))?;
+            tokens.push((Token::Number(value), line));
+        } else {
+            tokens.push((Token::Punct(c), line));
+            chars.next();
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    structs: HashMap<String, Vec<Member>>,
+    endianness: Endianness,
+    fields: Vec<Field>,
+    /// Number of fields and struct elements placed so far, limited to `MAX_PLACED`
+    placed: usize,
+}
+
+impl Parser {
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or_else(|| self.tokens.last())
+            .map(|(_, line)| *line)
+            .unwrap_or(1)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Result<Token, ImportError> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .map(|(token, _)| token.clone())
+            .ok_or_else(|| error(self.line(), "unexpected end of input"))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(&Token::Punct(c)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ImportError> {
+        let line = self.line();
+        match self.next()? {
+            Token::Punct(p) if p == c => Ok(()),
+            token => Err(error(line, format!("expected '{}', found '{}'", c, token.describe()))),
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, ImportError> {
+        let line = self.line();
+        match self.next()? {
+            Token::Ident(ident) if UNSUPPORTED_KEYWORDS.contains(&ident.as_str()) => {
+                Err(error(line, format!("'{}' is not supported", ident)))
+            }
+            Token::Ident(ident) => Ok(ident),
+            token => Err(error(line, format!("expected a name, found '{}'", token.describe()))),
+        }
+    }
+
+    fn number(&mut self) -> Result<usize, ImportError> {
+        let line = self.line();
+        match self.next()? {
+            Token::Number(n) => usize::try_from(n).map_err(|_| error(line, "number too large")),
+            token => Err(error(
+                line,
+                format!("expected a constant number, found '{}'", token.describe()),
+            )),
+        }
+    }
+
+    fn parse(&mut self) -> Result<(), ImportError> {
+        while self.peek().is_some() {
+            if self.eat('#') {
+                self.parse_pragma()?;
+            } else if self.peek() == Some(&Token::Ident("struct".to_string())) {
+                self.pos += 1;
+                self.parse_struct()?;
+            } else {
+                self.parse_placement()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// `#pragma endian little|big`; other pragmas are ignored
+    fn parse_pragma(&mut self) -> Result<(), ImportError> {
+        let line = self.line();
+        let name = self.ident()?;
+        if name == "endian" {
+            self.endianness = match self.ident()?.as_str() {
+                "little" => Endianness::Little,
+                "big" => Endianness::Big,
+                other => return Err(error(line, format!("unsupported endianness '{}'", other))),
+            };
+        }
+        while !self.eat(';') {
+            self.next()?;
+        }
+        Ok(())
+    }
+
+    /// `struct Name { members };`
+    fn parse_struct(&mut self) -> Result<(), ImportError> {
+        let line = self.line();
+        let name = self.ident()?;
+        if self.eat(':') {
+            return Err(error(line, "struct inheritance is not supported"));
+        }
+        self.expect('{')?;
+
+        let mut members = Vec::new();
+        while !self.eat('}') {
+            members.push(self.parse_member()?);
+        }
+        self.expect(';')?;
+
+        self.structs.insert(name, members);
+        Ok(())
+    }
+
+    /// Parse the optional endianness prefix and the type of a declaration
+    fn parse_type(&mut self) -> Result<(Option<Endianness>, String, usize), ImportError> {
+        let mut endianness = None;
+        let mut line = self.line();
+        let mut type_name = self.ident()?;
+        if matches!(type_name.as_str(), "le" | "be") {
+            endianness = Some(if type_name == "le" { Endianness::Little } else { Endianness::Big });
+            line = self.line();
+            type_name = self.ident()?;
+        }
+        Ok((endianness, type_name, line))
+    }
+
+    fn resolve_type(&self, type_name: &str, line: usize) -> Result<TypeRef, ImportError> {
+        if let Some(data_type) = builtin_type(type_name) {
+            Ok(TypeRef::Builtin(data_type))
+        } else if self.structs.contains_key(type_name) {
+            Ok(TypeRef::Struct(type_name.to_string()))
+        } else {
+            Err(error(line, format!("unknown type '{}'", type_name)))
+        }
+    }
+
+    /// Parse `[N]` if present
+    fn parse_count(&mut self) -> Result<usize, ImportError> {
+        if !self.eat('[') {
+            return Ok(1);
+        }
+        let line = self.line();
+        let count = self.number()?;
+        self.expect(']')?;
+        if count == 0 {
+            return Err(error(line, "array count must be at least 1"));
+        }
+        if count > MAX_ARRAY_COUNT {
+            return Err(error(line, format!("array count is limited to {}", MAX_ARRAY_COUNT)));
+        }
+        Ok(count)
+    }
+
+    /// Reject pointers, attributes and placements that this importer doesn't handle
+    fn reject_unsupported(&self) -> Result<(), ImportError> {
+        match self.peek() {
+            Some(Token::Punct('*')) => Err(error(self.line(), "pointers are not supported")),
+            Some(Token::Punct('[')) if self.tokens.get(self.pos + 1).map(|(t, _)| t) == Some(&Token::Punct('[')) => {
+                Err(error(self.line(), "attributes are not supported"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// `[le|be] type name[[N]];` or `padding[N];` inside a struct
+    fn parse_member(&mut self) -> Result<Member, ImportError> {
+        let (endianness, type_name, line) = self.parse_type()?;
+
+        if type_name == "padding" {
+            let count = self.parse_count()?;
+            self.expect(';')?;
+            return Ok(Member {
+                name: String::new(),
+                type_ref: TypeRef::Builtin(DataType::U8),
+                count,
+                endianness: None,
+                is_padding: true,
+                line,
+            });
+        }
+
+        let type_ref = self.resolve_type(&type_name, line)?;
+        self.reject_unsupported()?;
+        let name = self.ident()?;
+        let count = self.parse_count()?;
+        self.reject_unsupported()?;
+        if self.peek() == Some(&Token::Punct('@')) {
+            return Err(error(self.line(), "placements inside structs are not supported"));
+        }
+        self.expect(';')?;
+
+        Ok(Member {
+            name,
+            type_ref,
+            count,
+            endianness,
+            is_padding: false,
+            line,
+        })
+    }
+
+    /// Top-level `[le|be] type name[[N]] @ offset;`
+    fn parse_placement(&mut self) -> Result<(), ImportError> {
+        let (endianness, type_name, line) = self.parse_type()?;
+        let type_ref = self.resolve_type(&type_name, line)?;
+        self.reject_unsupported()?;
+        let name = self.ident()?;
+        let count = self.parse_count()?;
+        self.reject_unsupported()?;
+        if !self.eat('@') {
+            return Err(error(line, format!("top-level variable '{}' needs an @ placement", name)));
+        }
+        let offset = self.number()?;
+        self.reject_unsupported()?;
+        self.expect(';')?;
+
+        let endianness = endianness.unwrap_or(self.endianness);
+        self.place(&name, &type_ref, count, offset, endianness, line)?;
+        Ok(())
+    }
+
+    /// Emit the fields of a variable placed at `offset`, returning its size
+    fn place(
+        &mut self,
+        name: &str,
+        type_ref: &TypeRef,
+        count: usize,
+        offset: usize,
+        endianness: Endianness,
+        line: usize,
+    ) -> Result<usize, ImportError> {
+        let too_large = || error(line, format!("'{}' extends past the largest possible offset", name));
+        match type_ref {
+            TypeRef::Builtin(data_type) => {
+                self.count_placed(line)?;
+                let size = data_type.size().checked_mul(count).ok_or_else(too_large)?;
+                offset.checked_add(size).ok_or_else(too_large)?;
+                let mut field = Field::new(name.to_string(), offset, *data_type);
+                field.count = count;
+                field.endianness = endianness;
+                self.fields.push(field);
+                Ok(size)
+            }
+            TypeRef::Struct(struct_name) => {
+                let members = self.structs[struct_name].clone();
+                let mut size = 0;
+                for i in 0..count {
+                    self.count_placed(line)?;
+                    let prefix = if count > 1 { format!("{}[{}]", name, i) } else { name.to_string() };
+                    let mut cursor = offset.checked_add(size).ok_or_else(too_large)?;
+                    for member in &members {
+                        let member_size = if member.is_padding {
+                            member.count
+                        } else {
+                            self.place(
+                                &format!("{}.{}", prefix, member.name),
+                                &member.type_ref,
+                                member.count,
+                                cursor,
+                                member.endianness.unwrap_or(endianness),
+                                member.line,
+                            )?
+                        };
+                        cursor = cursor.checked_add(member_size).ok_or_else(too_large)?;
+                    }
+                    size = cursor - offset;
+                }
+                if size == 0 {
+                    return Err(error(line, format!("struct '{}' is empty", struct_name)));
+                }
+                Ok(size)
+            }
+        }
+    }
+
+    /// Count one more placed field or struct element, failing once there are too many
+    fn count_placed(&mut self, line: usize) -> Result<(), ImportError> {
+        self.placed += 1;
+        if self.placed > MAX_PLACED {
+            return Err(error(line, format!("the pattern places more than {} fields", MAX_PLACED)));
+        }
+        Ok(())
+    }
+}
+
+/// Import the fields placed by a pattern written in a subset of the ImHex pattern language
+///
+/// Supports `struct` definitions (flattened into `var.member` fields), built-in types
+/// (`u8`..`u64`, `s8`..`s64`, `float`, `double`, `char`, `bool`), `le`/`be` prefixes,
+/// `#pragma endian`, constant-size arrays, `padding[N]`, and top-level `@` placements.
+/// Other constructs such as pointers, conditionals and functions are reported as errors.
+pub fn parse_imhex_pattern(source: &str) -> Result<Vec<Field>, ImportError> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        pos: 0,
+        structs: HashMap::new(),
+        endianness: Endianness::Little,
+        fields: Vec::new(),
+        placed: 0,
+    };
+    parser.parse()?;
+    Ok(parser.fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Get the name, offset, type and count of each field
+    fn summary(fields: &[Field]) -> Vec<(String, usize, DataType, usize)> {
+        fields.iter().map(|f| (f.name.clone(), f.offset, f.data_type, f.count)).collect()
+    }
+
+    #[test]
+    fn places_builtin_variables() {
+        let fields = parse_imhex_pattern("u32 magic @ 0x00;\nbe u16 version @ 4; // trailing\nfloat scale[2] @ 8;").unwrap();
+        assert_eq!(
+            summary(&fields),
+            vec![
+                ("magic".to_string(), 0, DataType::U32, 1),
+                ("version".to_string(), 4, DataType::U16, 1),
+                ("scale".to_string(), 8, DataType::F32, 2),
+            ]
+        );
+        assert_eq!(fields[0].endianness, Endianness::Little);
+        assert_eq!(fields[1].endianness, Endianness::Big);
+    }
+
+    #[test]
+    fn flattens_structs_with_padding_and_arrays() {
+        let source = "
+            #pragma endian big
+            struct Entry { u8 kind; padding[1]; le u16 len; };
+            struct Header { u32 magic; Entry entries[2]; };
+            Header header @ 0x10;
+        ";
+        let fields = parse_imhex_pattern(source).unwrap();
+        assert_eq!(
+            summary(&fields),
+            vec![
+                ("header.magic".to_string(), 0x10, DataType::U32, 1),
+                ("header.entries[0].kind".to_string(), 0x14, DataType::U8, 1),
+                ("header.entries[0].len".to_string(), 0x16, DataType::U16, 1),
+                ("header.entries[1].kind".to_string(), 0x18, DataType::U8, 1),
+                ("header.entries[1].len".to_string(), 0x1A, DataType::U16, 1),
+            ]
+        );
+        assert_eq!(fields[0].endianness, Endianness::Big);
+        assert_eq!(fields[2].endianness, Endianness::Little);
+    }
+
+    #[test]
+    fn reports_unsupported_constructs_with_their_line() {
+        let error = |source| parse_imhex_pattern(source).unwrap_err();
+        let pointer = error("u8 a @ 0;\nu32 *p : u32 @ 4;");
+        assert_eq!((pointer.line, pointer.message.as_str()), (2, "pointers are not supported"));
+        assert_eq!(error("u8 a;").message, "top-level variable 'a' needs an @ placement");
+        assert_eq!(error("u24 a @ 0;").message, "unknown type 'u24'");
+        assert_eq!(error("u8 a[0] @ 0;").message, "array count must be at least 1");
+        assert_eq!(error("#include <std.pat>").message, "unsupported directive '#include <std.pat>'");
+        assert_eq!(error("/* open").message, "unterminated block comment");
+    }
+
+    #[test]
+    fn nested_arrays_are_limited() {
+        let source = "
+            struct Inner { padding[1]; };
+            struct Outer { Inner inner[1000000]; };
+            Outer outer[1000000] @ 0;
+        ";
+        let error = parse_imhex_pattern(source).unwrap_err();
+        assert_eq!(error.message, format!("the pattern places more than {} fields", MAX_PLACED));
+    }
+
+    #[test]
+    fn offsets_past_the_address_space_are_rejected() {
+        let source = format!("u64 a[2] @ {};", usize::MAX - 8);
+        let error = parse_imhex_pattern(&source).unwrap_err();
+        assert_eq!(error.message, "'a' extends past the largest possible offset");
+    }
+}
@@ -0,0 +1,24 @@
+pub mod c_struct;
+pub mod imhex;
+pub mod text;
+
+pub use c_struct::parse_c_struct;
+pub use imhex::parse_imhex_pattern;
+pub use text::parse_field_text;
+
+use std::fmt;
+
+/// An error produced while importing fields, with the 1-based line where it occurred
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ImportError {}
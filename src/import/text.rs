@@ -0,0 +1,222 @@
+use super::ImportError;
+use crate::schema::{DataType, Endianness, Field};
+use std::fmt;
+
+/// Error produced when a field definition line can't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    MissingName,
+    MissingType,
+    UnknownType(String),
+    InvalidOffset(String),
+    InvalidCount(String),
+    UnexpectedToken(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::MissingName => write!(f, "missing field name"),
+            ParseError::MissingType => write!(f, "missing field type"),
+            ParseError::UnknownType(t) => write!(f, "unknown type '{}'", t),
+            ParseError::InvalidOffset(o) => write!(f, "invalid offset '{}'", o),
+            ParseError::InvalidCount(c) => write!(f, "invalid array count '{}'", c),
+            ParseError::UnexpectedToken(t) => write!(f, "unexpected '{}'", t),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parse an offset literal (hex with `0x` prefix, or decimal)
+fn parse_offset(input: &str) -> Option<usize> {
+    match input.strip_prefix("0x").or_else(|| input.strip_prefix("0X")) {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => input.parse().ok(),
+    }
+}
+
+/// Parse a single field definition line
+///
+/// Syntax: `name[:] type[[count]] [le|be] [@offset] [# comment]`, e.g.
+/// `magic: u32 @0x0 # file signature` or `version u16`. Without `@offset` the
+/// field is placed at `next_offset`, and without `le` or `be` it is read in `endianness`.
+pub fn parse_field_line(line: &str, next_offset: usize, endianness: Endianness) -> Result<Field, ParseError> {
+    let (definition, comment) = match line.split_once('#') {
+        Some((definition, comment)) => (definition, comment.trim()),
+        None => (line, ""),
+    };
+
+    // Split off the placement so `@ 0x10` and `@0x10` both work
+    let (definition, placement) = match definition.split_once('@') {
+        Some((definition, placement)) => (definition, Some(placement.trim())),
+        None => (definition, None),
+    };
+
+    let mut tokens = definition.split_whitespace();
+
+    let name = tokens.next().ok_or(ParseError::MissingName)?;
+    // Allow `name: type` as well as `name : type` and `name type`
+    let (name, type_token) = match name.split_once(':') {
+        Some((name, rest)) if !rest.is_empty() => (name, Some(rest)),
+        Some((name, _)) => (name, None),
+        None => (name, None),
+    };
+    if name.is_empty() {
+        return Err(ParseError::MissingName);
+    }
+
+    let mut type_token = match type_token {
+        Some(t) => t,
+        None => match tokens.next().ok_or(ParseError::MissingType)? {
+            ":" => tokens.next().ok_or(ParseError::MissingType)?,
+            t => t,
+        },
+    };
+
+    // Optional array suffix: `u32[4]`
+    let mut count = 1;
+    if let Some((base, rest)) = type_token.split_once('[') {
+        let count_str = rest.strip_suffix(']').ok_or_else(|| ParseError::InvalidCount(rest.to_string()))?;
+        count = count_str
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|&c| c > 0)
+            .ok_or_else(|| ParseError::InvalidCount(count_str.to_string()))?;
+        type_token = base;
+    }
+
+    let data_type = DataType::from_name(type_token).ok_or_else(|| ParseError::UnknownType(type_token.to_string()))?;
+
+    let mut endianness = endianness;
+    for token in tokens {
+        match token.to_ascii_lowercase().as_str() {
+            "le" => endianness = Endianness::Little,
+            "be" => endianness = Endianness::Big,
+            _ => return Err(ParseError::UnexpectedToken(token.to_string())),
+        }
+    }
+
+    let offset = match placement {
+        Some(placement) => parse_offset(placement).ok_or_else(|| ParseError::InvalidOffset(placement.to_string()))?,
+        None => next_offset,
+    };
+
+    let mut field = Field::new(name.to_string(), offset, data_type);
+    field.count = count;
+    field.endianness = endianness;
+    field.comment = comment.to_string();
+    Ok(field)
+}
+
+/// Parse a block of field definitions, one per line
+///
+/// Blank lines and lines starting with `#` or `//` are skipped. Fields without an explicit
+/// offset are placed right after the previous field, starting at `start_offset`, and fields
+/// without a byte order are read in `endianness`.
+pub fn parse_field_text(text: &str, start_offset: usize, endianness: Endianness) -> Result<Vec<Field>, ImportError> {
+    let mut fields = Vec::new();
+    let mut next_offset = start_offset;
+
+    for (idx, line) in text.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with("//") {
+            continue;
+        }
+
+        let field = parse_field_line(trimmed, next_offset, endianness).map_err(|e| ImportError {
+            line: idx + 1,
+            message: e.to_string(),
+        })?;
+        next_offset = field
+            .data_type
+            .size()
+            .checked_mul(field.count)
+            .and_then(|size| field.offset.checked_add(size))
+            .ok_or_else(|| ImportError {
+                line: idx + 1,
+                message: format!("'{}' extends past the largest possible offset", field.name),
+            })?;
+        fields.push(field);
+    }
+
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_name_type_offset_and_comment() {
+        let field = parse_field_line("magic: u32 be @0x10 # file signature", 0, Endianness::Little).unwrap();
+        assert_eq!(field.name, "magic");
+        assert_eq!(field.data_type, DataType::U32);
+        assert_eq!(field.endianness, Endianness::Big);
+        assert_eq!(field.offset, 0x10);
+        assert_eq!(field.comment, "file signature");
+    }
+
+    #[test]
+    fn accepts_each_name_separator() {
+        for line in ["version u16", "version: u16", "version : u16", "version:u16"] {
+            let field = parse_field_line(line, 6, Endianness::Little).unwrap();
+            assert_eq!((field.name.as_str(), field.data_type, field.offset), ("version", DataType::U16, 6));
+        }
+    }
+
+    #[test]
+    fn byte_order_defaults_to_the_given_one() {
+        assert_eq!(parse_field_line("a u32", 0, Endianness::Big).unwrap().endianness, Endianness::Big);
+        assert_eq!(parse_field_line("a u32 le", 0, Endianness::Big).unwrap().endianness, Endianness::Little);
+
+        let fields = parse_field_text("a u16\nb u16 le", 0, Endianness::Big).unwrap();
+        assert_eq!(fields[0].endianness, Endianness::Big);
+        assert_eq!(fields[1].endianness, Endianness::Little);
+    }
+
+    #[test]
+    fn parses_arrays() {
+        let field = parse_field_line("entries u32[4]", 0, Endianness::Little).unwrap();
+        assert_eq!(field.count, 4);
+        assert_eq!(field.size(), 16);
+    }
+
+    #[test]
+    fn parses_hex_and_decimal_offsets() {
+        assert_eq!(parse_field_line("a u8 @ 0X1f", 0, Endianness::Little).unwrap().offset, 0x1F);
+        assert_eq!(parse_field_line("a u8 @32", 0, Endianness::Little).unwrap().offset, 32);
+    }
+
+    #[test]
+    fn reports_errors() {
+        let error = |line| parse_field_line(line, 0, Endianness::Little).unwrap_err().to_string();
+        assert_eq!(error(""), "missing field name");
+        assert_eq!(error(": u8"), "missing field name");
+        assert_eq!(error("name"), "missing field type");
+        assert_eq!(error("name u24"), "unknown type 'u24'");
+        assert_eq!(error("name u8 @0xZZ"), "invalid offset '0xZZ'");
+        assert_eq!(error("name u8[0]"), "invalid array count '0'");
+        assert_eq!(error("name u8[4"), "invalid array count '4'");
+        assert_eq!(error("name u8 middle"), "unexpected 'middle'");
+    }
+
+    #[test]
+    fn fields_follow_each_other_and_errors_report_the_line() {
+        let fields = parse_field_text("# header\na u16\n\nb u32[2]\nc u8 @0x40\nd u8", 0x100, Endianness::Little).unwrap();
+        let offsets: Vec<usize> = fields.iter().map(|f| f.offset).collect();
+        assert_eq!(offsets, vec![0x100, 0x102, 0x40, 0x41]);
+
+        let error = parse_field_text("a u8\nb bogus", 0, Endianness::Little).unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(error.message, "unknown type 'bogus'");
+    }
+
+    #[test]
+    fn fields_past_the_largest_offset_are_rejected() {
+        let error = parse_field_text("a u64[1000000] @0xFFFFFFFFFFFFFFF0\nb u8", 0, Endianness::Little).unwrap_err();
+        assert_eq!(error.line, 1);
+        assert_eq!(error.message, "'a' extends past the largest possible offset");
+    }
+}
@@ -0,0 +1,36 @@
+//! Schematic: describe binary file layouts with schemas of typed fields and decode them
+//!
+//! The core modules (`schema`, `binary_data`, `analysis`, `checksum`, `export`, `import`,
+//! `templates`, `watch`) have no GUI dependencies. The egui application lives behind the default `gui` feature.
+//!
+//! Decoding a file headlessly:
+//!
+//! ```no_run
+//! let schema = schematic::Schema::from_toml(&std::fs::read_to_string("format.toml").unwrap()).unwrap();
+//! for field in schema.decode(&std::fs::read("file.bin").unwrap()) {
+//!     println!("0x{:X} {} = {}", field.offset, field.name, field.value.unwrap_or_default());
+//! }
+//! ```
+
+pub mod analysis;
+pub mod binary_data;
+pub mod checksum;
+pub mod export;
+pub mod import;
+pub mod schema;
+pub mod templates;
+pub mod watch;
+
+#[cfg(feature = "gui")]
+pub mod app;
+#[cfg(feature = "gui")]
+pub mod app_state;
+#[cfg(feature = "gui")]
+pub mod associations;
+#[cfg(feature = "gui")]
+pub mod preferences;
+#[cfg(feature = "gui")]
+pub mod ui;
+
+pub use binary_data::BinaryData;
+pub use schema::{DataType, DecodedField, Endianness, Field, Schema};
@@ -1,11 +1,23 @@
-mod app;
-mod binary_data;
-mod schema;
-mod ui;
+use schematic::BinaryData;
+use schematic::app::SchematicApp;
 
-use app::SchematicApp;
+/// Read the binary from stdin when invoked as `schematic -` or `schematic --stdin`
+fn read_stdin_if_requested() -> Option<BinaryData> {
+    if !std::env::args().skip(1).any(|arg| arg == "-" || arg == "--stdin") {
+        return None;
+    }
+
+    let mut binary_data = BinaryData::new();
+    if let Err(e) = binary_data.load_from_reader(std::io::stdin().lock()) {
+        eprintln!("Error reading stdin: {}", e);
+        std::process::exit(1);
+    }
+    Some(binary_data)
+}
 
 fn main() -> eframe::Result<()> {
+    let stdin_data = read_stdin_if_requested();
+
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1200.0, 800.0])
@@ -16,6 +28,6 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "Schematic",
         native_options,
-        Box::new(|cc| Ok(Box::new(SchematicApp::new(cc)))),
+        Box::new(|cc| Ok(Box::new(SchematicApp::new(cc, stdin_data)))),
     )
 }
@@ -0,0 +1,47 @@
+use crate::schema::Endianness;
+use serde::{Deserialize, Serialize};
+
+/// Editor behavior preferences, persisted between runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    /// Warn about field names that aren't valid C/Rust identifiers
+    pub warn_invalid_identifiers: bool,
+    /// Refuse to add/save fields with name problems instead of only warning
+    pub block_invalid_names: bool,
+    /// Require a name for new fields instead of deriving one from the offset
+    pub require_field_names: bool,
+    /// Byte order given to newly created fields
+    pub default_endianness: Endianness,
+    /// 16-bit magics that tell a file's byte order by how they're stored at its start
+    pub endian_magics: Vec<u16>,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            warn_invalid_identifiers: true,
+            block_invalid_names: false,
+            require_field_names: false,
+            default_endianness: Endianness::Little,
+            endian_magics: Vec::new(),
+        }
+    }
+}
+
+impl Preferences {
+    /// Key used in eframe storage
+    pub const STORAGE_KEY: &'static str = "preferences";
+
+    /// Restore the preferences from eframe storage, if any were saved
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        storage
+            .and_then(|s| eframe::get_value(s, Self::STORAGE_KEY))
+            .unwrap_or_default()
+    }
+
+    /// Write the preferences to eframe storage
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, Self::STORAGE_KEY, self);
+    }
+}
@@ -0,0 +1,43 @@
+use super::{Field, StringPool, place_fields_in};
+
+/// A field decoded against some data, for scripting without the GUI
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedField {
+    /// Identifier of the field (its name, or `field@0x..` if unnamed)
+    pub name: String,
+    /// Offset of the field in the data, with anchors resolved
+    pub offset: usize,
+    /// Size of the field in bytes
+    pub size: usize,
+    /// Type as shown in the Data View, e.g. `u32[4]`
+    pub type_name: String,
+    /// Rendered value, `None` if the field runs past the end of the data
+    pub value: Option<String>,
+    /// Numeric value of a single (non-array) integer field
+    pub integer: Option<i128>,
+}
+
+/// Decode every field against `data`, in schema order
+/// String index fields resolve through `string_pool` when one is given
+pub fn decode(fields: &[Field], string_pool: Option<&StringPool>, data: &[u8]) -> Vec<DecodedField> {
+    place_fields_in(fields, 0, data)
+        .iter()
+        .map(|field| {
+            let value = match string_pool {
+                Some(pool) if field.is_string_index => field.read_pool_string(data, pool),
+                _ => field.read_value(data),
+            };
+            let integer = (field.data_type.is_integer() && !field.is_array())
+                .then(|| field.data_type.read_integer(data, field.offset, field.endianness))
+                .flatten();
+            DecodedField {
+                name: field.identifier().into_owned(),
+                offset: field.offset,
+                size: field.size(),
+                type_name: field.type_name(),
+                value,
+                integer,
+            }
+        })
+        .collect()
+}
@@ -1,39 +1,389 @@
-use super::types::{DataType, Endianness};
+use super::string_pool::StringPool;
+use super::types::{BitOrder, DataType, Endianness, FloatFormat, NumberFormat};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Maximum number of array elements rendered inline by `read_value`
+const MAX_INLINE_ELEMENTS: usize = 8;
+
+fn default_count() -> usize {
+    1
+}
+
+fn is_default_count(count: &usize) -> bool {
+    *count == 1
+}
+
+fn is_zero(value: &i64) -> bool {
+    *value == 0
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// Name given to one value of an enum-like integer field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnumLabel {
+    pub value: i64,
+    pub label: String,
+}
 
 /// Represents a field in a binary schema
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Field {
-    /// Name of the field
+    /// Name of the field, used as the identifier by the exporters
     pub name: String,
-    /// Offset in bytes from the start of the file
+    /// Optional human-readable label shown in the views instead of the name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Offset in bytes from the start of the file, or from the end of the `anchor` field
     pub offset: usize,
+    /// Name of the field this one follows; its offset is then relative to that field's end
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<String>,
+    /// Name of the integer field holding the length of this byte string field, read from the data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length_field: Option<String>,
     /// Data type of the field
     pub data_type: DataType,
     /// Optional comment/description
     pub comment: String,
     /// Endianness for this field
     pub endianness: Endianness,
+    /// Number of consecutive elements of `data_type` (1 for a scalar field)
+    #[serde(default = "default_count", skip_serializing_if = "is_default_count")]
+    pub count: usize,
+    /// Whether each element is an offset pointing elsewhere in the file
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub is_pointer: bool,
+    /// Added to each pointer value to get the target file offset
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub pointer_base: i64,
+    /// Whether each element is a byte index into the schema's string pool
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub is_string_index: bool,
+    /// Names of known values, rendered next to the number (e.g. `2 (Data)`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enum_labels: Vec<EnumLabel>,
+    /// Highlight color as RGB, overriding the palette
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<[u8; 3]>,
+    /// Members of a struct field, with offsets relative to the start of each element
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Field>,
 }
 
 impl Field {
     pub fn new(name: String, offset: usize, data_type: DataType) -> Self {
         Self {
             name,
+            label: None,
             offset,
+            anchor: None,
+            length_field: None,
             data_type,
             comment: String::new(),
             endianness: Endianness::default(),
+            count: 1,
+            is_pointer: false,
+            pointer_base: 0,
+            is_string_index: false,
+            enum_labels: Vec::new(),
+            color: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Get the identifier, derived from the offset (e.g. `field@0x1A0`) for unnamed fields
+    pub fn identifier(&self) -> Cow<'_, str> {
+        if self.name.is_empty() {
+            Cow::Owned(format!("field@0x{:X}", self.offset))
+        } else {
+            Cow::Borrowed(&self.name)
+        }
+    }
+
+    /// Get the name shown in the views: the label if set, otherwise the identifier
+    pub fn display_name(&self) -> Cow<'_, str> {
+        match &self.label {
+            Some(label) => Cow::Borrowed(label),
+            None => self.identifier(),
         }
     }
 
-    /// Get the size of this field in bytes
+    /// Get the size of this field in bytes, saturating for absurd counts
     pub fn size(&self) -> usize {
-        self.data_type.size()
+        self.data_type.size().saturating_mul(self.count)
+    }
+
+    /// Get the display name of this field's type, e.g. `u32`, `u16[4]`, `*u32[8]` for pointer tables
+    /// or `$u32` for string pool indices
+    pub fn type_name(&self) -> String {
+        let pointer = if self.is_pointer {
+            "*"
+        } else if self.is_string_index {
+            "$"
+        } else {
+            ""
+        };
+        let base = match self.data_type {
+            DataType::Bits { bit_offset, bit_width, .. } => {
+                format!("bits {}..{}", bit_offset, bit_offset.saturating_add(bit_width))
+            }
+            DataType::FixedString { len } | DataType::CString { max_len: len } | DataType::Bytes { len } => {
+                format!("{}({})", self.data_type.name(), len)
+            }
+            DataType::SliceDescriptor { ptr_type, len_type } => {
+                format!("slice<{}, {}>", ptr_type.name(), len_type.name())
+            }
+            DataType::Struct { size } => format!("struct({})", size),
+            data_type => data_type.name().to_string(),
+        };
+        if self.is_array() {
+            format!("{}{}[{}]", pointer, base, self.count)
+        } else {
+            format!("{}{}", pointer, base)
+        }
+    }
+
+    /// Get the absolute bit range covered by a bit field, counted in display (MSB-first) order
+    /// Returns `None` for other types, or layouts whose bits aren't contiguous on screen
+    pub fn display_bit_range(&self) -> Option<std::ops::Range<usize>> {
+        let DataType::Bits { bit_offset, bit_width, bit_order } = self.data_type else {
+            return None;
+        };
+        if self.is_array() {
+            return None;
+        }
+
+        let first_byte = self.offset.checked_add(bit_offset / 8)?;
+        let local_offset = bit_offset % 8;
+        let span = (local_offset + bit_width).div_ceil(8);
+        // Only a big-endian (or single-byte) container is laid out on screen in bit order
+        if self.endianness == Endianness::Little && span > 1 {
+            return None;
+        }
+
+        let start = match bit_order {
+            BitOrder::MsbFirst => first_byte * 8 + local_offset,
+            BitOrder::LsbFirst => first_byte * 8 + span * 8 - local_offset - bit_width,
+        };
+        Some(start..start + bit_width)
+    }
+
+    /// Get the absolute bit range the field occupies; whole bytes except for a single bit field
+    pub fn bit_span(&self) -> std::ops::Range<usize> {
+        let start = self.offset.saturating_mul(8);
+        match self.data_type {
+            DataType::Bits { bit_offset, bit_width, .. } if !self.is_array() => {
+                start.saturating_add(bit_offset)..start.saturating_add(bit_offset).saturating_add(bit_width)
+            }
+            _ => start..self.offset.saturating_add(self.size()).saturating_mul(8),
+        }
+    }
+
+    /// Check if values of this field are rendered with their enum labels
+    pub fn has_enum_labels(&self) -> bool {
+        !self.enum_labels.is_empty() && self.data_type.is_integer() && !self.is_pointer && !self.is_string_index
+    }
+
+    /// Get the label of an integer value, if one is defined
+    pub fn enum_label(&self, value: i128) -> Option<&str> {
+        self.enum_labels
+            .iter()
+            .find(|entry| i128::from(entry.value) == value)
+            .map(|entry| entry.label.as_str())
+    }
+
+    /// Group fields into a struct placed at the lowest of their offsets and sized to cover them all
+    pub fn new_struct(name: String, mut children: Vec<Field>) -> Self {
+        let base = children.iter().map(|child| child.offset).min().unwrap_or(0);
+        let end = super::fields_end(&children);
+        for child in &mut children {
+            child.offset -= base;
+        }
+        let mut field = Field::new(name, base, DataType::Struct { size: end - base });
+        field.children = children;
+        field
+    }
+
+    /// Get the members of element `index` of a struct field, placed at absolute offsets
+    pub fn struct_members(&self, index: usize) -> Vec<Field> {
+        let base = self.element_offset(index);
+        self.children
+            .iter()
+            .map(|child| Field {
+                offset: child.offset.saturating_add(base),
+                ..child.clone()
+            })
+            .collect()
+    }
+
+    /// Get the rows of a struct field's tree: every element and member below it, with their depth
+    /// Array elements become `[i]` rows and members are placed at absolute offsets
+    pub fn descendants(&self, limit: usize) -> Vec<(usize, Field)> {
+        let mut rows = Vec::new();
+        self.collect_descendants(1, limit, &mut rows);
+        rows
+    }
+
+    fn collect_descendants(&self, depth: usize, limit: usize, rows: &mut Vec<(usize, Field)>) {
+        if !self.data_type.is_struct() {
+            return;
+        }
+        for index in 0..self.count {
+            let depth = if self.is_array() {
+                if rows.len() >= limit {
+                    return;
+                }
+                let mut element = Field::new(format!("[{}]", index), self.element_offset(index), self.data_type);
+                element.children = self.children.clone();
+                rows.push((depth, element));
+                depth + 1
+            } else {
+                depth
+            };
+            for member in self.struct_members(index) {
+                if rows.len() >= limit {
+                    return;
+                }
+                rows.push((depth, member.clone()));
+                member.collect_descendants(depth + 1, limit, rows);
+            }
+        }
+    }
+
+    /// Check if this field is an array of more than one element
+    pub fn is_array(&self) -> bool {
+        self.count != 1
+    }
+
+    /// Get the offset of the element at `index`, or `usize::MAX` (past any data) if it overflows
+    pub fn element_offset(&self, index: usize) -> usize {
+        index
+            .checked_mul(self.data_type.size())
+            .and_then(|delta| self.offset.checked_add(delta))
+            .unwrap_or(usize::MAX)
     }
 
     /// Read the value of this field from the given binary data
+    /// Arrays are rendered as `[a, b, c, …]` with a cap on the number of elements shown
     pub fn read_value(&self, data: &[u8]) -> Option<String> {
-        self.data_type.read_value(data, self.offset, self.endianness)
+        self.read_value_as(data, NumberFormat::Decimal, FloatFormat::default())
+    }
+
+    /// Read the value of this field like `read_value`, rendering integer elements in `format`
+    /// and float elements in `float_format`
+    /// Pointers are always shown in hex; labelled values get their label, or `(unknown)`, appended
+    pub fn read_value_as(&self, data: &[u8], format: NumberFormat, float_format: FloatFormat) -> Option<String> {
+        let labelled = self.has_enum_labels();
+        let is_struct = self.data_type.is_struct();
+        if !self.is_array() && !self.is_pointer && !labelled && !is_struct {
+            return self.data_type.read_value_as(data, self.offset, self.endianness, format, float_format);
+        }
+
+        if self.offset.checked_add(self.size())? > data.len() {
+            return None;
+        }
+
+        let element = |i: usize| {
+            if is_struct {
+                self.read_struct_summary(data, i, format, float_format)
+            } else if self.is_pointer {
+                self.read_pointer(data, i)
+                    .map(|value| format!("0x{:X}", value))
+                    .unwrap_or_default()
+            } else {
+                let offset = self.element_offset(i);
+                let text = self
+                    .data_type
+                    .read_value_as(data, offset, self.endianness, format, float_format)
+                    .unwrap_or_default();
+                if !labelled {
+                    return text;
+                }
+                let value = self.data_type.read_integer(data, offset, self.endianness);
+                let label = value.and_then(|value| self.enum_label(value)).unwrap_or("unknown");
+                format!("{} ({})", text, label)
+            }
+        };
+
+        if !self.is_array() {
+            return Some(element(0));
+        }
+
+        let mut elements: Vec<String> = (0..self.count.min(MAX_INLINE_ELEMENTS)).map(element).collect();
+        if self.count > MAX_INLINE_ELEMENTS {
+            elements.push("…".to_string());
+        }
+
+        Some(format!("[{}]", elements.join(", ")))
+    }
+
+    /// Render element `index` of a struct as `{a: 1, b: 2, …}`
+    fn read_struct_summary(&self, data: &[u8], index: usize, format: NumberFormat, float_format: FloatFormat) -> String {
+        let mut members: Vec<String> = self
+            .struct_members(index)
+            .iter()
+            .take(MAX_INLINE_ELEMENTS)
+            .map(|member| {
+                let value = member.read_value_as(data, format, float_format).unwrap_or_default();
+                format!("{}: {}", member.display_name(), value)
+            })
+            .collect();
+        if self.children.len() > MAX_INLINE_ELEMENTS {
+            members.push("…".to_string());
+        }
+        format!("{{{}}}", members.join(", "))
+    }
+
+    /// Resolve the string pool entries referenced by this field, rendered like `read_value`
+    pub fn read_pool_string(&self, data: &[u8], pool: &StringPool) -> Option<String> {
+        if self.offset.checked_add(self.size())? > data.len() {
+            return None;
+        }
+
+        let element = |i: usize| {
+            let index = self.read_pointer(data, i);
+            match index.and_then(|index| pool.read_string(data, index)) {
+                Some(text) => format!("{:?}", text),
+                None => format!("<bad index {}>", index.unwrap_or_default()),
+            }
+        };
+
+        if !self.is_array() {
+            return Some(element(0));
+        }
+
+        let mut elements: Vec<String> = (0..self.count.min(MAX_INLINE_ELEMENTS)).map(element).collect();
+        if self.count > MAX_INLINE_ELEMENTS {
+            elements.push("…".to_string());
+        }
+
+        Some(format!("[{}]", elements.join(", ")))
+    }
+
+    /// Resolve the region described by the slice descriptor at `index`
+    /// Returns `None` for other types, or if the region extends past the end of the data
+    pub fn slice_target(&self, data: &[u8], index: usize) -> Option<std::ops::Range<usize>> {
+        let (target, len) = self
+            .data_type
+            .read_slice(data, self.element_offset(index), self.endianness)?;
+        let start = usize::try_from(target).ok()?;
+        let end = start.checked_add(usize::try_from(len).ok()?)?;
+        (end <= data.len()).then_some(start..end)
+    }
+
+    /// Read the raw pointer value of the element at `index`
+    pub fn read_pointer(&self, data: &[u8], index: usize) -> Option<i128> {
+        self.data_type
+            .read_integer(data, self.element_offset(index), self.endianness)
+    }
+
+    /// Resolve the target offset of the pointer at `index`, or `None` if it points outside the data
+    pub fn pointer_target(&self, data: &[u8], index: usize) -> Option<usize> {
+        let target = self.read_pointer(data, index)? + self.pointer_base as i128;
+        usize::try_from(target).ok().filter(|&t| t < data.len())
     }
 }
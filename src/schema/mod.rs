@@ -1,13 +1,476 @@
 pub mod types;
 pub mod field;
+pub mod string_pool;
+pub mod decode;
 
-pub use types::{DataType, Endianness};
-pub use field::Field;
+pub use types::{
+    BitOrder, ChecksumAlgorithm, DataType, Endianness, FloatFormat, FloatNotation, IntType, NumberFormat, ParseError,
+};
+pub use field::{EnumLabel, Field};
+pub use string_pool::StringPool;
+pub use decode::DecodedField;
 
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+/// A likely mistake in the layout of a schema's fields, referring to fields by index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaWarning {
+    /// The two fields cover some of the same bytes (or bits, for bit fields)
+    Overlap(usize, usize),
+    /// The field extends past the end of the file
+    OutOfFile(usize),
+    /// The bit field is narrower than 1 bit or wider than 64 bits
+    InvalidBitWidth(usize),
+    /// The length read from the field's length field runs past the end of the file, so it was cut short
+    LengthPastEnd(usize),
+}
+
+/// A field referring to a schema definition that doesn't exist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefError {
+    /// Identifier of the field holding the reference
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for RefError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// File format a schema is stored in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaFormat {
+    Toml,
+    Json,
+}
+
+impl SchemaFormat {
+    /// Pick the format from a file's extension: `.json` is JSON, anything else TOML
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => SchemaFormat::Json,
+            _ => SchemaFormat::Toml,
+        }
+    }
+}
 
 /// A complete schema definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Schema {
     pub fields: Vec<Field>,
+    /// Explicit total file size the schema describes, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_size: Option<usize>,
+    /// Region that string index fields point into, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub string_pool: Option<StringPool>,
+}
+
+impl Schema {
+    /// Parse a schema from its TOML representation
+    pub fn from_toml(text: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+
+    /// Serialize the schema to TOML
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string_pretty(self)
+    }
+
+    /// Parse a schema from its JSON representation
+    pub fn from_json(text: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(text)
+    }
+
+    /// Serialize the schema to pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Parse a schema stored in `format`
+    pub fn parse(text: &str, format: SchemaFormat) -> Result<Self, String> {
+        match format {
+            SchemaFormat::Toml => Self::from_toml(text).map_err(|e| format!("invalid TOML schema: {}", e)),
+            SchemaFormat::Json => Self::from_json(text).map_err(|e| format!("invalid JSON schema: {}", e)),
+        }
+    }
+
+    /// Serialize the schema in `format`
+    pub fn serialize(&self, format: SchemaFormat) -> Result<String, String> {
+        match format {
+            SchemaFormat::Toml => self.to_toml().map_err(|e| e.to_string()),
+            SchemaFormat::Json => self.to_json().map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Get the file size this schema expects: the explicit size if set, otherwise the end of the last field
+    pub fn expected_size(&self) -> usize {
+        self.expected_size.unwrap_or_else(|| fields_end(&self.fields))
+    }
+
+    /// Get the names used by more than one field
+    pub fn duplicate_names(&self) -> Vec<String> {
+        duplicate_names(&self.fields)
+    }
+
+    /// Check the fields for overlaps and for extending past a file of `file_size` bytes
+    pub fn validate(&self, file_size: usize) -> Vec<SchemaWarning> {
+        validate(&place_fields(&self.fields, 0), file_size)
+    }
+
+    /// Get the absolute offset of every field, following anchors
+    pub fn resolve_offsets(&self) -> Vec<usize> {
+        resolve_offsets(&self.fields)
+    }
+
+    /// Decode every field against `data`, without any GUI
+    pub fn decode(&self, data: &[u8]) -> Vec<DecodedField> {
+        decode::decode(&self.fields, self.string_pool.as_ref(), data)
+    }
+
+    /// Check that every definition referenced by a field exists
+    pub fn check_references(&self) -> Vec<RefError> {
+        let mut errors = anchor_errors(&self.fields);
+        for field in &self.fields {
+            if let Some(name) = &field.length_field {
+                let problem = match self.fields.iter().find(|f| !f.name.is_empty() && &f.name == name) {
+                    None => Some(format!("takes its length from unknown field `{}`", name)),
+                    Some(target) if !target.data_type.is_integer() || target.is_array() => {
+                        Some(format!("takes its length from `{}`, which is not an integer", name))
+                    }
+                    Some(_) if !field.data_type.is_byte_string() => {
+                        Some("has a length field, but is not a string or byte array".to_string())
+                    }
+                    Some(_) => None,
+                };
+                if let Some(message) = problem {
+                    errors.push(RefError {
+                        field: field.identifier().into_owned(),
+                        message,
+                    });
+                }
+            }
+            if field.is_string_index && self.string_pool.is_none() {
+                errors.push(RefError {
+                    field: field.identifier().into_owned(),
+                    message: "indexes the string pool, but none is defined".to_string(),
+                });
+            }
+        }
+        errors
+    }
+}
+
+/// Get the names used by more than one field, in order of first appearance
+/// Unnamed fields are ignored, since their names are derived from their offsets
+pub fn duplicate_names(fields: &[Field]) -> Vec<String> {
+    let named = || fields.iter().filter(|f| !f.name.is_empty());
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for field in named() {
+        *counts.entry(field.name.as_str()).or_default() += 1;
+    }
+
+    let mut duplicates: Vec<String> = Vec::new();
+    for field in named() {
+        if counts[field.name.as_str()] > 1 && !duplicates.contains(&field.name) {
+            duplicates.push(field.name.clone());
+        }
+    }
+    duplicates
+}
+
+/// Check the fields for overlaps and for extending past a file of `file_size` bytes
+pub fn validate(fields: &[Field], file_size: usize) -> Vec<SchemaWarning> {
+    let mut warnings = Vec::new();
+
+    // Sweep the fields in order of their start, comparing each with the ones starting before it ends
+    let mut order: Vec<usize> = (0..fields.len()).filter(|&i| fields[i].size() > 0).collect();
+    order.sort_by_key(|&i| fields[i].bit_span().start);
+    for (pos, &a) in order.iter().enumerate() {
+        let span = fields[a].bit_span();
+        for &b in order[pos + 1..].iter().take_while(|&&b| fields[b].bit_span().start < span.end) {
+            if !fields[b].bit_span().is_empty() {
+                warnings.push(SchemaWarning::Overlap(a.min(b), a.max(b)));
+            }
+        }
+    }
+
+    for (idx, field) in fields.iter().enumerate() {
+        if field.offset.saturating_add(field.size()) > file_size {
+            warnings.push(SchemaWarning::OutOfFile(idx));
+        }
+        if let DataType::Bits { bit_width, .. } = field.data_type
+            && !(1..=64).contains(&bit_width)
+        {
+            warnings.push(SchemaWarning::InvalidBitWidth(idx));
+        }
+    }
+    warnings
+}
+
+/// Get the first field whose bytes cover `offset`, with its index
+pub fn field_at_offset(fields: &[Field], offset: usize) -> Option<(usize, &Field)> {
+    fields
+        .iter()
+        .enumerate()
+        .find(|(_, field)| {
+            let end = field.offset.checked_add(field.size());
+            offset >= field.offset && end.is_some_and(|end| offset < end)
+        })
+}
+
+/// Get the end offset of the field that extends furthest into the file
+pub fn fields_end(fields: &[Field]) -> usize {
+    resolve_offsets(fields)
+        .iter()
+        .zip(fields)
+        .map(|(&offset, f)| offset.saturating_add(f.size()))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Fields placed by following their anchors and, given the data, their length fields
+struct Resolved {
+    /// Absolute offset of every field
+    offsets: Vec<usize>,
+    /// Data type of every field, with the lengths read from the data applied
+    types: Vec<DataType>,
+    /// Problems found on the way
+    errors: Vec<RefError>,
+    /// Fields whose length read from the data ran past its end, and was cut short
+    clamped: Vec<usize>,
+}
+
+/// Resolve the anchors of the fields into absolute offsets, with the problems found on the way
+/// A field whose anchor is unknown or part of a cycle keeps its offset as an absolute one
+///
+/// Given `data` and the base the fields are placed at, byte string fields with a length field
+/// also get their length read from the data, cut short at the end of the data.
+fn resolve_anchors(fields: &[Field], data: Option<(&[u8], usize)>) -> Resolved {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Unvisited,
+        InProgress,
+        Done,
+    }
+
+    let mut names: HashMap<&str, usize> = HashMap::new();
+    for (idx, field) in fields.iter().enumerate() {
+        if !field.name.is_empty() {
+            names.entry(field.name.as_str()).or_insert(idx);
+        }
+    }
+
+    let mut offsets: Vec<usize> = fields.iter().map(|f| f.offset).collect();
+    let mut types: Vec<DataType> = fields.iter().map(|f| f.data_type).collect();
+    let mut states = vec![State::Unvisited; fields.len()];
+    let mut errors = Vec::new();
+    let mut clamped = Vec::new();
+
+    for start in 0..fields.len() {
+        // Walk down the chains of anchors and length fields, resolving fields on the way back up
+        let mut stack = vec![start];
+        'walk: while let Some(&idx) = stack.last() {
+            if states[idx] == State::Done {
+                stack.pop();
+                continue;
+            }
+            states[idx] = State::InProgress;
+
+            let field = &fields[idx];
+            // Length fields only matter when there is data to read them from
+            let length_field = field.length_field.as_deref().filter(|_| data.is_some());
+            let mut problems = Vec::new();
+            let mut anchor_target = None;
+            let mut length_target = None;
+            for (name, is_anchor) in [(field.anchor.as_deref(), true), (length_field, false)] {
+                let Some(name) = name else { continue };
+                match (names.get(name), is_anchor) {
+                    (None, true) => problems.push(format!("follows unknown field `{}`", name)),
+                    (None, false) => problems.push(format!("takes its length from unknown field `{}`", name)),
+                    (Some(&target), _) => match states[target] {
+                        State::Done if is_anchor => anchor_target = Some(target),
+                        State::Done => length_target = Some(target),
+                        State::InProgress if is_anchor => problems.push(format!("anchor cycle through `{}`", name)),
+                        State::InProgress => problems.push(format!("length cycle through `{}`", name)),
+                        State::Unvisited => {
+                            stack.push(target);
+                            continue 'walk;
+                        }
+                    },
+                }
+            }
+
+            if let Some(target) = anchor_target {
+                offsets[idx] = offsets[target]
+                    .saturating_add(types[target].size().saturating_mul(fields[target].count))
+                    .saturating_add(field.offset);
+            }
+            if let (Some(target), Some((data, base))) = (length_target, data)
+                && field.data_type.is_byte_string()
+            {
+                let len_offset = offsets[target].saturating_add(base);
+                match types[target].read_integer(data, len_offset, fields[target].endianness) {
+                    Some(len) if len >= 0 => {
+                        // Guard against absurd lengths by cutting them short at the end of the data
+                        let available = data.len().saturating_sub(offsets[idx].saturating_add(base));
+                        let count = field.count.max(1);
+                        let len = usize::try_from(len).unwrap_or(usize::MAX);
+                        if len.saturating_mul(count) > available {
+                            clamped.push(idx);
+                        }
+                        types[idx] = field.data_type.with_len(len.min(available / count));
+                    }
+                    _ => problems.push(format!("can't read its length from `{}`", fields[target].identifier())),
+                }
+            }
+
+            for message in problems {
+                errors.push(RefError {
+                    field: field.identifier().into_owned(),
+                    message,
+                });
+            }
+            states[idx] = State::Done;
+            stack.pop();
+        }
+    }
+
+    Resolved {
+        offsets,
+        types,
+        errors,
+        clamped,
+    }
+}
+
+/// Get the absolute offset of every field, following anchors
+pub fn resolve_offsets(fields: &[Field]) -> Vec<usize> {
+    resolve_anchors(fields, None).offsets
+}
+
+/// Get the fields whose anchor names no field or leads back to themselves
+pub fn anchor_errors(fields: &[Field]) -> Vec<RefError> {
+    resolve_anchors(fields, None).errors
+}
+
+/// Get the fields as placed at `base`, i.e. with anchors resolved and `base` added to every offset
+/// Borrows the fields unchanged when the base is zero and no field is anchored
+pub fn place_fields(fields: &[Field], base: usize) -> Cow<'_, [Field]> {
+    place(fields, base, None)
+}
+
+/// Get the fields as placed at `base` over `data`, also with the lengths read from length fields
+pub fn place_fields_in<'a>(fields: &'a [Field], base: usize, data: &[u8]) -> Cow<'a, [Field]> {
+    place(fields, base, Some(data))
+}
+
+fn place<'a>(fields: &'a [Field], base: usize, data: Option<&[u8]>) -> Cow<'a, [Field]> {
+    let has_lengths = data.is_some() && fields.iter().any(|f| f.length_field.is_some());
+    if base == 0 && !has_lengths && fields.iter().all(|f| f.anchor.is_none()) {
+        return Cow::Borrowed(fields);
+    }
+
+    let resolved = resolve_anchors(fields, data.map(|data| (data, base)));
+    Cow::Owned(
+        resolved
+            .offsets
+            .into_iter()
+            .zip(resolved.types)
+            .zip(fields)
+            .map(|((offset, data_type), f)| Field {
+                offset: offset.saturating_add(base),
+                anchor: None,
+                data_type: data_type.placed(base),
+                ..f.clone()
+            })
+            .collect(),
+    )
+}
+
+/// Get the fields whose length, read from their length field, runs past the end of `data`
+pub fn length_warnings(fields: &[Field], base: usize, data: &[u8]) -> Vec<SchemaWarning> {
+    resolve_anchors(fields, Some((data, base)))
+        .clamped
+        .into_iter()
+        .map(SchemaWarning::LengthPastEnd)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anchor_on_array_follows_all_elements() {
+        let mut entries = Field::new("entries".to_string(), 0, DataType::U32);
+        entries.count = 4;
+        let mut after = Field::new("after".to_string(), 0, DataType::U8);
+        after.anchor = Some("entries".to_string());
+        let fields = vec![entries, after];
+
+        assert_eq!(resolve_offsets(&fields), vec![0, 16]);
+        assert_eq!(place_fields_in(&fields, 0, &[0; 32])[1].offset, 16);
+    }
+
+    #[test]
+    fn anchor_after_length_prefixed_field_uses_read_length() {
+        let len = Field::new("len".to_string(), 0, DataType::U8);
+        let mut body = Field::new("body".to_string(), 0, DataType::Bytes { len: 1 });
+        body.anchor = Some("len".to_string());
+        body.length_field = Some("len".to_string());
+        let mut tail = Field::new("tail".to_string(), 0, DataType::U8);
+        tail.anchor = Some("body".to_string());
+        let fields = vec![len, body, tail];
+
+        let placed = place_fields_in(&fields, 0, &[3, 0xAA, 0xBB, 0xCC, 7]);
+        assert_eq!(placed[1].size(), 3);
+        assert_eq!(placed[2].offset, 4);
+    }
+
+    #[test]
+    fn length_past_end_is_cut_short() {
+        let len = Field::new("len".to_string(), 0, DataType::U8);
+        let mut body = Field::new("body".to_string(), 1, DataType::Bytes { len: 1 });
+        body.length_field = Some("len".to_string());
+        let fields = vec![len, body];
+
+        let data = [200, 1, 2];
+        assert_eq!(place_fields_in(&fields, 0, &data)[1].size(), 2);
+        assert_eq!(length_warnings(&fields, 0, &data), vec![SchemaWarning::LengthPastEnd(1)]);
+    }
+
+    #[test]
+    fn bit_widths_outside_1_to_64_are_reported() {
+        let bits = |bit_width| DataType::Bits { bit_offset: 0, bit_width, bit_order: BitOrder::MsbFirst };
+        let fields = vec![
+            Field::new("ok".to_string(), 0, bits(64)),
+            Field::new("empty".to_string(), 8, bits(0)),
+            Field::new("wide".to_string(), 16, bits(128)),
+        ];
+        assert_eq!(
+            validate(&fields, 1024),
+            vec![SchemaWarning::InvalidBitWidth(1), SchemaWarning::InvalidBitWidth(2)]
+        );
+    }
+
+    #[test]
+    fn huge_offsets_and_counts_do_not_overflow() {
+        let mut field = Field::new("far".to_string(), usize::MAX - 2, DataType::U32);
+        assert_eq!(field.read_value(&[0; 8]), None);
+        field.count = usize::MAX / 2;
+        assert_eq!(field.size(), usize::MAX);
+        assert_eq!(field.read_value(&[0; 8]), None);
+        assert_eq!(field.element_offset(3), usize::MAX);
+
+        let fields = vec![field];
+        assert!(field_at_offset(&fields, usize::MAX - 1).is_none());
+        assert_eq!(fields_end(&fields), usize::MAX);
+    }
 }
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of bytes decoded for a single pool string
+const MAX_STRING_LEN: usize = 256;
+
+/// A region of null-terminated strings that string index fields refer to by byte offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StringPool {
+    /// Offset of the region from the start of the file
+    pub offset: usize,
+    /// Length of the region in bytes
+    pub length: usize,
+}
+
+impl StringPool {
+    pub fn new(offset: usize, length: usize) -> Self {
+        Self { offset, length }
+    }
+
+    /// Decode the C string starting `index` bytes into the pool
+    /// Returns `None` if the index falls outside the pool or the data
+    pub fn read_string(&self, data: &[u8], index: i128) -> Option<String> {
+        let index = usize::try_from(index).ok().filter(|&i| i < self.length)?;
+        let end = self.offset.checked_add(self.length)?.min(data.len());
+        let start = self.offset.checked_add(index).filter(|&s| s < end)?;
+
+        // The string stops at the terminator, the end of the pool, or the display limit
+        let bytes = &data[start..end];
+        let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        let mut text = String::from_utf8_lossy(&bytes[..len.min(MAX_STRING_LEN)]).into_owned();
+        if len > MAX_STRING_LEN {
+            text.push('…');
+        }
+        Some(text)
+    }
+}
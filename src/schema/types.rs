@@ -1,15 +1,458 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
 
 /// Byte ordering for multi-byte values
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Endianness {
+    #[default]
     Little,
     Big,
 }
 
-impl Default for Endianness {
+impl Endianness {
+    /// Get the short display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Endianness::Little => "LE",
+            Endianness::Big => "BE",
+        }
+    }
+}
+
+/// Order in which bits are numbered within a bit field's container
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BitOrder {
+    /// Bit 0 is the most significant bit (network/bitstream order)
+    #[default]
+    MsbFirst,
+    /// Bit 0 is the least significant bit (typical C bitfields on little-endian CPUs)
+    LsbFirst,
+}
+
+impl BitOrder {
+    /// Get the display name of this bit order
+    pub fn name(&self) -> &'static str {
+        match self {
+            BitOrder::MsbFirst => "MSB first",
+            BitOrder::LsbFirst => "LSB first",
+        }
+    }
+}
+
+/// Radix used to display integer values
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NumberFormat {
+    #[default]
+    Decimal,
+    /// Hexadecimal, negative values shown as two's complement (`0xFFFE`)
+    Hex,
+    /// Hexadecimal with a sign for negative values (`-0x2`)
+    SignedHex,
+    /// Octal, negative values shown as two's complement (`0o755`)
+    Octal,
+    Binary,
+}
+
+impl NumberFormat {
+    /// Get the display name of this format
+    pub fn name(&self) -> &'static str {
+        match self {
+            NumberFormat::Decimal => "Decimal",
+            NumberFormat::Hex => "Hex",
+            NumberFormat::SignedHex => "Signed Hex",
+            NumberFormat::Octal => "Octal",
+            NumberFormat::Binary => "Binary",
+        }
+    }
+
+    /// Get all available number formats
+    pub fn all() -> &'static [NumberFormat] {
+        &[
+            NumberFormat::Decimal,
+            NumberFormat::Hex,
+            NumberFormat::SignedHex,
+            NumberFormat::Octal,
+            NumberFormat::Binary,
+        ]
+    }
+
+    /// Format an integer read from a field `bits` bits wide
+    /// Hex and binary are zero-padded to the full width; two's complement is truncated to it
+    pub fn format(&self, value: i128, bits: usize) -> String {
+        let mask = if bits >= 128 { u128::MAX } else { (1u128 << bits) - 1 };
+        let raw = value as u128 & mask;
+        match self {
+            NumberFormat::Decimal => value.to_string(),
+            NumberFormat::Hex => format!("0x{:0width$X}", raw, width = bits.div_ceil(4)),
+            NumberFormat::SignedHex if value < 0 => {
+                format!("-0x{:0width$X}", value.unsigned_abs(), width = bits.div_ceil(4))
+            }
+            NumberFormat::SignedHex => format!("0x{:0width$X}", value, width = bits.div_ceil(4)),
+            NumberFormat::Octal => format!("0o{:o}", raw),
+            NumberFormat::Binary => format!("0b{:0width$b}", raw, width = bits),
+        }
+    }
+}
+
+/// Notation used to display float values
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FloatNotation {
+    /// Shortest text that reads back as the same value (`0.1`)
+    #[default]
+    Shortest,
+    /// Rounded to a number of significant digits, switching to scientific for very large or small values
+    Significant,
+    /// Fixed number of decimals (`0.100`)
+    Fixed,
+    /// Scientific notation with a fixed number of decimals (`1.000e-1`)
+    Scientific,
+}
+
+impl FloatNotation {
+    /// Get the display name of this notation
+    pub fn name(&self) -> &'static str {
+        match self {
+            FloatNotation::Shortest => "Shortest",
+            FloatNotation::Significant => "Significant",
+            FloatNotation::Fixed => "Fixed",
+            FloatNotation::Scientific => "Scientific",
+        }
+    }
+
+    /// Get all available float notations
+    pub fn all() -> &'static [FloatNotation] {
+        &[
+            FloatNotation::Shortest,
+            FloatNotation::Significant,
+            FloatNotation::Fixed,
+            FloatNotation::Scientific,
+        ]
+    }
+}
+
+/// How float values are displayed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FloatFormat {
+    pub notation: FloatNotation,
+    /// Significant digits, or decimals for fixed and scientific notation; unused by `Shortest`
+    pub digits: usize,
+}
+
+impl Default for FloatFormat {
     fn default() -> Self {
-        Self::Little
+        Self { notation: FloatNotation::Shortest, digits: 6 }
+    }
+}
+
+impl FloatFormat {
+    /// Format a float read from a field `bits` bits wide
+    /// NaN and infinities are written out as `NaN`, `+Inf` and `-Inf` in every notation
+    pub fn format(&self, value: f64, bits: usize) -> String {
+        if value.is_nan() {
+            return "NaN".to_string();
+        }
+        if value.is_infinite() {
+            return if value > 0.0 { "+Inf" } else { "-Inf" }.to_string();
+        }
+        match self.notation {
+            // An f32 widened to f64 would print as e.g. 0.10000000149011612
+            FloatNotation::Shortest if bits == 32 => (value as f32).to_string(),
+            FloatNotation::Shortest => value.to_string(),
+            FloatNotation::Significant => format_significant(value, self.digits.max(1)),
+            FloatNotation::Fixed => format!("{:.*}", self.digits, value),
+            FloatNotation::Scientific => format!("{:.*e}", self.digits, value),
+        }
+    }
+}
+
+/// Round `value` to `digits` significant digits like C's `%g`, dropping trailing zeros
+fn format_significant(value: f64, digits: usize) -> String {
+    // Round in scientific notation first, so the exponent accounts for carries (9.99 → 1.00e1)
+    let scientific = format!("{:.*e}", digits - 1, value);
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+    if exponent < -4 || exponent >= digits as i32 {
+        format!("{}e{}", trim_fraction_zeros(mantissa), exponent)
+    } else {
+        let decimals = (digits as i32 - 1 - exponent).max(0) as usize;
+        trim_fraction_zeros(&format!("{:.*}", decimals, value)).to_string()
+    }
+}
+
+/// Drop trailing zeros after a decimal point, and the point itself if nothing follows it
+fn trim_fraction_zeros(text: &str) -> &str {
+    if text.contains('.') { text.trim_end_matches('0').trim_end_matches('.') } else { text }
+}
+
+/// Read `bit_width` bits (1..=64) starting at absolute bit `bit_offset` of `data`
+///
+/// The bytes spanned by the bits are assembled into one integer according to `endianness`,
+/// then bits are counted from its most (`MsbFirst`) or least (`LsbFirst`) significant end.
+/// Returns `None` if the bits extend past the end of the data.
+pub fn read_bits(
+    data: &[u8],
+    bit_offset: usize,
+    bit_width: usize,
+    endianness: Endianness,
+    bit_order: BitOrder,
+) -> Option<u64> {
+    if bit_width == 0 || bit_width > 64 {
+        return None;
+    }
+
+    let first_byte = bit_offset / 8;
+    let local_offset = bit_offset % 8;
+    let span = (local_offset + bit_width).div_ceil(8);
+    let bytes = data.get(first_byte..first_byte.checked_add(span)?)?;
+
+    let container = match endianness {
+        Endianness::Little => bytes.iter().rev().fold(0u128, |acc, &b| (acc << 8) | b as u128),
+        Endianness::Big => bytes.iter().fold(0u128, |acc, &b| (acc << 8) | b as u128),
+    };
+
+    let shift = match bit_order {
+        BitOrder::LsbFirst => local_offset,
+        BitOrder::MsbFirst => span * 8 - local_offset - bit_width,
+    };
+    let mask = (1u128 << bit_width) - 1;
+
+    Some(((container >> shift) & mask) as u64)
+}
+
+/// Write the low `bit_width` bits (1..=64) of `value` at absolute bit `bit_offset` of `data`,
+/// laid out like `read_bits` reads them and leaving the other bits of the spanned bytes unchanged
+/// Returns `None` (writing nothing) if the bits extend past the end of the data
+pub fn write_bits(
+    data: &mut [u8],
+    bit_offset: usize,
+    bit_width: usize,
+    endianness: Endianness,
+    bit_order: BitOrder,
+    value: u64,
+) -> Option<()> {
+    if bit_width == 0 || bit_width > 64 {
+        return None;
+    }
+
+    let first_byte = bit_offset / 8;
+    let local_offset = bit_offset % 8;
+    let span = (local_offset + bit_width).div_ceil(8);
+    let bytes = data.get_mut(first_byte..first_byte.checked_add(span)?)?;
+
+    let container = match endianness {
+        Endianness::Little => bytes.iter().rev().fold(0u128, |acc, &b| (acc << 8) | b as u128),
+        Endianness::Big => bytes.iter().fold(0u128, |acc, &b| (acc << 8) | b as u128),
+    };
+
+    let shift = match bit_order {
+        BitOrder::LsbFirst => local_offset,
+        BitOrder::MsbFirst => span * 8 - local_offset - bit_width,
+    };
+    let mask = ((1u128 << bit_width) - 1) << shift;
+    let container = (container & !mask) | ((value as u128) << shift & mask);
+
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        let shift = match endianness {
+            Endianness::Little => i * 8,
+            Endianness::Big => (span - 1 - i) * 8,
+        };
+        *byte = (container >> shift) as u8;
+    }
+    Some(())
+}
+
+/// Why text couldn't be written as a value of a data type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The text isn't a value of the type
+    Invalid(String),
+    /// The number is valid but doesn't fit in the type
+    OutOfRange { value: String, type_name: &'static str },
+    /// The text is longer than the field
+    TooLong { len: usize, max: usize },
+    /// Values of this type can't be entered as text
+    Unsupported(&'static str),
+    /// The field extends past the end of the data
+    OutOfBounds,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Invalid(message) => write!(f, "{}", message),
+            ParseError::OutOfRange { value, type_name } => write!(f, "{} is out of range for {}", value, type_name),
+            ParseError::TooLong { len, max } => write!(f, "{} bytes don't fit in {}", len, max),
+            ParseError::Unsupported(type_name) => write!(f, "{} values can't be edited", type_name),
+            ParseError::OutOfBounds => write!(f, "field extends past the end of the data"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parse an integer written in decimal or with a `0x`, `0o` or `0b` prefix, optionally negative
+/// Digit group separators (`_` and `,`) are ignored
+fn parse_integer(text: &str) -> Option<i128> {
+    let text: String = text.trim().chars().filter(|&c| c != '_' && c != ',').collect();
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(&text)),
+    };
+    let lower = digits.to_ascii_lowercase();
+    let (radix, digits) = if let Some(hex) = lower.strip_prefix("0x") {
+        (16, hex)
+    } else if let Some(octal) = lower.strip_prefix("0o") {
+        (8, octal)
+    } else if let Some(binary) = lower.strip_prefix("0b") {
+        (2, binary)
+    } else {
+        (10, lower.as_str())
+    };
+    // Reject a second sign after the prefix, which from_str_radix would accept
+    if digits.starts_with(['+', '-']) {
+        return None;
+    }
+    let magnitude = i128::from_str_radix(digits, radix).ok()?;
+    Some(if negative { -magnitude } else { magnitude })
+}
+
+/// Maximum number of bytes rendered by `read_value` for a byte array
+const MAX_INLINE_BYTES: usize = 32;
+
+/// Render bytes as a quoted string, escaping anything that isn't printable ASCII
+fn escape_bytes(bytes: &[u8]) -> String {
+    let mut out = String::from("\"");
+    for &b in bytes {
+        match b {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            b'\n' => out.push_str("\\n"),
+            b'\r' => out.push_str("\\r"),
+            b'\t' => out.push_str("\\t"),
+            0 => out.push_str("\\0"),
+            0x20..=0x7E => out.push(b as char),
+            _ => out.push_str(&format!("\\x{:02X}", b)),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Seconds between 1601-01-01 (the FILETIME epoch) and 1970-01-01
+const FILETIME_UNIX_OFFSET: i128 = 11_644_473_600;
+
+/// Render seconds since 1970-01-01 as a UTC date and time, or `None` outside years 1..=9999
+fn format_unix_time(seconds: i128) -> Option<String> {
+    let days = seconds.div_euclid(86_400);
+    let secs = seconds.rem_euclid(86_400);
+
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i128::from(month <= 2);
+
+    if !(1..=9999).contains(&year) {
+        return None;
+    }
+    Some(format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    ))
+}
+
+/// Unsigned integer width used by the parts of composite types
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IntType {
+    U8,
+    U16,
+    #[default]
+    U32,
+    U64,
+}
+
+impl IntType {
+    /// Get the name of this type as a string
+    pub fn name(&self) -> &'static str {
+        self.data_type().name()
+    }
+
+    /// Get the size of this type in bytes
+    pub fn size(&self) -> usize {
+        self.data_type().size()
+    }
+
+    /// Get the equivalent scalar data type
+    pub fn data_type(&self) -> DataType {
+        match self {
+            IntType::U8 => DataType::U8,
+            IntType::U16 => DataType::U16,
+            IntType::U32 => DataType::U32,
+            IntType::U64 => DataType::U64,
+        }
+    }
+
+    /// Get all available integer widths
+    pub fn all() -> &'static [IntType] {
+        &[IntType::U8, IntType::U16, IntType::U32, IntType::U64]
+    }
+}
+
+/// Algorithm a checksum field is computed with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChecksumAlgorithm {
+    /// CRC-32 as used by zip and PNG
+    #[default]
+    Crc32,
+    /// CRC-16/CCITT-FALSE
+    Crc16,
+    /// Sum of the bytes modulo 256
+    Sum8,
+}
+
+impl ChecksumAlgorithm {
+    /// Get the display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Crc32 => "CRC-32",
+            ChecksumAlgorithm::Crc16 => "CRC-16",
+            ChecksumAlgorithm::Sum8 => "Sum8",
+        }
+    }
+
+    /// Get the integer type the checksum is stored as
+    pub fn int_type(&self) -> IntType {
+        match self {
+            ChecksumAlgorithm::Crc32 => IntType::U32,
+            ChecksumAlgorithm::Crc16 => IntType::U16,
+            ChecksumAlgorithm::Sum8 => IntType::U8,
+        }
+    }
+
+    /// Compute the checksum of `data`
+    pub fn compute(&self, data: &[u8]) -> u64 {
+        match self {
+            ChecksumAlgorithm::Crc32 => crate::checksum::crc32(data) as u64,
+            ChecksumAlgorithm::Crc16 => crate::checksum::crc16_ccitt(data) as u64,
+            ChecksumAlgorithm::Sum8 => crate::checksum::sum8(data) as u64,
+        }
+    }
+
+    /// Get all available algorithms
+    pub fn all() -> &'static [ChecksumAlgorithm] {
+        &[ChecksumAlgorithm::Crc32, ChecksumAlgorithm::Crc16, ChecksumAlgorithm::Sum8]
     }
 }
 
@@ -29,6 +472,50 @@ pub enum DataType {
     // Floating point
     F32,
     F64,
+    // Network addresses; IPv4 is a 32-bit integer in the field's byte order, usually big-endian
+    // (network order), but little-endian when stored as a native integer
+    MacAddr,
+    Ipv4,
+    Ipv6,
+    // Text padded with NULs to exactly `len` bytes
+    FixedString {
+        len: usize,
+    },
+    // NUL-terminated text within a region of `max_len` bytes
+    CString {
+        max_len: usize,
+    },
+    // Opaque bytes
+    Bytes {
+        len: usize,
+    },
+    // Offset of a region elsewhere in the file, directly followed by its length in bytes
+    SliceDescriptor {
+        ptr_type: IntType,
+        len_type: IntType,
+    },
+    // Seconds since 1970-01-01 UTC, unsigned 32-bit
+    UnixTimestamp32,
+    // Seconds since 1970-01-01 UTC, signed 64-bit
+    UnixTimestamp64,
+    // Windows FILETIME: 100 ns intervals since 1601-01-01 UTC
+    FileTime64,
+    // Record of child fields (stored on the `Field`) spanning `size` bytes
+    Struct {
+        size: usize,
+    },
+    // Bit field starting `bit_offset` bits into the field's first byte
+    Bits {
+        bit_offset: usize,
+        bit_width: usize,
+        bit_order: BitOrder,
+    },
+    // Stored checksum of the `len` bytes at `start`, which is placed like a field offset
+    Checksum {
+        algorithm: ChecksumAlgorithm,
+        start: usize,
+        len: usize,
+    },
 }
 
 impl DataType {
@@ -37,8 +524,15 @@ impl DataType {
         match self {
             DataType::U8 | DataType::I8 => 1,
             DataType::U16 | DataType::I16 => 2,
-            DataType::U32 | DataType::I32 | DataType::F32 => 4,
-            DataType::U64 | DataType::I64 | DataType::F64 => 8,
+            DataType::U32 | DataType::I32 | DataType::F32 | DataType::Ipv4 | DataType::UnixTimestamp32 => 4,
+            DataType::MacAddr => 6,
+            DataType::U64 | DataType::I64 | DataType::F64 | DataType::UnixTimestamp64 | DataType::FileTime64 => 8,
+            DataType::Ipv6 => 16,
+            DataType::FixedString { len } | DataType::Bytes { len } | DataType::Struct { size: len } => *len,
+            DataType::CString { max_len } => *max_len,
+            DataType::SliceDescriptor { ptr_type, len_type } => ptr_type.size() + len_type.size(),
+            DataType::Bits { bit_offset, bit_width, .. } => bit_offset.saturating_add(*bit_width).div_ceil(8),
+            DataType::Checksum { algorithm, .. } => algorithm.int_type().size(),
         }
     }
 
@@ -55,12 +549,388 @@ impl DataType {
             DataType::I64 => "i64",
             DataType::F32 => "f32",
             DataType::F64 => "f64",
+            DataType::MacAddr => "mac",
+            DataType::Ipv4 => "ipv4",
+            DataType::Ipv6 => "ipv6",
+            DataType::FixedString { .. } => "str",
+            DataType::CString { .. } => "cstr",
+            DataType::Bytes { .. } => "bytes",
+            DataType::SliceDescriptor { .. } => "slice",
+            DataType::UnixTimestamp32 => "unix32",
+            DataType::UnixTimestamp64 => "unix64",
+            DataType::FileTime64 => "filetime",
+            DataType::Struct { .. } => "struct",
+            DataType::Bits { .. } => "bits",
+            DataType::Checksum { .. } => "checksum",
+        }
+    }
+
+    /// Check if this type needs extra parameters beyond its kind (e.g. bit offset and width)
+    pub fn is_parameterized(&self) -> bool {
+        matches!(
+            self,
+            DataType::Bits { .. } | DataType::SliceDescriptor { .. } | DataType::Struct { .. } | DataType::Checksum { .. }
+        ) || self.is_byte_string()
+    }
+
+    /// Get the byte length of a string or byte array type
+    pub fn byte_len(&self) -> Option<usize> {
+        match self {
+            DataType::FixedString { len } | DataType::Bytes { len } => Some(*len),
+            DataType::CString { max_len } => Some(*max_len),
+            _ => None,
+        }
+    }
+
+    /// Check if two types are the same kind, ignoring parameters
+    pub fn same_kind(&self, other: &DataType) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    /// Look up a type by its name (as returned by `name()`), ignoring case
+    pub fn from_name(name: &str) -> Option<DataType> {
+        DataType::all()
+            .iter()
+            .copied()
+            .filter(|dt| !dt.is_parameterized())
+            .find(|dt| dt.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Check if this is an integer type
+    pub fn is_integer(&self) -> bool {
+        !self.is_float()
+            && !self.is_address()
+            && !self.is_slice()
+            && !self.is_byte_string()
+            && !self.is_timestamp()
+            && !self.is_struct()
+            && !self.is_checksum()
+    }
+
+    /// Check if this is a checksum over a region of the file
+    pub fn is_checksum(&self) -> bool {
+        matches!(self, DataType::Checksum { .. })
+    }
+
+    /// Get the type with the region a checksum covers shifted by `base`, like the field itself
+    pub fn placed(&self, base: usize) -> DataType {
+        match *self {
+            DataType::Checksum { algorithm, start, len } => DataType::Checksum {
+                algorithm,
+                start: start.saturating_add(base),
+                len,
+            },
+            data_type => data_type,
+        }
+    }
+
+    /// Get this byte string type with its length replaced by `len`, other types unchanged
+    pub fn with_len(&self, len: usize) -> DataType {
+        match *self {
+            DataType::FixedString { .. } => DataType::FixedString { len },
+            DataType::CString { .. } => DataType::CString { max_len: len },
+            DataType::Bytes { .. } => DataType::Bytes { len },
+            data_type => data_type,
+        }
+    }
+
+    /// Compare a stored checksum with the one computed over its region
+    /// Returns the stored and computed values, or `None` if either can't be read
+    pub fn verify_checksum(&self, data: &[u8], offset: usize, endianness: Endianness) -> Option<(u64, u64)> {
+        let DataType::Checksum { algorithm, start, len } = *self else {
+            return None;
+        };
+        let stored = algorithm.int_type().data_type().read_integer(data, offset, endianness)? as u64;
+        let region = data.get(start..start.checked_add(len)?)?;
+        Some((stored, algorithm.compute(region)))
+    }
+
+    /// Check if this is a record of child fields
+    pub fn is_struct(&self) -> bool {
+        matches!(self, DataType::Struct { .. })
+    }
+
+    /// Check if this is a timestamp type
+    pub fn is_timestamp(&self) -> bool {
+        matches!(self, DataType::UnixTimestamp32 | DataType::UnixTimestamp64 | DataType::FileTime64)
+    }
+
+    /// Get the integer type a timestamp is stored as
+    pub fn timestamp_int_type(&self) -> Option<DataType> {
+        match self {
+            DataType::UnixTimestamp32 => Some(DataType::U32),
+            DataType::UnixTimestamp64 => Some(DataType::I64),
+            DataType::FileTime64 => Some(DataType::U64),
+            _ => None,
         }
     }
 
+    /// Read the raw integer behind a timestamp at the given offset
+    /// Returns `None` for other types or if the read would go past the end of the data
+    pub fn read_timestamp_raw(&self, data: &[u8], offset: usize, endianness: Endianness) -> Option<i128> {
+        self.timestamp_int_type()?.read_integer(data, offset, endianness)
+    }
+
+    /// Check if this is a string or byte array type
+    pub fn is_byte_string(&self) -> bool {
+        matches!(self, DataType::FixedString { .. } | DataType::CString { .. } | DataType::Bytes { .. })
+    }
+
+    /// Check if this is an offset+length slice descriptor
+    pub fn is_slice(&self) -> bool {
+        matches!(self, DataType::SliceDescriptor { .. })
+    }
+
+    /// Read the target offset and length of a slice descriptor at the given offset
+    /// Returns `None` for other types or if the read would go past the end of the data
+    pub fn read_slice(&self, data: &[u8], offset: usize, endianness: Endianness) -> Option<(u64, u64)> {
+        let DataType::SliceDescriptor { ptr_type, len_type } = *self else {
+            return None;
+        };
+        let target = ptr_type.data_type().read_integer(data, offset, endianness)?;
+        let len = len_type
+            .data_type()
+            .read_integer(data, offset.checked_add(ptr_type.size())?, endianness)?;
+        Some((target as u64, len as u64))
+    }
+
+    /// Check if this is a floating point type
+    pub fn is_float(&self) -> bool {
+        matches!(self, DataType::F32 | DataType::F64)
+    }
+
+    /// Check if this is a network address type
+    pub fn is_address(&self) -> bool {
+        matches!(self, DataType::MacAddr | DataType::Ipv4 | DataType::Ipv6)
+    }
+
+    /// Read an integer of this type from bytes at the given offset
+    /// Returns `None` for non-integer types or if the read would go past the end of the data
+    pub fn read_integer(&self, data: &[u8], offset: usize, endianness: Endianness) -> Option<i128> {
+        if !self.is_integer() || offset.checked_add(self.size())? > data.len() {
+            return None;
+        }
+
+        if let DataType::Bits { bit_offset, bit_width, bit_order } = *self {
+            return read_bits(data, offset * 8 + bit_offset, bit_width, endianness, bit_order).map(|v| v as i128);
+        }
+
+        let bytes = &data[offset..offset + self.size()];
+
+        // Assemble the raw bits as a big-endian u64
+        let raw = match endianness {
+            Endianness::Little => bytes.iter().rev().fold(0u64, |acc, &b| (acc << 8) | b as u64),
+            Endianness::Big => bytes.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64),
+        };
+
+        Some(match self {
+            DataType::I8 => raw as i8 as i128,
+            DataType::I16 => raw as i16 as i128,
+            DataType::I32 => raw as i32 as i128,
+            DataType::I64 => raw as i64 as i128,
+            _ => raw as i128,
+        })
+    }
+
+    /// Get the number of bits an integer of this type occupies
+    pub fn bit_width(&self) -> usize {
+        match self {
+            DataType::Bits { bit_width, .. } => *bit_width,
+            _ => self.size() * 8,
+        }
+    }
+
+    /// Read a value of this type, rendering integers in `format` and floats in `float_format`
+    pub fn read_value_as(
+        &self,
+        data: &[u8],
+        offset: usize,
+        endianness: Endianness,
+        format: NumberFormat,
+        float_format: FloatFormat,
+    ) -> Option<String> {
+        if self.is_float() {
+            let value = self.read_float(data, offset, endianness)?;
+            return Some(float_format.format(value, self.bit_width()));
+        }
+        if format == NumberFormat::Decimal || !self.is_integer() {
+            return self.read_value(data, offset, endianness);
+        }
+        let value = self.read_integer(data, offset, endianness)?;
+        Some(format.format(value, self.bit_width()))
+    }
+
+    /// Read a float of this type from bytes at the given offset
+    /// Returns `None` for non-float types or if the read would go past the end of the data
+    pub fn read_float(&self, data: &[u8], offset: usize, endianness: Endianness) -> Option<f64> {
+        let bytes = data.get(offset..offset.checked_add(self.size())?)?;
+        match self {
+            DataType::F32 => {
+                let bytes: [u8; 4] = bytes.try_into().ok()?;
+                Some(match endianness {
+                    Endianness::Little => f32::from_le_bytes(bytes),
+                    Endianness::Big => f32::from_be_bytes(bytes),
+                } as f64)
+            }
+            DataType::F64 => {
+                let bytes: [u8; 8] = bytes.try_into().ok()?;
+                Some(match endianness {
+                    Endianness::Little => f64::from_le_bytes(bytes),
+                    Endianness::Big => f64::from_be_bytes(bytes),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Parse `input` as a value of this type and write its encoding at `offset`
+    /// Integers accept decimal, `0x`, `0o` and `0b` notation and timestamps their raw stored number;
+    /// strings are padded with zero bytes and byte arrays take hex digits for every byte
+    /// On error the data is left unchanged
+    pub fn write_value(
+        &self,
+        data: &mut [u8],
+        offset: usize,
+        endianness: Endianness,
+        input: &str,
+    ) -> Result<(), ParseError> {
+        let end = offset.checked_add(self.size()).ok_or(ParseError::OutOfBounds)?;
+        if end > data.len() {
+            return Err(ParseError::OutOfBounds);
+        }
+        let input = input.trim();
+
+        // Timestamps are entered as the integer they're stored as
+        if let Some(int_type) = self.timestamp_int_type() {
+            return int_type.write_value(data, offset, endianness, input);
+        }
+
+        let bytes: Vec<u8> = match *self {
+            DataType::Bits { bit_offset, bit_width, bit_order } => {
+                // Widths outside 1..=64 can come from a hand-edited schema file
+                if !(1..=64).contains(&bit_width) {
+                    return Err(ParseError::Invalid(format!("bit fields are 1 to 64 bits wide, not {}", bit_width)));
+                }
+                let value = parse_integer(input)
+                    .ok_or_else(|| ParseError::Invalid(format!("`{}` is not a number", input)))?;
+                let max = (1u128 << bit_width) - 1;
+                if !(0..=max as i128).contains(&value) {
+                    return Err(ParseError::OutOfRange { value: value.to_string(), type_name: "this bit field" });
+                }
+                write_bits(data, offset * 8 + bit_offset, bit_width, endianness, bit_order, value as u64)
+                    .ok_or(ParseError::OutOfBounds)?;
+                return Ok(());
+            }
+            DataType::U8 | DataType::U16 | DataType::U32 | DataType::U64 | DataType::I8 | DataType::I16
+            | DataType::I32 | DataType::I64 => {
+                let value = parse_integer(input)
+                    .ok_or_else(|| ParseError::Invalid(format!("`{}` is not a number", input)))?;
+                let bits = self.size() * 8;
+                let signed = matches!(self, DataType::I8 | DataType::I16 | DataType::I32 | DataType::I64);
+                let (min, max) = if signed {
+                    (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1)
+                } else {
+                    (0, (1i128 << bits) - 1)
+                };
+                if !(min..=max).contains(&value) {
+                    return Err(ParseError::OutOfRange { value: value.to_string(), type_name: self.name() });
+                }
+                let le = (value as u64).to_le_bytes();
+                let mut bytes = le[..self.size()].to_vec();
+                if endianness == Endianness::Big {
+                    bytes.reverse();
+                }
+                bytes
+            }
+            DataType::F32 | DataType::F64 => {
+                let value: f64 = input
+                    .parse()
+                    .map_err(|_| ParseError::Invalid(format!("`{}` is not a number", input)))?;
+                if *self == DataType::F32 {
+                    let single = value as f32;
+                    if single.is_infinite() && value.is_finite() {
+                        return Err(ParseError::OutOfRange { value: input.to_string(), type_name: "f32" });
+                    }
+                    match endianness {
+                        Endianness::Little => single.to_le_bytes().to_vec(),
+                        Endianness::Big => single.to_be_bytes().to_vec(),
+                    }
+                } else {
+                    match endianness {
+                        Endianness::Little => value.to_le_bytes().to_vec(),
+                        Endianness::Big => value.to_be_bytes().to_vec(),
+                    }
+                }
+            }
+            DataType::Ipv4 => {
+                let addr: Ipv4Addr = input
+                    .parse()
+                    .map_err(|_| ParseError::Invalid(format!("`{}` is not an IPv4 address", input)))?;
+                // Encoded as the integer `read_value` decodes it from
+                let value = u32::from(addr);
+                match endianness {
+                    Endianness::Little => value.to_le_bytes().to_vec(),
+                    Endianness::Big => value.to_be_bytes().to_vec(),
+                }
+            }
+            DataType::Ipv6 => {
+                let addr: Ipv6Addr = input
+                    .parse()
+                    .map_err(|_| ParseError::Invalid(format!("`{}` is not an IPv6 address", input)))?;
+                addr.octets().to_vec()
+            }
+            DataType::MacAddr => {
+                let invalid = || ParseError::Invalid(format!("`{}` is not a MAC address", input));
+                let bytes = input
+                    .split([':', '-'])
+                    .map(|part| if part.len() == 2 { u8::from_str_radix(part, 16).ok() } else { None })
+                    .collect::<Option<Vec<u8>>>()
+                    .ok_or_else(invalid)?;
+                if bytes.len() != 6 {
+                    return Err(invalid());
+                }
+                bytes
+            }
+            DataType::FixedString { len } | DataType::CString { max_len: len } => {
+                // Accept the quoted form the value is displayed in
+                let text = input
+                    .strip_prefix('"')
+                    .and_then(|rest| rest.strip_suffix('"'))
+                    .unwrap_or(input);
+                // A C string needs room for its terminator
+                let max = if matches!(self, DataType::CString { .. }) { len.saturating_sub(1) } else { len };
+                if text.len() > max {
+                    return Err(ParseError::TooLong { len: text.len(), max });
+                }
+                let mut bytes = text.as_bytes().to_vec();
+                bytes.resize(len, 0);
+                bytes
+            }
+            DataType::Bytes { len } => {
+                let digits: String = input.split_whitespace().collect();
+                if !digits.is_ascii() || !digits.len().is_multiple_of(2) {
+                    return Err(ParseError::Invalid("expected pairs of hex digits".to_string()));
+                }
+                let bytes = (0..digits.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).ok())
+                    .collect::<Option<Vec<u8>>>()
+                    .ok_or_else(|| ParseError::Invalid("expected pairs of hex digits".to_string()))?;
+                if bytes.len() != len {
+                    return Err(ParseError::Invalid(format!("expected {} bytes, got {}", len, bytes.len())));
+                }
+                bytes
+            }
+            _ => return Err(ParseError::Unsupported(self.name())),
+        };
+
+        data[offset..end].copy_from_slice(&bytes);
+        Ok(())
+    }
+
     /// Read a value of this type from bytes at the given offset
     pub fn read_value(&self, data: &[u8], offset: usize, endianness: Endianness) -> Option<String> {
-        if offset + self.size() > data.len() {
+        if offset.checked_add(self.size())? > data.len() {
             return None;
         }
 
@@ -120,14 +990,85 @@ impl DataType {
                     Endianness::Little => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
                     Endianness::Big => f32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
                 };
-                value.to_string()
+                FloatFormat::default().format(value as f64, 32)
             }
             DataType::F64 => {
                 let value = match endianness {
                     Endianness::Little => f64::from_le_bytes(bytes.try_into().unwrap()),
                     Endianness::Big => f64::from_be_bytes(bytes.try_into().unwrap()),
                 };
-                value.to_string()
+                FloatFormat::default().format(value, 64)
+            }
+
+            // MAC and IPv6 addresses are byte strings; only IPv4 is sometimes stored as a native integer
+            DataType::MacAddr => bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<Vec<_>>()
+                .join(":"),
+            DataType::Ipv4 => {
+                let octets: [u8; 4] = bytes.try_into().unwrap();
+                let value = match endianness {
+                    Endianness::Little => u32::from_le_bytes(octets),
+                    Endianness::Big => u32::from_be_bytes(octets),
+                };
+                Ipv4Addr::from(value).to_string()
+            }
+            DataType::Ipv6 => {
+                let octets: [u8; 16] = bytes.try_into().unwrap();
+                Ipv6Addr::from(octets).to_string()
+            }
+
+            DataType::FixedString { .. } => {
+                let end = bytes.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+                escape_bytes(&bytes[..end])
+            }
+            DataType::CString { .. } => {
+                match bytes.iter().position(|&b| b == 0) {
+                    Some(end) => escape_bytes(&bytes[..end]),
+                    // Unterminated within the region
+                    None => format!("{}…", escape_bytes(bytes)),
+                }
+            }
+            DataType::Bytes { .. } => {
+                let mut hex: Vec<String> =
+                    bytes.iter().take(MAX_INLINE_BYTES).map(|b| format!("{:02X}", b)).collect();
+                if bytes.len() > MAX_INLINE_BYTES {
+                    hex.push("…".to_string());
+                }
+                hex.join(" ")
+            }
+
+            DataType::SliceDescriptor { .. } => {
+                let (target, len) = self.read_slice(data, offset, endianness)?;
+                format!("@0x{:X} +{} bytes", target, len)
+            }
+
+            DataType::UnixTimestamp32 | DataType::UnixTimestamp64 | DataType::FileTime64 => {
+                let raw = self.read_timestamp_raw(data, offset, endianness)?;
+                let seconds = match self {
+                    DataType::FileTime64 => raw.div_euclid(10_000_000) - FILETIME_UNIX_OFFSET,
+                    _ => raw,
+                };
+                format_unix_time(seconds).unwrap_or_else(|| format!("invalid time ({})", raw))
+            }
+
+            // The members are stored on the field, which renders them
+            DataType::Struct { .. } => "{…}".to_string(),
+
+            DataType::Bits { bit_offset, bit_width, bit_order } => {
+                read_bits(data, offset * 8 + bit_offset, *bit_width, endianness, *bit_order)?.to_string()
+            }
+
+            DataType::Checksum { algorithm, .. } => {
+                let digits = algorithm.int_type().size() * 2;
+                match self.verify_checksum(data, offset, endianness) {
+                    Some((stored, computed)) if stored == computed => format!("OK (0x{:0digits$X})", stored),
+                    Some((stored, computed)) => {
+                        format!("MISMATCH (expected 0x{:0digits$X}, got 0x{:0digits$X})", computed, stored)
+                    }
+                    None => "(checksum range out of bounds)".to_string(),
+                }
             }
         })
     }
@@ -145,6 +1086,173 @@ impl DataType {
             DataType::I64,
             DataType::F32,
             DataType::F64,
+            DataType::MacAddr,
+            DataType::Ipv4,
+            DataType::Ipv6,
+            DataType::FixedString { len: 16 },
+            DataType::CString { max_len: 16 },
+            DataType::Bytes { len: 16 },
+            DataType::SliceDescriptor {
+                ptr_type: IntType::U32,
+                len_type: IntType::U32,
+            },
+            DataType::UnixTimestamp32,
+            DataType::UnixTimestamp64,
+            DataType::FileTime64,
+            DataType::Bits {
+                bit_offset: 0,
+                bit_width: 1,
+                bit_order: BitOrder::MsbFirst,
+            },
+            DataType::Checksum {
+                algorithm: ChecksumAlgorithm::Crc32,
+                start: 0,
+                len: 0,
+            },
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write `input` into a zeroed buffer and read it back
+    fn round_trip(data_type: DataType, endianness: Endianness, input: &str) -> (Vec<u8>, Option<String>) {
+        let mut data = vec![0; data_type.size()];
+        data_type.write_value(&mut data, 0, endianness, input).unwrap();
+        let value = data_type.read_value(&data, 0, endianness);
+        (data, value)
+    }
+
+    #[test]
+    fn ipv4_is_read_in_the_field_byte_order() {
+        let data = [0xC0, 0xA8, 0x00, 0x01];
+        assert_eq!(DataType::Ipv4.read_value(&data, 0, Endianness::Big).as_deref(), Some("192.168.0.1"));
+        assert_eq!(DataType::Ipv4.read_value(&data, 0, Endianness::Little).as_deref(), Some("1.0.168.192"));
+    }
+
+    #[test]
+    fn byte_string_addresses_ignore_the_byte_order() {
+        for endianness in [Endianness::Little, Endianness::Big] {
+            let mac = DataType::MacAddr.read_value(&[0x00, 0x1A, 0x2B, 0x3C, 0x4D, 0x5E], 0, endianness);
+            assert_eq!(mac.as_deref(), Some("00:1a:2b:3c:4d:5e"));
+
+            let mut ipv6 = [0; 16];
+            ipv6[..2].copy_from_slice(&[0x20, 0x01]);
+            ipv6[15] = 1;
+            assert_eq!(DataType::Ipv6.read_value(&ipv6, 0, endianness).as_deref(), Some("2001::1"));
+        }
+    }
+
+    #[test]
+    fn addresses_round_trip_in_both_byte_orders() {
+        for endianness in [Endianness::Little, Endianness::Big] {
+            let (_, value) = round_trip(DataType::Ipv4, endianness, "192.168.0.1");
+            assert_eq!(value.as_deref(), Some("192.168.0.1"));
+
+            let (_, value) = round_trip(DataType::MacAddr, endianness, "00:1a:2b:3c:4d:5e");
+            assert_eq!(value.as_deref(), Some("00:1a:2b:3c:4d:5e"));
+
+            let (_, value) = round_trip(DataType::Ipv6, endianness, "fe80::1:2");
+            assert_eq!(value.as_deref(), Some("fe80::1:2"));
+        }
+    }
+
+    #[test]
+    fn ipv4_is_written_in_the_field_byte_order() {
+        let (data, _) = round_trip(DataType::Ipv4, Endianness::Big, "192.168.0.1");
+        assert_eq!(data, [0xC0, 0xA8, 0x00, 0x01]);
+        let (data, _) = round_trip(DataType::Ipv4, Endianness::Little, "192.168.0.1");
+        assert_eq!(data, [0x01, 0x00, 0xA8, 0xC0]);
+    }
+
+    #[test]
+    fn invalid_addresses_are_rejected() {
+        let mut data = [0; 16];
+        assert!(DataType::Ipv4.write_value(&mut data, 0, Endianness::Little, "300.1.1.1").is_err());
+        assert!(DataType::MacAddr.write_value(&mut data, 0, Endianness::Little, "00:11:22").is_err());
+        assert!(DataType::Ipv6.write_value(&mut data, 0, Endianness::Little, "::g").is_err());
+    }
+
+    const BYTE_ORDERS: [Endianness; 2] = [Endianness::Little, Endianness::Big];
+    const BIT_ORDERS: [BitOrder; 2] = [BitOrder::MsbFirst, BitOrder::LsbFirst];
+
+    #[test]
+    fn bits_are_counted_from_the_chosen_end() {
+        let data = [0b1010_0000];
+        assert_eq!(read_bits(&data, 0, 3, Endianness::Little, BitOrder::MsbFirst), Some(0b101));
+        assert_eq!(read_bits(&data, 5, 3, Endianness::Little, BitOrder::LsbFirst), Some(0b101));
+    }
+
+    #[test]
+    fn bits_crossing_bytes_follow_the_byte_order() {
+        // 12 bits starting at bit 4 of 0x12 0x34
+        let data = [0x12, 0x34];
+        assert_eq!(read_bits(&data, 4, 12, Endianness::Big, BitOrder::MsbFirst), Some(0x234));
+        assert_eq!(read_bits(&data, 0, 12, Endianness::Big, BitOrder::LsbFirst), Some(0x234));
+        // Little endian assembles 0x3412
+        assert_eq!(read_bits(&data, 4, 12, Endianness::Little, BitOrder::MsbFirst), Some(0x412));
+        assert_eq!(read_bits(&data, 4, 12, Endianness::Little, BitOrder::LsbFirst), Some(0x341));
+    }
+
+    #[test]
+    fn bits_round_trip_across_bytes() {
+        for endianness in BYTE_ORDERS {
+            for bit_order in BIT_ORDERS {
+                for (bit_offset, bit_width, value) in [(3, 7, 0x55), (5, 13, 0x1ABC), (7, 33, 0x1_2345_6789), (0, 1, 1)] {
+                    let mut data = [0; 8];
+                    write_bits(&mut data, bit_offset, bit_width, endianness, bit_order, value).unwrap();
+                    assert_eq!(read_bits(&data, bit_offset, bit_width, endianness, bit_order), Some(value));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn full_width_bits_round_trip() {
+        for endianness in BYTE_ORDERS {
+            for bit_order in BIT_ORDERS {
+                for bit_offset in [0, 3] {
+                    let mut data = [0; 9];
+                    write_bits(&mut data, bit_offset, 64, endianness, bit_order, u64::MAX - 1).unwrap();
+                    assert_eq!(read_bits(&data, bit_offset, 64, endianness, bit_order), Some(u64::MAX - 1));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn writing_bits_leaves_neighbours_untouched() {
+        for endianness in BYTE_ORDERS {
+            for bit_order in BIT_ORDERS {
+                let mut data = [0xFF; 4];
+                write_bits(&mut data, 6, 12, endianness, bit_order, 0).unwrap();
+                assert_eq!(read_bits(&data, 6, 12, endianness, bit_order), Some(0));
+                // Exactly the 12 written bits were cleared
+                let cleared: u32 = data.iter().map(|b| b.count_zeros()).sum();
+                assert_eq!(cleared, 12);
+                assert_eq!(data[3], 0xFF);
+            }
+        }
+    }
+
+    #[test]
+    fn editing_bit_fields_of_invalid_width_fails() {
+        let mut data = [0; 32];
+        for bit_width in [0, 65, 128, 200] {
+            let data_type = DataType::Bits { bit_offset: 0, bit_width, bit_order: BitOrder::MsbFirst };
+            assert!(data_type.write_value(&mut data, 0, Endianness::Big, "1").is_err());
+        }
+    }
+
+    #[test]
+    fn bits_past_the_end_or_out_of_range_are_rejected() {
+        let mut data = [0; 2];
+        assert_eq!(read_bits(&data, 10, 7, Endianness::Big, BitOrder::MsbFirst), None);
+        assert_eq!(read_bits(&data, 0, 0, Endianness::Big, BitOrder::MsbFirst), None);
+        assert_eq!(read_bits(&data, 0, 65, Endianness::Big, BitOrder::MsbFirst), None);
+        assert_eq!(write_bits(&mut data, 10, 7, Endianness::Big, BitOrder::MsbFirst, 1), None);
+        assert_eq!(data, [0, 0]);
+    }
+}
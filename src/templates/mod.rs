@@ -0,0 +1,61 @@
+//! Built-in schemas for common file formats, embedded in the binary
+
+use crate::schema::Schema;
+
+/// A built-in schema, stored as TOML
+pub struct Template {
+    pub name: &'static str,
+    pub description: &'static str,
+    source: &'static str,
+}
+
+impl Template {
+    /// Parse the template into a schema
+    pub fn schema(&self) -> Result<Schema, toml::de::Error> {
+        Schema::from_toml(self.source)
+    }
+}
+
+/// All built-in templates, in menu order
+pub const TEMPLATES: &[Template] = &[
+    Template {
+        name: "PNG",
+        description: "Signature and IHDR chunk",
+        source: include_str!("png.toml"),
+    },
+    Template {
+        name: "ELF (64-bit)",
+        description: "ELF64 file header, little endian",
+        source: include_str!("elf64.toml"),
+    },
+    Template {
+        name: "ZIP",
+        description: "Local file header of the first entry",
+        source: include_str!("zip.toml"),
+    },
+    Template {
+        name: "BMP",
+        description: "File header and BITMAPINFOHEADER",
+        source: include_str!("bmp.toml"),
+    },
+    Template {
+        name: "WAV",
+        description: "RIFF header and fmt chunk",
+        source: include_str!("wav.toml"),
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn templates_parse_and_reference_known_fields() {
+        for template in TEMPLATES {
+            let schema = template
+                .schema()
+                .unwrap_or_else(|e| panic!("template {} doesn't parse: {}", template.name, e));
+            assert_eq!(schema.check_references(), Vec::new(), "template {} has broken references", template.name);
+        }
+    }
+}
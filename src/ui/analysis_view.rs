@@ -0,0 +1,233 @@
+use crate::analysis::{guess_period, shannon_entropy};
+use crate::binary_data::BinaryData;
+use egui::{Color32, RichText};
+use egui_extras::{Column, TableBuilder};
+use std::ops::Range;
+
+/// Maximum number of bytes scanned by the period guess, to keep it responsive on large files
+const PERIOD_SAMPLE_LIMIT: usize = 1 << 20;
+
+/// Number of period candidates shown
+const PERIOD_CANDIDATES_SHOWN: usize = 10;
+
+/// Height of the histogram and entropy charts
+const CHART_HEIGHT: f32 = 100.0;
+
+/// Byte statistics computed for one version of the data
+struct ByteStats {
+    /// Data generation and entropy block size the stats were computed for
+    key: (u64, usize),
+    histogram: [usize; 256],
+    /// Entropy of each block, in bits per byte
+    entropy: Vec<f64>,
+}
+
+/// Analysis panel with heuristics that help reverse-engineer the loaded binary
+pub struct AnalysisView {
+    /// Largest record size considered by the period guess
+    max_period: usize,
+    /// Cached period candidates from the last run
+    period_candidates: Option<Vec<(usize, f32)>>,
+    /// Byte range the last run covered
+    period_range: Range<usize>,
+    /// Size of the blocks the entropy is computed over
+    entropy_block: usize,
+    /// Cached byte statistics, computed when the section is first shown
+    byte_stats: Option<ByteStats>,
+}
+
+impl AnalysisView {
+    pub fn new() -> Self {
+        Self {
+            max_period: 256,
+            period_candidates: None,
+            period_range: 0..0,
+            entropy_block: 4096,
+            byte_stats: None,
+        }
+    }
+
+    /// Drop cached results (e.g. after a new file is loaded)
+    pub fn invalidate(&mut self) {
+        self.period_candidates = None;
+        self.byte_stats = None;
+    }
+
+    /// Render the analysis panel for the given binary data
+    /// The record size guess covers the hex `selection` if there is one, otherwise the whole file
+    /// Returns the offset to jump to if an entropy block was clicked
+    pub fn show(&mut self, ui: &mut egui::Ui, binary_data: &BinaryData, selection: Option<Range<usize>>) -> Option<usize> {
+        if binary_data.is_empty() {
+            ui.label("No file loaded");
+            return None;
+        }
+
+        self.show_period_guess(ui, binary_data.bytes(), selection);
+
+        ui.separator();
+        egui::CollapsingHeader::new("Byte Distribution")
+            .show(ui, |ui| self.show_byte_stats(ui, binary_data))
+            .body_returned
+            .flatten()
+    }
+
+    /// Render the record size guess section
+    fn show_period_guess(&mut self, ui: &mut egui::Ui, data: &[u8], selection: Option<Range<usize>>) {
+        ui.heading("Record Size Guess");
+        ui.label("Ranks likely repeating record sizes by byte autocorrelation.");
+
+        // A selection narrows the guess down to the records in it
+        let range = selection.filter(|s| s.end <= data.len()).unwrap_or(0..data.len());
+        ui.horizontal(|ui| {
+            ui.label("Max period:");
+            ui.add(egui::DragValue::new(&mut self.max_period).range(1..=4096));
+            let button = if range.len() < data.len() { "Analyze Selection" } else { "Analyze" };
+            if ui.button(button).clicked() {
+                let sample = &data[range.start..range.end.min(range.start + PERIOD_SAMPLE_LIMIT)];
+                self.period_candidates = Some(guess_period(sample, self.max_period));
+                self.period_range = range.clone();
+            }
+        });
+
+        if range.len() > PERIOD_SAMPLE_LIMIT {
+            ui.label(
+                RichText::new(format!("Only the first {} bytes are analyzed.", PERIOD_SAMPLE_LIMIT))
+                    .color(Color32::from_rgb(120, 120, 120))
+                    .italics(),
+            );
+        }
+
+        let Some(candidates) = &self.period_candidates else {
+            return;
+        };
+
+        let analyzed = &self.period_range;
+        if analyzed.len() < data.len() {
+            ui.label(
+                RichText::new(format!("Results for the {} bytes from 0x{:X}:", analyzed.len(), analyzed.start))
+                    .color(Color32::from_rgb(120, 120, 120))
+                    .italics(),
+            );
+        }
+        if candidates.is_empty() {
+            ui.label("Too few bytes to detect repetition.");
+            return;
+        }
+
+        ui.push_id("period_candidates", |ui| {
+            TableBuilder::new(ui)
+                .striped(true)
+                .column(Column::exact(80.0)) // Period
+                .column(Column::exact(80.0)) // Score
+                .column(Column::remainder()) // Bar
+                .header(20.0, |mut header| {
+                    header.col(|ui| {
+                        ui.strong("Period");
+                    });
+                    header.col(|ui| {
+                        ui.strong("Score");
+                    });
+                    header.col(|_| {});
+                })
+                .body(|mut body| {
+                    for &(period, score) in candidates.iter().take(PERIOD_CANDIDATES_SHOWN) {
+                        body.row(18.0, |mut row| {
+                            row.col(|ui| {
+                                ui.label(format!("{} (0x{:X})", period, period));
+                            });
+                            row.col(|ui| {
+                                ui.label(format!("{:.1}%", score * 100.0));
+                            });
+                            row.col(|ui| {
+                                ui.add(egui::ProgressBar::new(score).desired_height(10.0));
+                            });
+                        });
+                    }
+                });
+        });
+    }
+
+    /// Render the byte histogram and the entropy of each block, recomputed when the data changes
+    /// Returns the start of the entropy block that was clicked, if any
+    fn show_byte_stats(&mut self, ui: &mut egui::Ui, binary_data: &BinaryData) -> Option<usize> {
+        ui.horizontal(|ui| {
+            ui.label("Entropy block size:");
+            ui.add(egui::DragValue::new(&mut self.entropy_block).range(16..=1 << 20));
+        });
+
+        let key = (binary_data.generation(), self.entropy_block);
+        if self.byte_stats.as_ref().is_none_or(|stats| stats.key != key) {
+            self.byte_stats = Some(ByteStats {
+                key,
+                histogram: binary_data.histogram(),
+                entropy: binary_data.entropy_windows(self.entropy_block),
+            });
+        }
+        let stats = self.byte_stats.as_ref()?;
+
+        ui.label(format!("Overall entropy: {:.3} bits/byte", shannon_entropy(&stats.histogram)));
+
+        // Histogram: one bar per byte value, scaled to the most frequent one
+        let (rect, response) =
+            ui.allocate_exact_size(egui::vec2(ui.available_width(), CHART_HEIGHT), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+        let max_count = stats.histogram.iter().copied().max().unwrap_or(0).max(1);
+        let bar_width = rect.width() / 256.0;
+        for (value, &count) in stats.histogram.iter().enumerate() {
+            let height = count as f32 / max_count as f32 * rect.height();
+            let left = rect.left() + value as f32 * bar_width;
+            let bar = egui::Rect::from_min_max(
+                egui::pos2(left, rect.bottom() - height),
+                egui::pos2(left + bar_width, rect.bottom()),
+            );
+            painter.rect_filled(bar, 0.0, Color32::from_rgb(100, 150, 255));
+        }
+        if let Some(pointer) = response.hover_pos() {
+            let value = (((pointer.x - rect.left()) / bar_width) as usize).min(255);
+            let count = stats.histogram[value];
+            let share = count as f64 / binary_data.size() as f64 * 100.0;
+            response.on_hover_text(format!("0x{:02X}: {} ({:.2}%)", value, count, share));
+        }
+
+        ui.add_space(4.0);
+        ui.label("Entropy per block (click to jump):");
+
+        // Entropy: one column per pixel, showing the highest block entropy it covers
+        let (rect, response) =
+            ui.allocate_exact_size(egui::vec2(ui.available_width(), CHART_HEIGHT), egui::Sense::click());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+        let blocks = stats.entropy.len();
+        let columns = (rect.width() as usize).max(1);
+        let block_at = |x: f32| (((x - rect.left()) / rect.width() * blocks as f32) as usize).min(blocks - 1);
+        for column in 0..columns {
+            let first = column * blocks / columns;
+            let last = ((column + 1) * blocks / columns).max(first + 1).min(blocks);
+            let entropy = stats.entropy[first..last].iter().copied().fold(0.0, f64::max);
+            let height = (entropy / 8.0) as f32 * rect.height();
+            let x = rect.left() + column as f32;
+            // Shade from blue (structured) to red (compressed or encrypted)
+            let heat = (entropy / 8.0).clamp(0.0, 1.0) as f32;
+            let color = Color32::from_rgb((80.0 + 175.0 * heat) as u8, 120, (255.0 - 175.0 * heat) as u8);
+            painter.line_segment(
+                [egui::pos2(x + 0.5, rect.bottom()), egui::pos2(x + 0.5, rect.bottom() - height)],
+                egui::Stroke::new(1.0, color),
+            );
+        }
+
+        let pointer = response.hover_pos()?;
+        let block = block_at(pointer.x);
+        let start = block * self.entropy_block;
+        let end = (start + self.entropy_block).min(binary_data.size());
+        let clicked = response.clicked();
+        response.on_hover_text(format!("0x{:X}..0x{:X}: {:.3} bits/byte", start, end, stats.entropy[block]));
+        clicked.then_some(start)
+    }
+}
+
+impl Default for AnalysisView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,132 @@
+use super::{HexView, ViewSettings};
+use crate::binary_data::BinaryData;
+use egui::{Color32, RichText};
+use std::collections::HashSet;
+use std::io;
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// Action requested from the comparison toolbar
+#[derive(Debug, Clone)]
+pub enum CompareAction {
+    /// Select this differing range in both views
+    Jump(Range<usize>),
+    /// Stop comparing
+    Close,
+}
+
+/// Second file shown next to the main hex view, with the bytes that differ marked in both
+pub struct CompareView {
+    data: BinaryData,
+    hex_view: HexView,
+    /// Sorted ranges of bytes that differ between the two files
+    diff: Vec<Range<usize>>,
+    /// Generations of the main and compared data the diff was computed for
+    diff_key: Option<(u64, u64)>,
+    /// Top offsets of the main and compared views during the last frame, to tell which one scrolled
+    synced_tops: (usize, usize),
+}
+
+impl CompareView {
+    /// Load the file to compare the main one against
+    /// Also returns the error memory mapping failed with, if the file was read instead
+    pub fn open(path: PathBuf) -> io::Result<(Self, Option<io::Error>)> {
+        let mut data = BinaryData::new();
+        let map_error = data.load_from_file(path)?;
+        let view = Self {
+            data,
+            hex_view: HexView::new(),
+            diff: Vec::new(),
+            diff_key: None,
+            synced_tops: (0, 0),
+        };
+        Ok((view, map_error))
+    }
+
+    /// Get the path of the compared file
+    pub fn file_path(&self) -> Option<&PathBuf> {
+        self.data.file_path()
+    }
+
+    /// Recompute the differences when either file changed, and pass them to both views
+    pub fn update_diff(&mut self, main: &BinaryData, main_view: &mut HexView) {
+        let key = (main.generation(), self.data.generation());
+        if self.diff_key == Some(key) {
+            return;
+        }
+        self.diff_key = Some(key);
+        self.diff = main.diff(&self.data);
+        main_view.set_diff(self.diff.clone(), self.data.size());
+        self.hex_view.set_diff(self.diff.clone(), main.size());
+    }
+
+    /// Render the summary of the differences, with buttons stepping through them from `cursor`
+    pub fn show_toolbar(&self, ui: &mut egui::Ui, cursor: Option<usize>) -> Option<CompareAction> {
+        let mut action = None;
+        ui.horizontal(|ui| {
+            let name = self
+                .file_path()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let differing: usize = self.diff.iter().map(|range| range.len()).sum();
+            ui.label(format!("Comparing with {}:", name));
+            ui.label(
+                RichText::new(format!("{} difference(s), {} byte(s)", self.diff.len(), differing))
+                    .color(Color32::from_rgb(200, 80, 255)),
+            );
+
+            let previous = cursor.and_then(|c| self.diff.iter().rev().find(|range| range.start < c));
+            let next = match cursor {
+                Some(c) => self.diff.iter().find(|range| range.start > c),
+                None => self.diff.first(),
+            };
+            if ui.add_enabled(previous.is_some(), egui::Button::new("◀ Previous")).clicked() {
+                action = previous.cloned().map(CompareAction::Jump);
+            }
+            if ui.add_enabled(next.is_some(), egui::Button::new("Next ▶")).clicked() {
+                action = next.cloned().map(CompareAction::Jump);
+            }
+            if ui.button("Close").clicked() {
+                action = Some(CompareAction::Close);
+            }
+        });
+        action
+    }
+
+    /// Render the compared file, which is read-only
+    /// Its offset column counts from `relative_base` like the main file's
+    /// Returns whether the pointer is over it
+    pub fn show(&mut self, ui: &mut egui::Ui, settings: &ViewSettings, relative_base: Option<usize>) -> bool {
+        ui.push_id("compare_view", |ui| {
+            // Typing is off without focus, and actions referring to fields don't apply here
+            let _ = self.hex_view.show(ui, self.data.bytes(), &[], &HashSet::new(), None, settings, relative_base, false);
+            ui.ui_contains_pointer()
+        })
+        .inner
+    }
+
+    /// Select `range` in the compared view
+    pub fn select_range(&mut self, range: Range<usize>) {
+        let end = range.end.min(self.data.size());
+        if range.start < end {
+            self.hex_view.select_range(range.start..end);
+        } else {
+            self.hex_view.scroll_to_offset(range.start);
+        }
+    }
+
+    /// Make the view that didn't lead this frame follow the other's scroll position
+    /// The compared view leads while the pointer is over it
+    pub fn sync_scroll(&mut self, main_view: &mut HexView, compare_leads: bool) {
+        let tops = (main_view.top_offset(), self.hex_view.top_offset());
+        if compare_leads {
+            if tops.1 != self.synced_tops.1 {
+                main_view.restore_top_offset(tops.1);
+            }
+        } else if tops.0 != self.synced_tops.0 {
+            self.hex_view.restore_top_offset(tops.0);
+        }
+        self.synced_tops = tops;
+    }
+}
@@ -1,65 +1,462 @@
-use crate::schema::Field;
+use super::ViewSettings;
+use crate::analysis::float_looks_suspicious;
+use crate::export::to_hex_string;
+use crate::schema::{DataType, Field, NumberFormat, SchemaWarning, StringPool};
+use crate::watch::ValueHistory;
 use egui::{Color32, RichText, ScrollArea};
 use egui_extras::{Column, TableBuilder};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+/// Number of array elements checked for implausible float values
+const MAX_FLOAT_CHECKS: usize = 64;
+/// Maximum number of member rows shown under an expanded struct
+const MAX_STRUCT_ROWS: usize = 1000;
+/// Indentation of each nesting level of struct members
+const STRUCT_INDENT: f32 = 14.0;
+/// Number of bytes shown in the Bytes column before truncating
+const MAX_ROW_BYTES: usize = 8;
+/// Number of bytes listed in the Bytes column's tooltip
+const MAX_TOOLTIP_BYTES: usize = 64;
+/// Initial widths of the resizable columns: Offset, Name, Type, Value, Bytes and Comment
+pub const DEFAULT_COLUMN_WIDTHS: [f32; 6] = [80.0, 150.0, 80.0, 120.0, 180.0, 200.0];
 
 /// Action to perform on a field
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum FieldAction {
     Select(usize),
     Edit(usize),
     Delete(usize),
+    /// Insert a copy of the field right after it
+    Duplicate(usize),
+    /// Swap the field with the one before it
+    MoveUp(usize),
+    /// Swap the field with the one after it
+    MoveDown(usize),
+    /// Replace the field's comment (edited inline)
+    SetComment(usize, String),
+    /// Start or stop recording the field's value history
+    ToggleWatch(usize),
+    /// Scroll the hex view to a file offset (e.g. a pointer target)
+    GoTo(usize),
+    /// Select this byte range in the hex view
+    SelectRange(Range<usize>),
+    /// Overwrite the bytes at a file offset with an edited value's encoding
+    SetValue { offset: usize, bytes: Vec<u8> },
+}
+
+/// A value being edited in place in the Value column
+struct ValueEdit {
+    field: usize,
+    text: String,
+    /// Why the text last entered couldn't be written
+    error: Option<String>,
+    /// Give the text box keyboard focus on the next frame
+    request_focus: bool,
+}
+
+/// Column the rows can be sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortColumn {
+    Offset,
+    Name,
+    Type,
 }
 
 /// Data view widget showing interpreted fields
-pub struct DataView;
+pub struct DataView {
+    /// Column the rows are sorted by and whether the order is descending; schema order if `None`
+    sort: Option<(SortColumn, bool)>,
+    /// Field row to bring into view on the next frame
+    scroll_target: Option<usize>,
+    /// Field index and how many other places in the file hold its bytes
+    match_count: Option<(usize, usize)>,
+    /// Layout problems of each field, shown as a tooltip on its warning icon
+    warnings: HashMap<usize, Vec<String>>,
+    /// Field whose row was under the mouse pointer during the last frame
+    hovered_field: Option<usize>,
+    /// Field under the hex cursor during the last frame, to scroll only when it changes
+    cursor_field: Option<usize>,
+    /// Struct fields whose members are shown
+    expanded: HashSet<usize>,
+    /// Only fields whose name, label or comment contain this text (ignoring case) are listed
+    filter: String,
+    /// Current widths of the resizable columns, as last laid out
+    column_widths: [f32; 6],
+    /// Value being edited in place, if any
+    value_edit: Option<ValueEdit>,
+    /// Whether the buttons and comment boxes that change fields are disabled
+    schema_locked: bool,
+}
 
 impl DataView {
     pub fn new() -> Self {
-        Self
+        Self {
+            sort: None,
+            scroll_target: None,
+            match_count: None,
+            warnings: HashMap::new(),
+            hovered_field: None,
+            cursor_field: None,
+            expanded: HashSet::new(),
+            filter: String::new(),
+            column_widths: DEFAULT_COLUMN_WIDTHS,
+            value_edit: None,
+            schema_locked: false,
+        }
+    }
+
+    /// Disable the actions that change fields, leaving selection and value editing available
+    pub fn set_schema_locked(&mut self, locked: bool) {
+        self.schema_locked = locked;
+    }
+
+    /// Get the widths of the resizable columns, to persist them
+    pub fn column_widths(&self) -> [f32; 6] {
+        self.column_widths
+    }
+
+    /// Start the resizable columns at saved `widths`; ignored unless there is one width per column
+    pub fn set_column_widths(&mut self, widths: &[f32]) {
+        if let Ok(widths) = <[f32; 6]>::try_from(widths) {
+            self.column_widths = widths.map(|w| w.max(30.0));
+        }
+    }
+
+    /// Show the layout warnings of the fields next to their names
+    pub fn set_warnings(&mut self, fields: &[Field], warnings: &[SchemaWarning]) {
+        self.warnings.clear();
+        for warning in warnings {
+            match *warning {
+                SchemaWarning::Overlap(a, b) => {
+                    for (idx, other) in [(a, b), (b, a)] {
+                        if let Some(other) = fields.get(other) {
+                            let message = format!("Overlaps {}", other.display_name());
+                            self.warnings.entry(idx).or_default().push(message);
+                        }
+                    }
+                }
+                SchemaWarning::OutOfFile(idx) => {
+                    self.warnings
+                        .entry(idx)
+                        .or_default()
+                        .push("Extends past the end of the file".to_string());
+                }
+                SchemaWarning::InvalidBitWidth(idx) => {
+                    self.warnings
+                        .entry(idx)
+                        .or_default()
+                        .push("Bit width must be between 1 and 64".to_string());
+                }
+                SchemaWarning::LengthPastEnd(idx) => {
+                    self.warnings
+                        .entry(idx)
+                        .or_default()
+                        .push("Length runs past the end of the file; cut short".to_string());
+                }
+            }
+        }
+    }
+
+    /// Show a badge with the number of other occurrences of a field's bytes
+    pub fn set_match_count(&mut self, match_count: Option<(usize, usize)>) {
+        self.match_count = match_count;
+    }
+
+    /// Whether any element of a float field decodes to an implausible value
+    fn has_suspicious_float(field: &Field, data: &[u8], max_magnitude: f64) -> bool {
+        if !field.data_type.is_float() {
+            return false;
+        }
+        // Checking the first elements is enough to hint at a wrong interpretation
+        (0..field.count.min(MAX_FLOAT_CHECKS))
+            .filter_map(|i| field.data_type.read_float(data, field.element_offset(i), field.endianness))
+            .any(|value| float_looks_suspicious(value, max_magnitude))
+    }
+
+    /// Check whether a field's value can be edited in place
+    /// Arrays, structs, slices and checksums (which are computed) can't
+    fn is_value_editable(field: &Field, data: &[u8]) -> bool {
+        !field.is_array()
+            && !field.data_type.is_struct()
+            && !field.data_type.is_slice()
+            && !field.data_type.is_checksum()
+            && field.offset.saturating_add(field.size()) <= data.len()
+    }
+
+    /// Start editing a field's value, pre-filled with the value in the notation `write_value` accepts
+    fn start_value_edit(&mut self, idx: usize, field: &Field, data: &[u8]) {
+        let bytes = data.get(field.offset..field.offset + field.size()).unwrap_or_default();
+        let text = match field.data_type {
+            DataType::FixedString { .. } | DataType::CString { .. } => {
+                let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+                String::from_utf8_lossy(&bytes[..len]).into_owned()
+            }
+            DataType::Bytes { .. } => to_hex_string(bytes),
+            data_type => match data_type.read_timestamp_raw(data, field.offset, field.endianness) {
+                Some(raw) => raw.to_string(),
+                None => data_type.read_value(data, field.offset, field.endianness).unwrap_or_default(),
+            },
+        };
+        self.value_edit = Some(ValueEdit {
+            field: idx,
+            text,
+            error: None,
+            request_focus: true,
+        });
+    }
+
+    /// Render the text box of the value being edited
+    /// Enter writes the value if it's valid for the field's type, Escape or clicking elsewhere cancels
+    fn show_value_editor(edit: &mut ValueEdit, ui: &mut egui::Ui, field: &Field, data: &[u8]) -> (bool, Option<FieldAction>) {
+        let mut text_edit = egui::TextEdit::singleline(&mut edit.text).desired_width(f32::INFINITY);
+        if edit.error.is_some() {
+            text_edit = text_edit.text_color(Color32::from_rgb(200, 80, 80));
+        }
+        let mut response = ui.add(text_edit);
+        if let Some(error) = &edit.error {
+            response = response.on_hover_text(error);
+        }
+        if edit.request_focus {
+            response.request_focus();
+            edit.request_focus = false;
+        }
+        if !response.lost_focus() {
+            return (false, None);
+        }
+        if !ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            return (true, None);
+        }
+
+        // Encode into a copy so nothing is written when the text is invalid
+        let mut bytes = data.get(field.offset..field.offset + field.size()).unwrap_or_default().to_vec();
+        match field.data_type.write_value(&mut bytes, 0, field.endianness, &edit.text) {
+            Ok(()) => (true, Some(FieldAction::SetValue { offset: field.offset, bytes })),
+            Err(e) => {
+                edit.error = Some(e.to_string());
+                edit.request_focus = true;
+                (false, None)
+            }
+        }
+    }
+
+    /// Render the bytes a field covers, truncated to a few, with the full span in the tooltip
+    /// Bytes past the end of the data are counted as missing
+    fn show_raw_bytes(ui: &mut egui::Ui, field: &Field, data: &[u8], settings: &ViewSettings) {
+        let end = field.offset.saturating_add(field.size());
+        let present = data.get(field.offset..end.min(data.len())).unwrap_or_default();
+        let missing = field.size() - present.len();
+
+        let mut text = settings.hex_case(to_hex_string(&present[..present.len().min(MAX_ROW_BYTES)]));
+        if present.len() > MAX_ROW_BYTES {
+            text.push_str(" …");
+        }
+
+        let mut tooltip = format!(
+            "{}..{} ({} bytes)",
+            settings.format_offset_prefixed(field.offset, data.len()),
+            settings.format_offset_prefixed(end, data.len()),
+            field.size()
+        );
+        if present.len() > MAX_ROW_BYTES {
+            let listed = &present[..present.len().min(MAX_TOOLTIP_BYTES)];
+            tooltip.push_str(&format!("\n{}", settings.hex_case(to_hex_string(listed))));
+            if present.len() > MAX_TOOLTIP_BYTES {
+                tooltip.push_str(" …");
+            }
+        }
+        if missing > 0 {
+            tooltip.push_str(&format!("\n{} byte(s) past the end of the data", missing));
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(text).monospace().color(Color32::from_rgb(140, 140, 140)))
+                .on_hover_text(&tooltip);
+            if missing > 0 {
+                ui.label(RichText::new(format!("+{} missing", missing)).color(Color32::from_rgb(200, 80, 80)))
+                    .on_hover_text(&tooltip);
+            }
+        });
+    }
+
+    /// Render a read-only row for a member (or array element) of an expanded struct
+    fn show_member_row(
+        mut row: egui_extras::TableRow<'_, '_>,
+        depth: usize,
+        member: &Field,
+        data: &[u8],
+        settings: &ViewSettings,
+        relative_base: Option<usize>,
+    ) {
+        row.col(|ui| {
+            let dim = ui.visuals().weak_text_color();
+            ui.label(RichText::new(settings.format_row_offset_prefixed(member.offset, relative_base, data.len())).color(dim));
+        });
+        row.col(|ui| {
+            ui.horizontal(|ui| {
+                ui.add_space(depth as f32 * STRUCT_INDENT);
+                ui.label(member.display_name());
+            });
+        });
+        row.col(|ui| {
+            ui.label(RichText::new(member.type_name()).color(Color32::from_rgb(80, 150, 200)));
+        });
+        row.col(|ui| {
+            match member.read_value_as(data, settings.number_format, settings.float_format) {
+                Some(value) => ui.label(value),
+                None => ui.label(RichText::new("(out of bounds)").color(Color32::from_rgb(200, 80, 80))),
+            };
+        });
+        row.col(|ui| Self::show_raw_bytes(ui, member, data, settings));
+        row.col(|ui| {
+            ui.label(RichText::new(&member.comment).color(Color32::from_rgb(120, 120, 120)));
+        });
+        row.col(|_| {});
+    }
+
+    /// Get the field indices in display order; sorting is stable, so ties keep schema order
+    fn row_order(&self, fields: &[Field]) -> Vec<usize> {
+        let filter = self.filter.trim().to_lowercase();
+        let mut order: Vec<usize> = (0..fields.len())
+            .filter(|&idx| filter.is_empty() || Self::matches_filter(&fields[idx], &filter))
+            .collect();
+        if let Some((column, descending)) = self.sort {
+            match column {
+                SortColumn::Offset => order.sort_by_key(|&idx| fields[idx].bit_span().start),
+                SortColumn::Name => order.sort_by_cached_key(|&idx| fields[idx].display_name().to_lowercase()),
+                SortColumn::Type => order.sort_by_cached_key(|&idx| fields[idx].type_name()),
+            }
+            if descending {
+                order.reverse();
+            }
+        }
+        order
+    }
+
+    /// Check whether the field's name, label or comment contains `filter`, which is lowercase
+    fn matches_filter(field: &Field, filter: &str) -> bool {
+        field.identifier().to_lowercase().contains(filter)
+            || field.label.as_ref().is_some_and(|label| label.to_lowercase().contains(filter))
+            || field.comment.to_lowercase().contains(filter)
+    }
+
+    /// Render the filter box, with the number of fields listed while it is active
+    fn show_filter(&mut self, ui: &mut egui::Ui, listed: usize, total: usize) {
+        ui.horizontal(|ui| {
+            ui.label("Filter:");
+            ui.add(egui::TextEdit::singleline(&mut self.filter).hint_text("name or comment").desired_width(200.0));
+            if !self.filter.is_empty() {
+                if ui.small_button("✖").on_hover_text("Clear the filter").clicked() {
+                    self.filter.clear();
+                }
+                ui.label(format!("{} of {} fields", listed, total));
+            }
+        });
+    }
+
+    /// Render a column heading that cycles ascending → descending → schema order when clicked
+    fn sort_header(&mut self, ui: &mut egui::Ui, title: &str, column: SortColumn) {
+        let arrow = match self.sort {
+            Some((sorted, false)) if sorted == column => " ▲",
+            Some((sorted, true)) if sorted == column => " ▼",
+            _ => "",
+        };
+        let response = ui
+            .add(egui::Label::new(RichText::new(format!("{}{}", title, arrow)).heading()).sense(egui::Sense::click()))
+            .on_hover_text("Click to sort");
+        if response.clicked() {
+            self.sort = match self.sort {
+                Some((sorted, false)) if sorted == column => Some((column, true)),
+                Some((sorted, true)) if sorted == column => None,
+                _ => Some((column, false)),
+            };
+        }
+    }
+
+    /// Get the index of the field whose row was under the mouse pointer during the last frame
+    pub fn hovered_field(&self) -> Option<usize> {
+        self.hovered_field
+    }
+
+    /// Scroll so that the row of field `idx` becomes visible on the next frame
+    pub fn scroll_to_field(&mut self, idx: usize) {
+        self.scroll_target = Some(idx);
     }
 
     /// Render the data view for the given fields and binary data
     /// Returns an optional action to perform on a field
+    #[allow(clippy::too_many_arguments)]
     pub fn show(
         &mut self,
         ui: &mut egui::Ui,
         fields: &[Field],
         data: &[u8],
         selected_fields: &HashSet<usize>,
+        hovered_field: Option<usize>,
+        cursor_field: Option<usize>,
+        settings: &ViewSettings,
+        relative_base: Option<usize>,
+        watches: &HashMap<usize, ValueHistory>,
+        duplicate_names: &[String],
+        string_pool: Option<&StringPool>,
     ) -> Option<FieldAction> {
         let mut action = None;
+        let mut hovered_row = None;
+        if cursor_field != self.cursor_field {
+            self.cursor_field = cursor_field;
+            if let Some(idx) = cursor_field {
+                self.scroll_target = Some(idx);
+            }
+        }
         if fields.is_empty() {
             ui.label("No fields defined. Add fields to interpret the binary data.");
+            self.hovered_field = None;
             return None;
         }
 
-        ScrollArea::vertical()
+        let rows = self.row_order(fields);
+        self.show_filter(ui, rows.len(), fields.len());
+
+        // Columns widened past the panel scroll horizontally
+        let widths = self.column_widths;
+        let resizable = |i: usize| Column::initial(widths[i]).at_least(30.0).resizable(true).clip(true);
+        ScrollArea::both()
             .id_salt("data_view_scroll")
             .auto_shrink([false, false])
             .show(ui, |ui| {
                 TableBuilder::new(ui)
                     .striped(true)
-                    .column(Column::exact(80.0)) // Offset
-                    .column(Column::exact(150.0)) // Name
-                    .column(Column::exact(80.0)) // Type
-                    .column(Column::exact(120.0)) // Value
-                    .column(Column::remainder().at_least(100.0)) // Comment
-                    .column(Column::exact(120.0)) // Actions
+                    .column(resizable(0)) // Offset
+                    .column(resizable(1)) // Name
+                    .column(resizable(2)) // Type
+                    .column(resizable(3)) // Value
+                    .column(resizable(4)) // Bytes
+                    .column(resizable(5)) // Comment
+                    .column(Column::exact(300.0)) // Actions
                     .header(20.0, |mut header| {
+                        // Each header cell spans its column, so its width is the column's current width
                         header.col(|ui| {
-                            ui.heading("Offset");
+                            self.column_widths[0] = ui.max_rect().width();
+                            self.sort_header(ui, "Offset", SortColumn::Offset);
                         });
                         header.col(|ui| {
-                            ui.heading("Name");
+                            self.column_widths[1] = ui.max_rect().width();
+                            self.sort_header(ui, "Name", SortColumn::Name);
                         });
                         header.col(|ui| {
-                            ui.heading("Type");
+                            self.column_widths[2] = ui.max_rect().width();
+                            self.sort_header(ui, "Type", SortColumn::Type);
                         });
                         header.col(|ui| {
+                            self.column_widths[3] = ui.max_rect().width();
                             ui.heading("Value");
                         });
                         header.col(|ui| {
+                            self.column_widths[4] = ui.max_rect().width();
+                            ui.heading("Bytes");
+                        });
+                        header.col(|ui| {
+                            self.column_widths[5] = ui.max_rect().width();
                             ui.heading("Comment");
                         });
                         header.col(|ui| {
@@ -67,34 +464,73 @@ impl DataView {
                         });
                     })
                     .body(|mut body| {
-                        for (idx, field) in fields.iter().enumerate() {
+                        for idx in rows {
+                            let field = &fields[idx];
                             let is_selected = selected_fields.contains(&idx);
 
                             body.row(18.0, |mut row| {
+                                // Mirror the field hovered in the hex view
+                                row.set_hovered(hovered_field == Some(idx));
+                                // Emphasize the field the hex cursor is in
+                                row.set_selected(cursor_field == Some(idx));
                                 // Offset - clickable to select row
                                 row.col(|ui| {
-                                    let mut text = RichText::new(format!("0x{:08X}", field.offset))
-                                        .color(Color32::from_rgb(100, 100, 100));
+                                    let mut text = RichText::new(settings.format_row_offset_prefixed(field.offset, relative_base, data.len()))
+                                        .color(ui.visuals().weak_text_color());
                                     if is_selected {
                                         text = text.strong();
                                     }
-                                    if ui.selectable_label(is_selected, text).clicked() {
+                                    let response = ui.selectable_label(is_selected, text);
+                                    if response.clicked() {
                                         action = Some(FieldAction::Select(idx));
                                     }
+                                    if self.scroll_target == Some(idx) {
+                                        response.scroll_to_me(Some(egui::Align::Center));
+                                        self.scroll_target = None;
+                                    }
                                 });
 
                                 // Name
                                 row.col(|ui| {
-                                    let mut text = RichText::new(&field.name);
+                                    let mut text = RichText::new(field.display_name());
                                     if is_selected {
                                         text = text.strong();
                                     }
-                                    ui.label(text);
+                                    ui.horizontal(|ui| {
+                                        if field.data_type.is_struct() {
+                                            let expanded = self.expanded.contains(&idx);
+                                            if ui.small_button(if expanded { "▾" } else { "▸" }).clicked() {
+                                                if expanded {
+                                                    self.expanded.remove(&idx);
+                                                } else {
+                                                    self.expanded.insert(idx);
+                                                }
+                                            }
+                                        }
+                                        let response = ui.label(text);
+                                        if field.label.is_some() {
+                                            response.on_hover_text(format!("Identifier: {}", field.identifier()));
+                                        }
+                                        if let Some((match_idx, count)) = self.match_count
+                                            && match_idx == idx
+                                        {
+                                            ui.label(RichText::new(format!("×{}", count)).color(Color32::from_rgb(255, 170, 0)))
+                                                .on_hover_text(format!("{} other occurrence(s) of these bytes", count));
+                                        }
+                                        if duplicate_names.contains(&field.name) {
+                                            ui.label(RichText::new("⚠").color(Color32::from_rgb(220, 160, 60)))
+                                                .on_hover_text("Another field has the same name");
+                                        }
+                                        if let Some(warnings) = self.warnings.get(&idx) {
+                                            ui.label(RichText::new("⚠").color(Color32::from_rgb(200, 80, 80)))
+                                                .on_hover_text(warnings.join("\n"));
+                                        }
+                                    });
                                 });
 
                                 // Type
                                 row.col(|ui| {
-                                    let mut text = RichText::new(field.data_type.name())
+                                    let mut text = RichText::new(field.type_name())
                                         .color(Color32::from_rgb(80, 150, 200));
                                     if is_selected {
                                         text = text.strong();
@@ -104,8 +540,59 @@ impl DataView {
 
                                 // Value
                                 row.col(|ui| {
-                                    let mut text = if let Some(value) = field.read_value(data) {
-                                        RichText::new(value)
+                                    if let Some(edit) = self.value_edit.as_mut().filter(|edit| edit.field == idx) {
+                                        let (done, edited) = Self::show_value_editor(edit, ui, field, data);
+                                        if done {
+                                            self.value_edit = None;
+                                        }
+                                        if edited.is_some() {
+                                            action = edited;
+                                        }
+                                        return;
+                                    }
+
+                                    // String indices resolve through the pool when one is defined
+                                    let value = match string_pool {
+                                        Some(pool) if field.is_string_index => field.read_pool_string(data, pool),
+                                        _ => field.read_value_as(data, settings.number_format, settings.float_format),
+                                    };
+                                    let mut text = if let Some(value) = value {
+                                        // Pointer values and byte arrays are shown in hex
+                                        // Labelled values carry text that mustn't be regrouped or recased
+                                        let is_number = field.data_type.is_integer()
+                                            && !field.is_string_index
+                                            && !field.has_enum_labels();
+                                        let is_bytes = matches!(field.data_type, DataType::Bytes { .. });
+                                        let value = if field.is_pointer || is_bytes {
+                                            settings.hex_case(value)
+                                        } else if is_number {
+                                            match settings.number_format {
+                                                NumberFormat::Decimal if settings.group_thousands => {
+                                                    group_thousands(&value)
+                                                }
+                                                NumberFormat::Hex | NumberFormat::SignedHex => settings.hex_case(value),
+                                                _ => value,
+                                            }
+                                        } else {
+                                            value
+                                        };
+                                        // Hex, octal and binary digits line up between rows in monospace
+                                        let aligned = field.is_pointer
+                                            || is_bytes
+                                            || (is_number && settings.number_format != NumberFormat::Decimal);
+                                        let text = if aligned {
+                                            RichText::new(value).monospace()
+                                        } else {
+                                            RichText::new(value)
+                                        };
+                                        // Checksums are green when they match their region, red otherwise
+                                        match field.data_type.verify_checksum(data, field.offset, field.endianness) {
+                                            Some((stored, computed)) if stored == computed => {
+                                                text.color(Color32::from_rgb(80, 180, 80))
+                                            }
+                                            Some(_) => text.color(Color32::from_rgb(200, 80, 80)),
+                                            None => text,
+                                        }
                                     } else {
                                         RichText::new("(out of bounds)")
                                             .color(Color32::from_rgb(200, 80, 80))
@@ -113,43 +600,256 @@ impl DataView {
                                     if is_selected {
                                         text = text.strong();
                                     }
-                                    ui.label(text);
+                                    let value_text = text.text().to_string();
+                                    ui.horizontal(|ui| {
+                                        if field.is_pointer && field.offset + field.size() <= data.len() {
+                                            ui.menu_button("▾", |ui| {
+                                                if let Some(target) =
+                                                    Self::show_pointer_entries(ui, field, data, settings)
+                                                {
+                                                    action = Some(FieldAction::GoTo(target));
+                                                    ui.close_menu();
+                                                }
+                                            });
+                                        }
+                                        if field.data_type.is_slice() && field.offset + field.size() <= data.len() {
+                                            ui.menu_button("▾", |ui| {
+                                                if let Some(range) = Self::show_slice_entries(ui, field, data, settings) {
+                                                    action = Some(FieldAction::SelectRange(range));
+                                                    ui.close_menu();
+                                                }
+                                            });
+                                        }
+                                        let editable = Self::is_value_editable(field, data);
+                                        let mut response = ui.add(egui::Label::new(text).sense(egui::Sense::click()));
+                                        if editable {
+                                            response = response.on_hover_text("Double-click to edit");
+                                            if response.double_clicked() {
+                                                self.start_value_edit(idx, field, data);
+                                            }
+                                        }
+                                        response.context_menu(|ui| {
+                                            if ui.add_enabled(editable, egui::Button::new("Edit Value")).clicked() {
+                                                self.start_value_edit(idx, field, data);
+                                                ui.close_menu();
+                                            }
+                                            if ui.button("Copy Value").clicked() {
+                                                ui.ctx().copy_text(value_text.clone());
+                                                ui.close_menu();
+                                            }
+                                            let bytes = data.get(field.offset..field.offset.saturating_add(field.size()));
+                                            if ui.add_enabled(bytes.is_some(), egui::Button::new("Copy Bytes as Hex")).clicked() {
+                                                ui.ctx().copy_text(settings.hex_case(to_hex_string(bytes.unwrap_or_default())));
+                                                ui.close_menu();
+                                            }
+                                        });
+                                        if Self::has_suspicious_float(field, data, settings.float_warning_magnitude) {
+                                            ui.label(RichText::new("⚠").color(Color32::from_rgb(220, 160, 60)))
+                                                .on_hover_text("Unlikely float (NaN, infinite, denormal or huge): check the type, endianness and alignment");
+                                        }
+                                        if field.is_string_index && string_pool.is_none() {
+                                            response.on_hover_text("No string pool defined (Schema → String Pool)");
+                                        } else if !field.is_array()
+                                            && let Some(raw) =
+                                                field.data_type.read_timestamp_raw(data, field.offset, field.endianness)
+                                        {
+                                            response.on_hover_text(format!("Raw value: {}", raw));
+                                        }
+                                    });
                                 });
 
-                                // Comment
+                                // Bytes
+                                row.col(|ui| Self::show_raw_bytes(ui, field, data, settings));
+
+                                // Comment, editable in place
                                 row.col(|ui| {
-                                    let text_str = if !field.comment.is_empty() {
-                                        &field.comment
-                                    } else {
-                                        ""
-                                    };
-                                    let mut text = RichText::new(text_str)
-                                        .color(Color32::from_rgb(120, 120, 120))
-                                        .italics();
-                                    if is_selected {
-                                        text = text.strong();
+                                    let mut comment = field.comment.clone();
+                                    let response = ui.add_enabled(
+                                        !self.schema_locked,
+                                        egui::TextEdit::singleline(&mut comment)
+                                            .frame(false)
+                                            .hint_text("add comment")
+                                            .text_color(Color32::from_rgb(120, 120, 120))
+                                            .desired_width(f32::INFINITY),
+                                    );
+                                    if response.changed() {
+                                        action = Some(FieldAction::SetComment(idx, comment));
                                     }
-                                    ui.label(text);
                                 });
 
                                 // Actions
                                 row.col(|ui| {
                                     ui.horizontal(|ui| {
-                                        if ui.button("Edit").clicked() {
-                                            action = Some(FieldAction::Edit(idx));
-                                        }
-                                        if ui.button("Delete").clicked() {
-                                            action = Some(FieldAction::Delete(idx));
+                                        // Locked fields keep their place and definition
+                                        ui.add_enabled_ui(!self.schema_locked, |ui| {
+                                            // Moving only makes sense while rows are shown in schema order
+                                            let unsorted = self.sort.is_none();
+                                            let sorted_hint = "Clear the sort to reorder fields";
+                                            if ui
+                                                .add_enabled(unsorted && idx > 0, egui::Button::new("↑"))
+                                                .on_hover_text("Move up")
+                                                .on_disabled_hover_text(if unsorted { "Already the first field" } else { sorted_hint })
+                                                .clicked()
+                                            {
+                                                action = Some(FieldAction::MoveUp(idx));
+                                            }
+                                            if ui
+                                                .add_enabled(unsorted && idx + 1 < fields.len(), egui::Button::new("↓"))
+                                                .on_hover_text("Move down")
+                                                .on_disabled_hover_text(if unsorted { "Already the last field" } else { sorted_hint })
+                                                .clicked()
+                                            {
+                                                action = Some(FieldAction::MoveDown(idx));
+                                            }
+                                            if ui.button("Edit").clicked() {
+                                                action = Some(FieldAction::Edit(idx));
+                                            }
+                                            if ui.button("Duplicate").on_hover_text("Insert a copy right after this field").clicked() {
+                                                action = Some(FieldAction::Duplicate(idx));
+                                            }
+                                            if ui.button("Delete").clicked() {
+                                                action = Some(FieldAction::Delete(idx));
+                                            }
+                                        });
+                                        if ui
+                                            .selectable_label(watches.contains_key(&idx), "Watch")
+                                            .on_hover_text("Record this field's value as the data changes")
+                                            .clicked()
+                                        {
+                                            action = Some(FieldAction::ToggleWatch(idx));
                                         }
                                     });
                                 });
+
+                                if row.response().contains_pointer() {
+                                    hovered_row = Some(idx);
+                                }
                             });
+
+                            if self.expanded.contains(&idx) {
+                                for (depth, member) in field.descendants(MAX_STRUCT_ROWS) {
+                                    body.row(18.0, |row| Self::show_member_row(row, depth, &member, data, settings, relative_base));
+                                }
+                            }
                         }
                     });
             });
 
+        self.hovered_field = hovered_row;
         action
     }
+
+    /// List each entry of a pointer field with its target and a button to jump there
+    /// Returns the target offset to go to if a button was clicked
+    fn show_pointer_entries(
+        ui: &mut egui::Ui,
+        field: &Field,
+        data: &[u8],
+        settings: &ViewSettings,
+    ) -> Option<usize> {
+        let mut go_to = None;
+
+        ScrollArea::vertical()
+            .id_salt("pointer_entries_scroll")
+            .max_height(300.0)
+            .show(ui, |ui| {
+                egui::Grid::new("pointer_entries").striped(true).show(ui, |ui| {
+                    for i in 0..field.count {
+                        ui.label(format!("[{}]", i));
+                        ui.monospace(
+                            field
+                                .read_pointer(data, i)
+                                .map(|value| settings.hex_case(format!("0x{:X}", value)))
+                                .unwrap_or_default(),
+                        );
+                        match field.pointer_target(data, i) {
+                            Some(target) => {
+                                ui.label(format!("→ {}", settings.format_offset_prefixed(target, data.len())));
+                                if ui.small_button("Go").clicked() {
+                                    go_to = Some(target);
+                                }
+                            }
+                            None => {
+                                ui.label(RichText::new("→ (out of bounds)").color(Color32::from_rgb(200, 80, 80)));
+                                ui.add_enabled(false, egui::Button::new("Go").small());
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+
+        go_to
+    }
+
+    /// List the regions of a slice descriptor field, returning the one to select if clicked
+    fn show_slice_entries(
+        ui: &mut egui::Ui,
+        field: &Field,
+        data: &[u8],
+        settings: &ViewSettings,
+    ) -> Option<Range<usize>> {
+        let mut select = None;
+
+        ScrollArea::vertical()
+            .id_salt("slice_entries_scroll")
+            .max_height(300.0)
+            .show(ui, |ui| {
+                egui::Grid::new("slice_entries").striped(true).show(ui, |ui| {
+                    for i in 0..field.count {
+                        ui.label(format!("[{}]", i));
+                        match field.slice_target(data, i) {
+                            Some(range) => {
+                                ui.label(format!(
+                                    "{}..{}",
+                                    settings.format_offset_prefixed(range.start, data.len()),
+                                    settings.format_offset_prefixed(range.end, data.len())
+                                ));
+                                if ui.small_button("Select target").clicked() {
+                                    select = Some(range);
+                                }
+                            }
+                            None => {
+                                ui.label(RichText::new("(out of bounds)").color(Color32::from_rgb(200, 80, 80)));
+                                ui.add_enabled(false, egui::Button::new("Select target").small());
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+
+        select
+    }
+}
+
+/// Insert thousands separators into the integer numbers of `s` (e.g. `-1234567` → `-1,234,567`)
+/// Digits after a `.` (fractions) or in `0x` literals are left untouched
+fn group_thousands(s: &str) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    let mut out = String::with_capacity(s.len() + s.len() / 3);
+    let mut i = 0;
+    while i < chars.len() {
+        if !chars[i].is_ascii_digit() {
+            out.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < chars.len() && chars[i].is_ascii_alphanumeric() {
+            i += 1;
+        }
+        let run = &chars[start..i];
+        let is_plain_integer = run.iter().all(|c| c.is_ascii_digit()) && (start == 0 || chars[start - 1] != '.');
+        for (idx, &c) in run.iter().enumerate() {
+            if is_plain_integer && idx > 0 && (run.len() - idx).is_multiple_of(3) {
+                out.push(',');
+            }
+            out.push(c);
+        }
+    }
+    out
 }
 
 impl Default for DataView {
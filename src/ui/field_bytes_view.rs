@@ -0,0 +1,61 @@
+use super::ViewSettings;
+use crate::schema::Field;
+use egui::{Color32, RichText, ScrollArea};
+
+/// Maximum number of bytes shown in the strip, so huge blobs stay responsive
+const MAX_STRIP_BYTES: usize = 1024;
+
+/// Compact strip showing just the bytes of one field, indexed from 0
+pub struct FieldBytesView;
+
+impl FieldBytesView {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Render the bytes of `field`
+    pub fn show(&mut self, ui: &mut egui::Ui, field: &Field, data: &[u8], settings: &ViewSettings) {
+        let start = field.offset.min(data.len());
+        let end = (field.offset + field.size()).min(data.len());
+        let bytes = &data[start..end];
+
+        ui.horizontal(|ui| {
+            ui.strong(field.display_name());
+            ui.label(
+                RichText::new(format!("{} bytes @ {}", field.size(), settings.hex_case(format!("0x{:X}", field.offset))))
+                    .color(Color32::from_rgb(120, 120, 120)),
+            );
+            if bytes.len() < field.size() {
+                ui.colored_label(Color32::from_rgb(200, 80, 80), "(truncated by end of file)");
+            }
+        });
+
+        ScrollArea::horizontal()
+            .id_salt("field_bytes_scroll")
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.spacing_mut().item_spacing.x = 6.0;
+                    for (idx, byte) in bytes.iter().take(MAX_STRIP_BYTES).enumerate() {
+                        ui.vertical(|ui| {
+                            ui.label(
+                                RichText::new(format!("{:>2}", idx))
+                                    .monospace()
+                                    .small()
+                                    .color(Color32::from_rgb(120, 120, 120)),
+                            );
+                            ui.label(RichText::new(settings.format_byte(*byte)).monospace());
+                        });
+                    }
+                    if bytes.len() > MAX_STRIP_BYTES {
+                        ui.label("…");
+                    }
+                });
+            });
+    }
+}
+
+impl Default for FieldBytesView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,15 +1,104 @@
-use crate::schema::Field;
+use super::ViewSettings;
+use crate::export::{to_base64, to_c_array, to_hex_string};
+use crate::schema::{self, DataType, Endianness, Field};
 use egui::{Color32, RichText, ScrollArea, TextStyle};
 use std::collections::HashSet;
+use std::ops::Range;
+
+/// Width of the field position gutter next to the scroll area
+const GUTTER_WIDTH: f32 = 10.0;
+
+/// Seconds a jumped-to row stays highlighted
+const FLASH_DURATION: f64 = 1.0;
+
+/// Action requested by the hex view, to be applied by the app
+#[derive(Debug, Clone)]
+pub enum HexAction {
+    /// Write the same byte value at each of the offsets
+    WriteByte { offsets: Vec<usize>, value: u8 },
+    /// Write each byte value at its offset, in order
+    WriteBytes(Vec<(usize, u8)>),
+    /// Open the Add Field dialog at this (uncovered) offset
+    DefineField(usize),
+    /// Open the Add Field dialog covering the selected byte range
+    DefineFieldFromSelection(Range<usize>),
+    /// Open the Add Field dialog at this offset with the type and byte order picked
+    DefineFieldAs {
+        offset: usize,
+        data_type: DataType,
+        endianness: Endianness,
+    },
+    /// Edit the field with this index
+    EditField(usize),
+    /// Delete the field with this index
+    DeleteField(usize),
+}
 
 /// Hexadecimal viewer widget
 pub struct HexView {
-    bytes_per_row: usize,
+    /// Offset to bring into view on the next frame
+    scroll_target: Option<usize>,
+    /// How the target row is aligned once scrolled into view
+    scroll_align: egui::Align,
+    /// Offset of the first row visible during the last frame
+    top_offset: usize,
+    /// Number of rows fully visible during the last frame
+    visible_rows: usize,
+    /// Byte offsets of the active cursors; the last one is the primary cursor
+    cursors: Vec<usize>,
+    /// Other end of the selected byte range, which ends at the primary cursor
+    selection_anchor: Option<usize>,
+    /// Byte a click-drag selection started on, while the primary button is held
+    drag_origin: Option<usize>,
+    /// High nibble typed at the cursors, waiting for the low nibble
+    pending_nibble: Option<u8>,
+    /// Whether typing enters characters (cursor placed in the ASCII column) rather than hex digits
+    ascii_input: bool,
+    /// Offsets of the bytes changed since loading
+    dirty: HashSet<usize>,
+    /// Byte under the mouse pointer during the last frame
+    hovered_offset: Option<usize>,
+    /// Offset whose row is briefly highlighted, and when the highlight started
+    flash: Option<(usize, f64)>,
+    /// Byte the context menu was opened on
+    context_byte: Option<usize>,
+    /// Sorted start offsets of highlighted matches, and the length of each match
+    matches: Vec<usize>,
+    match_len: usize,
+    /// Sorted start offsets of search hits, and the length of each hit
+    search_matches: Vec<usize>,
+    search_len: usize,
+    /// Colors cycled through by field index
+    palette: Vec<Color32>,
+    /// Sorted ranges of bytes that differ from a compared file, and that file's size
+    diff: Vec<Range<usize>>,
+    diff_other_len: usize,
 }
 
 impl Default for HexView {
     fn default() -> Self {
-        Self { bytes_per_row: 16 }
+        Self {
+            scroll_target: None,
+            scroll_align: egui::Align::Center,
+            top_offset: 0,
+            visible_rows: 1,
+            cursors: Vec::new(),
+            selection_anchor: None,
+            drag_origin: None,
+            pending_nibble: None,
+            ascii_input: false,
+            dirty: HashSet::new(),
+            flash: None,
+            hovered_offset: None,
+            context_byte: None,
+            matches: Vec::new(),
+            match_len: 0,
+            search_matches: Vec::new(),
+            search_len: 0,
+            palette: Vec::new(),
+            diff: Vec::new(),
+            diff_other_len: 0,
+        }
     }
 }
 
@@ -18,27 +107,440 @@ impl HexView {
         Self::default()
     }
 
+    /// Scroll so that the row containing `offset` becomes visible on the next frame
+    pub fn scroll_to_offset(&mut self, offset: usize) {
+        self.scroll_target = Some(offset);
+        self.scroll_align = egui::Align::Center;
+    }
+
+    /// Scroll to `offset`, put the cursor there and briefly highlight its row
+    pub fn go_to_offset(&mut self, offset: usize, now: f64) {
+        self.place_cursor(offset, egui::Modifiers::NONE);
+        self.scroll_to_offset(offset);
+        self.flash = Some((offset, now));
+    }
+
+    /// Get the offset of the first visible row
+    /// Kept as a byte offset so it stays meaningful if the row width changes
+    pub fn top_offset(&self) -> usize {
+        self.top_offset
+    }
+
+    /// Scroll so that the row containing `offset` is the first visible one on the next frame
+    pub fn restore_top_offset(&mut self, offset: usize) {
+        self.scroll_target = Some(offset);
+        self.scroll_align = egui::Align::Min;
+    }
+
+    /// Get the offset of the byte under the mouse pointer during the last frame, if any
+    pub fn hovered_offset(&self) -> Option<usize> {
+        self.hovered_offset
+    }
+
+    /// Get the index of the field covering the byte under the mouse pointer, if any
+    pub fn hovered_field(&self, fields: &[Field]) -> Option<usize> {
+        let offset = self.hovered_offset?;
+        schema::field_at_offset(fields, offset).map(|(idx, _)| idx)
+    }
+
+    /// Get the primary cursor offset, if any
+    pub fn cursor_offset(&self) -> Option<usize> {
+        self.cursors.last().copied()
+    }
+
+    /// Highlight `len` bytes at each of the sorted `offsets`
+    pub fn set_matches(&mut self, offsets: Vec<usize>, len: usize) {
+        self.matches = offsets;
+        self.match_len = len;
+    }
+
+    /// Highlight the search hits: `len` bytes at each of the sorted `offsets`
+    pub fn set_search_matches(&mut self, offsets: Vec<usize>, len: usize) {
+        self.search_matches = offsets;
+        self.search_len = len;
+    }
+
+    /// Mark the bytes that differ from a compared file of `other_len` bytes
+    /// Bytes past the end of the other file are shaded as only present in this one
+    pub fn set_diff(&mut self, ranges: Vec<Range<usize>>, other_len: usize) {
+        self.diff = ranges;
+        self.diff_other_len = other_len;
+    }
+
+    /// Stop marking differences from a compared file
+    pub fn clear_diff(&mut self) {
+        self.diff.clear();
+    }
+
+    /// Mark the bytes changed since loading
+    pub fn set_dirty_offsets(&mut self, offsets: impl IntoIterator<Item = usize>) {
+        self.dirty = offsets.into_iter().collect();
+    }
+
+    /// Get the selected byte range (click-drag or Shift+click), if any
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.selection_anchor?;
+        let cursor = self.cursor_offset()?;
+        Some(anchor.min(cursor)..anchor.max(cursor) + 1)
+    }
+
+    /// Select the bytes in `range` and scroll to its start
+    /// An empty range just places the cursor at its start
+    pub fn select_range(&mut self, range: Range<usize>) {
+        self.pending_nibble = None;
+        self.selection_anchor = (!range.is_empty()).then_some(range.start);
+        self.cursors = vec![range.end.max(range.start + 1) - 1];
+        self.scroll_to_offset(range.start);
+    }
+
+    /// Place the cursor at `offset`
+    /// Ctrl toggles an additional cursor there instead, Shift extends the selection to it
+    fn place_cursor(&mut self, offset: usize, modifiers: egui::Modifiers) {
+        self.pending_nibble = None;
+        if modifiers.shift {
+            self.selection_anchor = self.selection_anchor.or(self.cursor_offset()).or(Some(offset));
+            self.cursors = vec![offset];
+        } else if modifiers.ctrl {
+            self.selection_anchor = None;
+            if let Some(pos) = self.cursors.iter().position(|&c| c == offset) {
+                self.cursors.remove(pos);
+            } else {
+                self.cursors.push(offset);
+            }
+        } else {
+            self.selection_anchor = None;
+            self.cursors = vec![offset];
+        }
+    }
+
+    /// Move the primary cursor with the arrow keys, Home/End and PageUp/PageDown
+    /// Shift extends the selection, and the view scrolls to keep the cursor visible
+    fn handle_navigation(&mut self, ui: &egui::Ui, data_len: usize, bytes_per_row: usize) {
+        if ui.ctx().wants_keyboard_input() {
+            return;
+        }
+
+        let cursor = self.cursor_offset().unwrap_or(self.top_offset).min(data_len - 1);
+        let row_start = cursor - cursor % bytes_per_row;
+        let page = self.visible_rows.max(1) * bytes_per_row;
+        let (target, modifiers) = ui.input(|i| {
+            let target = if i.key_pressed(egui::Key::ArrowLeft) {
+                Some(cursor.saturating_sub(1))
+            } else if i.key_pressed(egui::Key::ArrowRight) {
+                Some(cursor + 1)
+            } else if i.key_pressed(egui::Key::ArrowUp) {
+                Some(cursor.checked_sub(bytes_per_row).unwrap_or(cursor))
+            } else if i.key_pressed(egui::Key::ArrowDown) {
+                Some(if cursor + bytes_per_row < data_len { cursor + bytes_per_row } else { cursor })
+            } else if i.key_pressed(egui::Key::PageUp) {
+                Some(cursor.checked_sub(page).unwrap_or(cursor % bytes_per_row))
+            } else if i.key_pressed(egui::Key::PageDown) {
+                Some(cursor + page)
+            } else if i.key_pressed(egui::Key::Home) {
+                // Ctrl+Home jumps to the start of the file
+                Some(if i.modifiers.command { 0 } else { row_start })
+            } else if i.key_pressed(egui::Key::End) {
+                Some(if i.modifiers.command { data_len - 1 } else { row_start + bytes_per_row - 1 })
+            } else {
+                None
+            };
+            (target, i.modifiers)
+        });
+        let Some(target) = target else {
+            return;
+        };
+
+        let target = target.min(data_len - 1);
+        let extend = egui::Modifiers { shift: modifiers.shift, ..egui::Modifiers::NONE };
+        self.place_cursor(target, extend);
+
+        // Scroll just enough to bring the cursor's row into view
+        let target_row_start = target - target % bytes_per_row;
+        let visible_end = self.top_offset + page;
+        if target < self.top_offset {
+            self.restore_top_offset(target_row_start);
+        } else if target >= visible_end {
+            self.restore_top_offset(target_row_start.saturating_sub(page - bytes_per_row));
+        }
+    }
+
+    /// Handle typed hex digits, writing a byte at every cursor once two digits are entered
+    fn handle_typing(&mut self, ui: &egui::Ui, data_len: usize) -> Option<HexAction> {
+        // Don't steal keystrokes from text inputs elsewhere
+        if self.cursors.is_empty() || ui.ctx().wants_keyboard_input() {
+            return None;
+        }
+
+        let (text, escape) = ui.input(|i| {
+            let text: String = i
+                .events
+                .iter()
+                .filter_map(|e| match e {
+                    egui::Event::Text(t) => Some(t.as_str()),
+                    _ => None,
+                })
+                .collect();
+            (text, i.key_pressed(egui::Key::Escape))
+        });
+
+        if escape {
+            // Drop the pending digit and selection, and collapse to the primary cursor
+            self.pending_nibble = None;
+            self.selection_anchor = None;
+            self.cursors.drain(..self.cursors.len() - 1);
+            return None;
+        }
+
+        if self.ascii_input {
+            return self.handle_ascii_typing(&text, data_len);
+        }
+
+        let mut action = None;
+        for c in text.chars() {
+            let Some(nibble) = c.to_digit(16) else {
+                continue;
+            };
+            match self.pending_nibble.take() {
+                None => self.pending_nibble = Some(nibble as u8),
+                Some(high) => {
+                    action = Some(HexAction::WriteByte {
+                        offsets: self.cursors.clone(),
+                        value: (high << 4) | nibble as u8,
+                    });
+                    // Advance every cursor to the next byte
+                    for cursor in &mut self.cursors {
+                        *cursor = (*cursor + 1).min(data_len - 1);
+                    }
+                    self.cursors.dedup();
+                }
+            }
+        }
+
+        action
+    }
+
+    /// Write typed printable characters at every cursor, advancing after each one
+    fn handle_ascii_typing(&mut self, text: &str, data_len: usize) -> Option<HexAction> {
+        let mut writes = Vec::new();
+        for c in text.chars().filter(|c| c.is_ascii_graphic() || *c == ' ') {
+            for cursor in &mut self.cursors {
+                writes.push((*cursor, c as u8));
+                *cursor = (*cursor + 1).min(data_len - 1);
+            }
+            self.cursors.dedup();
+        }
+        (!writes.is_empty()).then_some(HexAction::WriteBytes(writes))
+    }
+
+    /// Copy the selected bytes (or the primary cursor's byte) as hex on Ctrl+C
+    fn handle_copy(&self, ui: &egui::Ui, data: &[u8], settings: &ViewSettings) {
+        if ui.ctx().wants_keyboard_input() || !ui.input(|i| i.events.iter().any(|e| matches!(e, egui::Event::Copy))) {
+            return;
+        }
+        let range = self
+            .selection()
+            .or_else(|| self.cursor_offset().map(|c| c..c + 1));
+        if let Some(bytes) = range.and_then(|range| data.get(range)) {
+            ui.ctx().copy_text(settings.hex_case(to_hex_string(bytes)));
+        }
+    }
+
+    /// Show the copy entries for the selected bytes
+    fn show_copy_menu(ui: &mut egui::Ui, bytes: &[u8], settings: &ViewSettings) {
+        let copied = if ui.button("Copy as Hex").clicked() {
+            Some(settings.hex_case(to_hex_string(bytes)))
+        } else if ui.button("Copy as C Array").clicked() {
+            Some(to_c_array(bytes, "data", settings.lowercase_hex))
+        } else if ui.button("Copy as Base64").clicked() {
+            Some(to_base64(bytes))
+        } else {
+            None
+        };
+        if let Some(text) = copied {
+            ui.ctx().copy_text(text);
+            ui.close_menu();
+        }
+    }
+
+    /// Show the context menu entries for the byte at `offset`
+    fn show_byte_context_menu(ui: &mut egui::Ui, fields: &[Field], data: &[u8], offset: usize) -> Option<HexAction> {
+        let mut action = None;
+        ui.menu_button("Interpret here as...", |ui| {
+            action = Self::show_interpretations(ui, data, offset);
+        });
+        if action.is_some() {
+            return action;
+        }
+
+        match schema::field_at_offset(fields, offset) {
+            Some((idx, field)) => {
+                ui.label(RichText::new(field.display_name()).strong());
+                if ui.button("Edit field").clicked() {
+                    return Some(HexAction::EditField(idx));
+                }
+                if ui.button("Delete field").clicked() {
+                    return Some(HexAction::DeleteField(idx));
+                }
+            }
+            None => {
+                if ui.button(format!("Define field at 0x{:X}", offset)).clicked() {
+                    return Some(HexAction::DefineField(offset));
+                }
+            }
+        }
+        None
+    }
+
+    /// Show the value of every data type at `offset` in both byte orders, without creating a field
+    /// Clicking a value asks to create a field of that type
+    fn show_interpretations(ui: &mut egui::Ui, data: &[u8], offset: usize) -> Option<HexAction> {
+        let mut action = None;
+        ui.label(RichText::new(format!("Values at 0x{:X}", offset)).strong());
+        ui.label(RichText::new("Click a value to create a field with its type").weak());
+        egui::Grid::new("interpret_here").striped(true).show(ui, |ui| {
+            ui.label("");
+            ui.label(RichText::new("Little endian").strong());
+            ui.label(RichText::new("Big endian").strong());
+            ui.end_row();
+
+            // Checksums need a region to be meaningful
+            for &data_type in DataType::all().iter().filter(|dt| !dt.is_checksum()) {
+                ui.label(data_type.name());
+                for endianness in [Endianness::Little, Endianness::Big] {
+                    // Single bytes and text read the same either way
+                    if endianness == Endianness::Big && (data_type.size() <= 1 || data_type.is_byte_string()) {
+                        ui.label("");
+                        continue;
+                    }
+                    let mut field = Field::new(String::new(), offset, data_type);
+                    field.endianness = endianness;
+                    let Some(value) = field.read_value(data) else {
+                        ui.weak("—").on_hover_text("Runs past the end of the file");
+                        continue;
+                    };
+                    let hover = format!("Create a {} field here ({})", data_type.name(), endianness.name());
+                    if ui.selectable_label(false, value).on_hover_text(hover).clicked() {
+                        action = Some(HexAction::DefineFieldAs {
+                            offset,
+                            data_type,
+                            endianness,
+                        });
+                    }
+                }
+                ui.end_row();
+            }
+        });
+        if action.is_some() {
+            ui.close_menu();
+        }
+        action
+    }
+
+    /// Describe the byte at `offset`: its position, value in several bases and the field covering it
+    fn show_byte_tooltip(
+        ui: &mut egui::Ui,
+        fields: &[Field],
+        settings: &ViewSettings,
+        data_len: usize,
+        offset: usize,
+        value: u8,
+    ) {
+        ui.label(format!("Offset {}", settings.format_offset_prefixed(offset, data_len)));
+        ui.monospace(format!("{}  0x{:02X}  0b{:08b}", value, value, value));
+        match schema::field_at_offset(fields, offset) {
+            Some((_, field)) => ui.label(format!("Field: {}", field.display_name())),
+            None => ui.label(RichText::new("No field").weak()),
+        };
+    }
+
+    /// Get the index of the byte under `pos` in a column of `len` cells of `cell_width`
+    fn byte_at(pos: Option<egui::Pos2>, rect: &egui::Rect, cell_width: f32, len: usize) -> Option<usize> {
+        let x = pos?.x - rect.left();
+        let byte_idx = (x / cell_width) as usize;
+        (x >= 0.0 && byte_idx < len).then_some(byte_idx)
+    }
+
+    /// Outline the byte at `byte_idx` (within the row) in both the hex and ASCII columns
+    fn draw_cursor(
+        painter: &egui::Painter,
+        hex_rect: &egui::Rect,
+        ascii_rect: &egui::Rect,
+        byte_idx: usize,
+        char_width: f32,
+        stroke: egui::Stroke,
+    ) {
+        let hex_x = hex_rect.left() + byte_idx as f32 * 3.0 * char_width;
+        let ascii_x = ascii_rect.left() + byte_idx as f32 * char_width;
+        painter.rect_stroke(
+            egui::Rect::from_x_y_ranges(hex_x - 1.0..=hex_x + 2.0 * char_width + 1.0, hex_rect.y_range()),
+            1.0,
+            stroke,
+        );
+        painter.rect_stroke(
+            egui::Rect::from_x_y_ranges(ascii_x..=ascii_x + char_width, ascii_rect.y_range()),
+            1.0,
+            stroke,
+        );
+    }
+
+    /// Shade bytes `start..end` (within the row) in both the hex and ASCII columns
+    fn draw_selection(
+        painter: &egui::Painter,
+        hex_rect: &egui::Rect,
+        ascii_rect: &egui::Rect,
+        start: usize,
+        end: usize,
+        char_width: f32,
+        color: Color32,
+    ) {
+        let hex_x = hex_rect.left() + start as f32 * 3.0 * char_width;
+        let hex_end = hex_rect.left() + (end as f32 * 3.0 - 1.0) * char_width;
+        painter.rect_filled(egui::Rect::from_x_y_ranges(hex_x..=hex_end, hex_rect.y_range()), 0.0, color);
+        let ascii_x = ascii_rect.left() + start as f32 * char_width;
+        let ascii_end = ascii_rect.left() + end as f32 * char_width;
+        painter.rect_filled(egui::Rect::from_x_y_ranges(ascii_x..=ascii_end, ascii_rect.y_range()), 0.0, color);
+    }
+
     /// Get the field that contains the given byte offset, if any
-    fn get_field_at_offset<'a>(fields: &'a [Field], offset: usize) -> Option<(usize, &'a Field)> {
-        fields
+    /// Set the colors used to tell fields apart
+    pub fn set_palette(&mut self, colors: &[[u8; 3]]) {
+        self.palette = colors.iter().map(|&[r, g, b]| Color32::from_rgb(r, g, b)).collect();
+    }
+
+    /// Resolve each field's highlight color: its own color if set, otherwise a palette color
+    /// that differs from the fields right before and after it in the file
+    fn resolve_field_colors(&self, fields: &[Field]) -> Vec<Color32> {
+        let mut colors: Vec<Option<Color32>> = fields
             .iter()
-            .enumerate()
-            .find(|(_, field)| offset >= field.offset && offset < field.offset + field.size())
+            .map(|field| field.color.map(|[r, g, b]| Color32::from_rgb(r, g, b)))
+            .collect();
+
+        if !self.palette.is_empty() {
+            let mut order: Vec<usize> = (0..fields.len()).collect();
+            order.sort_by_key(|&idx| fields[idx].offset);
+
+            for (pos, &idx) in order.iter().enumerate() {
+                if colors[idx].is_some() {
+                    continue;
+                }
+                // Fields later in the file only have a color yet if it was set explicitly
+                let prev = pos.checked_sub(1).and_then(|p| colors[order[p]]);
+                let next = order.get(pos + 1).and_then(|&n| colors[n]);
+                let start = idx % self.palette.len();
+                let color = (0..self.palette.len())
+                    .map(|step| self.palette[(start + step) % self.palette.len()])
+                    .find(|&color| Some(color) != prev && Some(color) != next)
+                    .unwrap_or(self.palette[start]);
+                colors[idx] = Some(color);
+            }
+        }
+
+        colors.into_iter().map(|color| color.unwrap_or(Color32::GRAY)).collect()
     }
 
-    /// Generate a distinct color for each field
-    fn get_field_color(field_idx: usize) -> Color32 {
-        let colors = [
-            Color32::from_rgb(100, 150, 255), // Blue
-            Color32::from_rgb(255, 150, 100), // Orange
-            Color32::from_rgb(150, 255, 100), // Green
-            Color32::from_rgb(255, 100, 200), // Pink
-            Color32::from_rgb(200, 100, 255), // Purple
-            Color32::from_rgb(100, 255, 200), // Cyan
-            Color32::from_rgb(255, 255, 100), // Yellow
-            Color32::from_rgb(255, 150, 150), // Light red
-        ];
-        colors[field_idx % colors.len()]
+    /// Look up the resolved color of a field
+    fn get_field_color(colors: &[Color32], field_idx: usize) -> Color32 {
+        colors.get(field_idx).copied().unwrap_or(Color32::GRAY)
     }
 
     /// Draw fancy rounded border highlight for a field's bytes
@@ -51,11 +553,13 @@ impl HexView {
         end_byte: usize,
         field_idx: usize,
         selected_fields: &HashSet<usize>,
+        hovered_field: Option<usize>,
+        colors: &[Color32],
         char_width: f32,
-        line_height: f32,
     ) {
         let is_selected = selected_fields.contains(&field_idx);
-        let color = Self::get_field_color(field_idx);
+        let is_hovered = hovered_field == Some(field_idx);
+        let color = Self::get_field_color(colors, field_idx);
 
         // Calculate rects for hex column
         // Each byte is "XX" (2 chars) + space (1 char) except the last one
@@ -82,8 +586,9 @@ impl HexView {
 
         // Draw rounded rectangles
         let rounding = 3.0;
-        let stroke_width = if is_selected { 2.0 } else { 1.0 };
-        let fill_alpha = if is_selected { 40 } else { 20 };
+        // The field hovered here or in the data view stands out a little more
+        let stroke_width = if is_selected { 2.0 } else { 1.0 } + if is_hovered { 1.0 } else { 0.0 };
+        let fill_alpha = if is_selected { 40 } else { 20 } + if is_hovered { 30 } else { 0 };
 
         // Hex column highlight
         painter.rect(
@@ -102,116 +607,330 @@ impl HexView {
         );
     }
 
+    /// Underline the exact bits of a bit field within one row's hex column
+    /// `start_bit..end_bit` are relative to the start of the row
+    #[allow(clippy::too_many_arguments)]
+    fn draw_bit_underline(
+        painter: &egui::Painter,
+        hex_rect: &egui::Rect,
+        start_bit: usize,
+        end_bit: usize,
+        field_idx: usize,
+        colors: &[Color32],
+        char_width: f32,
+    ) {
+        // Each byte is "XX " (3 chars) and each hex digit covers 4 bits
+        let bit_x = |bit: usize| hex_rect.left() + ((bit / 8) * 3) as f32 * char_width + (bit % 8) as f32 * char_width / 4.0;
+        let y = hex_rect.bottom() - 1.0;
+        painter.line_segment(
+            [egui::pos2(bit_x(start_bit), y), egui::pos2(bit_x(end_bit - 1) + char_width / 4.0, y)],
+            egui::Stroke::new(3.0, Self::get_field_color(colors, field_idx)),
+        );
+    }
+
+    /// Draw the gutter with a tick for each field, proportional to its position in the file
+    /// Clicking a tick jumps to that field; clicking elsewhere jumps to the matching offset
+    fn show_field_gutter(
+        &mut self,
+        ui: &mut egui::Ui,
+        rect: egui::Rect,
+        data_len: usize,
+        fields: &[Field],
+        colors: &[Color32],
+    ) {
+        let response = ui.interact(rect, ui.id().with("field_gutter"), egui::Sense::click());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+
+        let offset_to_y = |offset: usize| rect.top() + (offset as f32 / data_len as f32) * rect.height();
+
+        // Tick rects, kept for hit-testing
+        let ticks: Vec<(usize, egui::Rect)> = fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| field.offset < data_len)
+            .map(|(idx, field)| {
+                let top = offset_to_y(field.offset);
+                let bottom = offset_to_y(field.offset + field.size()).max(top + 2.0);
+                (idx, egui::Rect::from_x_y_ranges(rect.x_range(), top..=bottom))
+            })
+            .collect();
+
+        for &(idx, tick_rect) in &ticks {
+            painter.rect_filled(tick_rect, 0.0, Self::get_field_color(colors, idx));
+        }
+
+        let Some(pointer) = response.hover_pos() else {
+            return;
+        };
+
+        // Allow a little slack so thin ticks are easy to hit
+        let hovered = ticks
+            .iter()
+            .find(|(_, tick_rect)| tick_rect.expand2(egui::vec2(0.0, 2.0)).contains(pointer))
+            .map(|&(idx, _)| idx);
+
+        let response = match hovered {
+            Some(idx) => response.on_hover_text(format!("{} @ 0x{:X}", fields[idx].display_name(), fields[idx].offset)),
+            None => response,
+        };
+
+        if response.clicked() {
+            let target = match hovered {
+                Some(idx) => fields[idx].offset,
+                None => {
+                    let fraction = ((pointer.y - rect.top()) / rect.height()).clamp(0.0, 1.0);
+                    ((fraction * data_len as f32) as usize).min(data_len - 1)
+                }
+            };
+            self.scroll_to_offset(target);
+        }
+    }
+
     /// Render the hex view for the given binary data
+    /// Returns an optional action (e.g. a byte edit) for the app to apply
+    #[allow(clippy::too_many_arguments)]
     pub fn show(
         &mut self,
         ui: &mut egui::Ui,
         data: &[u8],
         fields: &[Field],
         selected_fields: &HashSet<usize>,
-    ) {
+        hovered_field: Option<usize>,
+        settings: &ViewSettings,
+        relative_base: Option<usize>,
+        has_focus: bool,
+    ) -> Option<HexAction> {
         if data.is_empty() {
             ui.label("No file loaded");
-            return;
+            return None;
         }
 
-        ScrollArea::vertical()
-            .id_salt("hex_view_scroll")
-            .auto_shrink([false, false])
-            .show(ui, |ui| {
-                ui.horizontal(|ui| {
-                    // Use monospace font for better alignment
-                    ui.style_mut().override_text_style = Some(TextStyle::Monospace);
+        // Drop cursors and selection left past the end by a smaller file
+        self.cursors.retain(|&c| c < data.len());
+        self.selection_anchor = self.selection_anchor.filter(|&a| a < data.len());
+        let selection = self.selection();
+        let bytes_per_row = settings.bytes_per_row.max(1);
 
-                    ui.vertical(|ui| {
-                        // Calculate character width for monospace font
-                        let char_width = ui.fonts(|f| f.glyph_width(&egui::TextStyle::Monospace.resolve(ui.style()), '0'));
+        let mut action = if has_focus { self.handle_typing(ui, data.len()) } else { None };
+        if has_focus {
+            self.handle_navigation(ui, data.len(), bytes_per_row);
+            self.handle_copy(ui, data, settings);
+        }
+        // Byte clicked this frame, the modifiers held and whether it was in the ASCII column
+        let mut clicked_byte: Option<(usize, egui::Modifiers, bool)> = None;
+        let mut hovered_offset = None;
+        // Byte under the pointer while drag-selecting, which may be on another row than the drag started
+        let mut dragged_byte = None;
+        let pointer_pos = ui.input(|i| i.pointer.interact_pos());
+        if !ui.input(|i| i.pointer.primary_down()) {
+            self.drag_origin = None;
+        }
 
-                        // Render each row
-                        for (row_idx, chunk) in data.chunks(self.bytes_per_row).enumerate() {
-                            let row_response = ui.horizontal(|ui| {
-                                let offset = row_idx * self.bytes_per_row;
+        // Field gutter on the right, scroll area in the remaining space
+        let available = ui.available_rect_before_wrap();
+        let gutter_rect = egui::Rect::from_min_max(
+            egui::pos2(available.right() - GUTTER_WIDTH, available.top()),
+            available.max,
+        );
+        let content_rect = egui::Rect::from_min_max(
+            available.min,
+            egui::pos2(gutter_rect.left() - 4.0, available.bottom()),
+        );
+        let colors = self.resolve_field_colors(fields);
+        self.show_field_gutter(ui, gutter_rect, data.len(), fields, &colors);
 
-                                // Offset column - selectable label
-                                ui.label(
-                                    RichText::new(format!("{:08X}", offset))
-                                        .color(Color32::from_rgb(100, 100, 100))
-                                );
+        ui.allocate_new_ui(egui::UiBuilder::new().max_rect(content_rect), |ui| {
+            // Only the visible rows are laid out, so huge files stay responsive
+            let row_height = ui.text_style_height(&TextStyle::Monospace);
+            let row_pitch = row_height + ui.spacing().item_spacing.y;
+            let total_rows = data.len().div_ceil(bytes_per_row);
+            let mut scroll_area = ScrollArea::vertical()
+                .id_salt("hex_view_scroll")
+                .auto_shrink([false, false]);
+            // Bring the requested row into view; targets past the end land on the last row
+            if let Some(target) = self.scroll_target.take() {
+                let row = target.min(data.len() - 1) / bytes_per_row;
+                let mut scroll_y = row as f32 * row_pitch;
+                if self.scroll_align == egui::Align::Center {
+                    scroll_y -= (ui.available_height() - row_height) / 2.0;
+                }
+                scroll_area = scroll_area.vertical_scroll_offset(scroll_y.max(0.0));
+            }
+
+            scroll_area.show_rows(ui, row_height, total_rows, |ui, rows| {
+                // Use monospace font for better alignment
+                ui.style_mut().override_text_style = Some(TextStyle::Monospace);
+
+                // Keep scrolling while a drag selection is held above or below the rows
+                if self.drag_origin.is_some()
+                    && let Some(pos) = pointer_pos
+                {
+                    let clip = ui.clip_rect();
+                    if pos.y < clip.top() {
+                        ui.scroll_with_delta(egui::vec2(0.0, row_pitch));
+                    } else if pos.y > clip.bottom() {
+                        ui.scroll_with_delta(egui::vec2(0.0, -row_pitch));
+                    }
+                }
 
-                                ui.label("│");
+                // Calculate character width for monospace font
+                let char_width = ui.fonts(|f| f.glyph_width(&egui::TextStyle::Monospace.resolve(ui.style()), '0'));
 
-                                // Hex bytes column - selectable label
-                                let hex_string: String = chunk
-                                    .iter()
-                                    .map(|b| format!("{:02X}", b))
-                                    .collect::<Vec<_>>()
-                                    .join(" ");
-                                let padding = if chunk.len() < self.bytes_per_row {
-                                    "   ".repeat(self.bytes_per_row - chunk.len())
+                // Render each visible row
+                self.top_offset = rows.start * bytes_per_row;
+                // The last row is usually cut off, so it doesn't count as visible
+                self.visible_rows = rows.len().saturating_sub(1).max(1);
+                for row_idx in rows {
+                    let row_start = row_idx * bytes_per_row;
+                    let chunk = &data[row_start..(row_start + bytes_per_row).min(data.len())];
+                    ui.horizontal(|ui| {
+                        let offset = row_idx * bytes_per_row;
+
+                        // Offset column - selectable label
+                        ui.label(
+                            RichText::new(settings.format_row_offset(offset, relative_base, data.len()))
+                                .color(ui.visuals().weak_text_color())
+                        );
+
+                        ui.label("│");
+
+                        // Hex bytes column - selectable label
+                        let hex_string: String = chunk
+                            .iter()
+                            .map(|&b| settings.format_byte(b))
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        let padding = if chunk.len() < bytes_per_row {
+                            "   ".repeat(bytes_per_row - chunk.len())
+                        } else {
+                            String::new()
+                        };
+
+                        let hex_response = ui.add(
+                            egui::Label::new(RichText::new(format!("{}{}", hex_string, padding)))
+                                .sense(egui::Sense::click_and_drag()),
+                        );
+
+                        ui.label("│");
+
+                        // ASCII column - selectable label
+                        let ascii_string: String = chunk
+                            .iter()
+                            .map(|&b| {
+                                if b.is_ascii_graphic() || b == b' ' {
+                                    b as char
                                 } else {
-                                    String::new()
-                                };
+                                    '.'
+                                }
+                            })
+                            .collect();
 
-                                let hex_response = ui.label(RichText::new(format!("{}{}", hex_string, padding)));
+                        let ascii_response = ui.add(
+                            egui::Label::new(
+                                RichText::new(ascii_string).color(ui.visuals().text_color().gamma_multiply(0.75)),
+                            )
+                            .sense(egui::Sense::click_and_drag()),
+                        );
 
-                                ui.label("│");
+                        // Map clicks to the byte under the pointer
+                        let hex_cell = 3.0 * char_width;
+                        let modifiers = ui.input(|i| i.modifiers);
+                        if hex_response.clicked()
+                            && let Some(byte_idx) = Self::byte_at(hex_response.interact_pointer_pos(), &hex_response.rect, hex_cell, chunk.len())
+                        {
+                            clicked_byte = Some((offset + byte_idx, modifiers, false));
+                        }
+                        if ascii_response.clicked()
+                            && let Some(byte_idx) = Self::byte_at(ascii_response.interact_pointer_pos(), &ascii_response.rect, char_width, chunk.len())
+                        {
+                            clicked_byte = Some((offset + byte_idx, modifiers, true));
+                        }
 
-                                // ASCII column - selectable label
-                                let ascii_string: String = chunk
-                                    .iter()
-                                    .map(|&b| {
-                                        if b.is_ascii_graphic() || b == b' ' {
-                                            b as char
-                                        } else {
-                                            '.'
-                                        }
-                                    })
-                                    .collect();
+                        // Dragging selects from the byte the press started on to the byte under the pointer
+                        let press_origin = ui.input(|i| i.pointer.press_origin());
+                        if hex_response.drag_started()
+                            && let Some(byte_idx) = Self::byte_at(press_origin, &hex_response.rect, hex_cell, chunk.len())
+                        {
+                            self.drag_origin = Some(offset + byte_idx);
+                            self.ascii_input = false;
+                        }
+                        if ascii_response.drag_started()
+                            && let Some(byte_idx) = Self::byte_at(press_origin, &ascii_response.rect, char_width, chunk.len())
+                        {
+                            self.drag_origin = Some(offset + byte_idx);
+                            self.ascii_input = true;
+                        }
+                        if self.drag_origin.is_some()
+                            && let Some(pos) = pointer_pos
+                            && hex_response.rect.y_range().contains(pos.y)
+                        {
+                            let (rect, cell) = if self.ascii_input {
+                                (&ascii_response.rect, char_width)
+                            } else {
+                                (&hex_response.rect, hex_cell)
+                            };
+                            let x = (pos.x - rect.left()).max(0.0);
+                            dragged_byte = Some(offset + ((x / cell) as usize).min(chunk.len() - 1));
+                        }
 
-                                let ascii_response = ui.label(
-                                    RichText::new(ascii_string)
-                                        .color(Color32::from_rgb(150, 150, 150))
-                                );
+                        let hovered_byte = Self::byte_at(hex_response.hover_pos(), &hex_response.rect, hex_cell, chunk.len())
+                            .or_else(|| Self::byte_at(ascii_response.hover_pos(), &ascii_response.rect, char_width, chunk.len()));
+                        if let Some(byte_idx) = hovered_byte {
+                            hovered_offset = Some(offset + byte_idx);
+                        }
 
-                                // Get painter after all UI rendering
-                                let painter = ui.painter().clone();
-
-                                // Draw field highlights using painter
-                                let line_height = hex_response.rect.height();
-
-                                // Group consecutive bytes by field for rounded borders
-                                let mut current_field: Option<(usize, usize, usize)> = None; // (field_idx, start_byte, end_byte)
-
-                                for (byte_idx, _) in chunk.iter().enumerate() {
-                                    let byte_offset = offset + byte_idx;
-
-                                    if let Some((field_idx, _field)) = Self::get_field_at_offset(fields, byte_offset) {
-                                        match current_field {
-                                            Some((curr_field_idx, start, _)) if curr_field_idx == field_idx => {
-                                                // Same field, extend the range
-                                                current_field = Some((field_idx, start, byte_idx));
-                                            }
-                                            _ => {
-                                                // Draw previous field if any
-                                                if let Some((prev_field_idx, start, end)) = current_field {
-                                                    Self::draw_field_highlight(
-                                                        &painter,
-                                                        &hex_response.rect,
-                                                        &ascii_response.rect,
-                                                        start,
-                                                        end,
-                                                        prev_field_idx,
-                                                        selected_fields,
-                                                        char_width,
-                                                        line_height,
-                                                    );
-                                                }
-                                                // Start new field
-                                                current_field = Some((field_idx, byte_idx, byte_idx));
-                                            }
-                                        }
-                                    } else {
-                                        // No field, draw previous if any
+                        // Context menu for the right-clicked byte, depending on whether a field covers it
+                        if (hex_response.secondary_clicked() || ascii_response.secondary_clicked())
+                            && let Some(byte_idx) = hovered_byte
+                        {
+                            self.context_byte = Some(offset + byte_idx);
+                        }
+                        for response in [&hex_response, &ascii_response] {
+                            response.context_menu(|ui| {
+                                let Some(byte_offset) = self.context_byte else {
+                                    ui.close_menu();
+                                    return;
+                                };
+                                // Right-clicking inside the selection offers to copy it
+                                if let Some((range, bytes)) = selection
+                                    .as_ref()
+                                    .filter(|s| s.contains(&byte_offset))
+                                    .and_then(|s| Some((s.clone(), data.get(s.clone())?)))
+                                {
+                                    ui.label(RichText::new(format!("{} selected bytes", bytes.len())).strong());
+                                    Self::show_copy_menu(ui, bytes, settings);
+                                    if ui.button("Create field here").clicked() {
+                                        action = Some(HexAction::DefineFieldFromSelection(range));
+                                        ui.close_menu();
+                                    }
+                                    ui.separator();
+                                }
+                                if let Some(menu_action) = Self::show_byte_context_menu(ui, fields, data, byte_offset) {
+                                    action = Some(menu_action);
+                                    ui.close_menu();
+                                }
+                            });
+                        }
+
+                        // Get painter after all UI rendering
+                        let painter = ui.painter().clone();
+
+                        // Draw field highlights using painter
+                        // Group consecutive bytes by field for rounded borders
+                        let mut current_field: Option<(usize, usize, usize)> = None; // (field_idx, start_byte, end_byte)
+
+                        for (byte_idx, _) in chunk.iter().enumerate() {
+                            let byte_offset = offset + byte_idx;
+
+                            if let Some((field_idx, _field)) = schema::field_at_offset(fields, byte_offset) {
+                                match current_field {
+                                    Some((curr_field_idx, start, _)) if curr_field_idx == field_idx => {
+                                        // Same field, extend the range
+                                        current_field = Some((field_idx, start, byte_idx));
+                                    }
+                                    _ => {
+                                        // Draw previous field if any
                                         if let Some((prev_field_idx, start, end)) = current_field {
                                             Self::draw_field_highlight(
                                                 &painter,
@@ -221,15 +940,17 @@ impl HexView {
                                                 end,
                                                 prev_field_idx,
                                                 selected_fields,
+                                                hovered_field,
+                                                &colors,
                                                 char_width,
-                                                line_height,
                                             );
                                         }
-                                        current_field = None;
+                                        // Start new field
+                                        current_field = Some((field_idx, byte_idx, byte_idx));
                                     }
                                 }
-
-                                // Draw last field if any
+                            } else {
+                                // No field, draw previous if any
                                 if let Some((prev_field_idx, start, end)) = current_field {
                                     Self::draw_field_highlight(
                                         &painter,
@@ -239,14 +960,219 @@ impl HexView {
                                         end,
                                         prev_field_idx,
                                         selected_fields,
+                                        hovered_field,
+                                        &colors,
                                         char_width,
-                                        line_height,
                                     );
                                 }
-                            });
+                                current_field = None;
+                            }
+                        }
+
+                        // Draw last field if any
+                        if let Some((prev_field_idx, start, end)) = current_field {
+                            Self::draw_field_highlight(
+                                &painter,
+                                &hex_response.rect,
+                                &ascii_response.rect,
+                                start,
+                                end,
+                                prev_field_idx,
+                                selected_fields,
+                                hovered_field,
+                                &colors,
+                                char_width,
+                            );
+                        }
+
+                        // Shade matches of the selected field's bytes overlapping this row
+                        let row_range = offset..offset + chunk.len();
+                        let first_match = self.matches.partition_point(|&m| m + self.match_len <= row_range.start);
+                        for &m in self.matches[first_match..].iter().take_while(|&&m| m < row_range.end) {
+                            let start = m.max(row_range.start);
+                            let end = (m + self.match_len).min(row_range.end);
+                            Self::draw_selection(
+                                &painter,
+                                &hex_response.rect,
+                                &ascii_response.rect,
+                                start - offset,
+                                end - offset,
+                                char_width,
+                                Color32::from_rgba_unmultiplied(255, 170, 0, 50),
+                            );
+                        }
+
+                        // Fade out the highlight of a jumped-to row
+                        if let Some((flash_offset, start)) = self.flash
+                            && row_range.contains(&flash_offset)
+                        {
+                            let elapsed = ui.input(|i| i.time) - start;
+                            if elapsed < FLASH_DURATION {
+                                let alpha = ((1.0 - elapsed / FLASH_DURATION) * 80.0) as u8;
+                                painter.rect_filled(
+                                    hex_response.rect.union(ascii_response.rect),
+                                    2.0,
+                                    Color32::from_rgba_unmultiplied(255, 220, 80, alpha),
+                                );
+                                ui.ctx().request_repaint();
+                            }
+                        }
+
+                        // Shade search hits overlapping this row
+                        let first_hit = self.search_matches.partition_point(|&m| m + self.search_len <= row_range.start);
+                        for &m in self.search_matches[first_hit..].iter().take_while(|&&m| m < row_range.end) {
+                            let start = m.max(row_range.start);
+                            let end = (m + self.search_len).min(row_range.end);
+                            Self::draw_selection(
+                                &painter,
+                                &hex_response.rect,
+                                &ascii_response.rect,
+                                start - offset,
+                                end - offset,
+                                char_width,
+                                Color32::from_rgba_unmultiplied(80, 200, 255, 50),
+                            );
+                        }
+
+                        // Shade the bytes that differ from the compared file, or that it lacks
+                        let first_diff = self.diff.partition_point(|d| d.end <= row_range.start);
+                        for diff in self.diff[first_diff..].iter().take_while(|d| d.start < row_range.end) {
+                            let start = diff.start.max(row_range.start);
+                            let end = diff.end.min(row_range.end);
+                            let split = self.diff_other_len.clamp(start, end);
+                            for (range, color) in [
+                                (start..split, Color32::from_rgba_unmultiplied(200, 80, 255, 60)),
+                                (split..end, Color32::from_rgba_unmultiplied(80, 200, 80, 60)),
+                            ] {
+                                if !range.is_empty() {
+                                    Self::draw_selection(
+                                        &painter,
+                                        &hex_response.rect,
+                                        &ascii_response.rect,
+                                        range.start - offset,
+                                        range.end - offset,
+                                        char_width,
+                                        color,
+                                    );
+                                }
+                            }
+                        }
+
+                        // Tint the bytes changed since loading
+                        if !self.dirty.is_empty() {
+                            for byte_offset in row_range.clone().filter(|o| self.dirty.contains(o)) {
+                                Self::draw_selection(
+                                    &painter,
+                                    &hex_response.rect,
+                                    &ascii_response.rect,
+                                    byte_offset - offset,
+                                    byte_offset - offset + 1,
+                                    char_width,
+                                    Color32::from_rgba_unmultiplied(230, 60, 60, 60),
+                                );
+                            }
+                        }
+
+                        // Shade the selected bytes in this row
+                        if let Some(selection) = &selection {
+                            let start = selection.start.max(row_range.start);
+                            let end = selection.end.min(row_range.end);
+                            if start < end {
+                                Self::draw_selection(
+                                    &painter,
+                                    &hex_response.rect,
+                                    &ascii_response.rect,
+                                    start - offset,
+                                    end - offset,
+                                    char_width,
+                                    ui.visuals().selection.bg_fill.gamma_multiply(0.4),
+                                );
+                            }
+                        }
+
+                        // Outline the cursors in this row; the primary one is drawn stronger
+                        let cursor_color = ui.visuals().strong_text_color();
+                        for (i, &cursor) in self.cursors.iter().enumerate() {
+                            if row_range.contains(&cursor) {
+                                let width = if i + 1 == self.cursors.len() { 2.0 } else { 1.0 };
+                                Self::draw_cursor(
+                                    &painter,
+                                    &hex_response.rect,
+                                    &ascii_response.rect,
+                                    cursor - offset,
+                                    char_width,
+                                    egui::Stroke::new(width, cursor_color),
+                                );
+                            }
+                        }
+
+                        // Box the hovered byte in both columns so hex and ASCII can be correlated
+                        if let Some(byte_idx) = hovered_byte {
+                            Self::draw_cursor(
+                                &painter,
+                                &hex_response.rect,
+                                &ascii_response.rect,
+                                byte_idx,
+                                char_width,
+                                ui.visuals().widgets.hovered.fg_stroke,
+                            );
+
+                            // Only the byte under the pointer gets its own response, for the tooltip
+                            if self.drag_origin.is_none() {
+                                let (rect, cell, width) = if hex_response.hovered() {
+                                    (&hex_response.rect, hex_cell, 2.0 * char_width)
+                                } else {
+                                    (&ascii_response.rect, char_width, char_width)
+                                };
+                                let left = rect.left() + byte_idx as f32 * cell;
+                                let cell_rect = egui::Rect::from_x_y_ranges(left..=left + width, rect.y_range());
+                                let byte_offset = offset + byte_idx;
+                                ui.interact(cell_rect, ui.id().with(("hex_byte", byte_offset)), egui::Sense::hover())
+                                    .on_hover_ui(|ui| {
+                                        Self::show_byte_tooltip(ui, fields, settings, data.len(), byte_offset, chunk[byte_idx]);
+                                    });
+                            }
+                        }
+
+                        // Underline the exact bits of bit fields, which may cover bytes only partially
+                        let row_bits = offset * 8..(offset + chunk.len()) * 8;
+                        for (field_idx, field) in fields.iter().enumerate() {
+                            if let Some(bits) = field.display_bit_range() {
+                                let start = bits.start.max(row_bits.start);
+                                let end = bits.end.min(row_bits.end);
+                                if start < end {
+                                    Self::draw_bit_underline(
+                                        &painter,
+                                        &hex_response.rect,
+                                        start - row_bits.start,
+                                        end - row_bits.start,
+                                        field_idx,
+                                        &colors,
+                                        char_width,
+                                    );
+                                }
+                            }
                         }
                     });
-                });
+                }
             });
+        });
+        ui.allocate_rect(available, egui::Sense::hover());
+
+        self.hovered_offset = hovered_offset;
+
+        if let Some(origin) = self.drag_origin
+            && let Some(offset) = dragged_byte
+        {
+            self.selection_anchor = Some(origin);
+            self.cursors = vec![offset];
+            self.pending_nibble = None;
+        }
+        if let Some((offset, modifiers, ascii)) = clicked_byte {
+            self.place_cursor(offset, modifiers);
+            self.ascii_input = ascii;
+        }
+
+        action
     }
 }
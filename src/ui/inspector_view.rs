@@ -0,0 +1,78 @@
+use super::ViewSettings;
+use crate::schema::{DataType, Endianness, Field};
+use egui::{Color32, RichText};
+use egui_extras::{Column, TableBuilder};
+
+/// Panel decoding the bytes at one offset as every data type, in both byte orders
+pub struct InspectorView;
+
+impl InspectorView {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Render the decoded values of the bytes at `offset`
+    pub fn show(&mut self, ui: &mut egui::Ui, data: &[u8], offset: Option<usize>, settings: &ViewSettings) {
+        let Some(offset) = offset.filter(|&offset| offset < data.len()) else {
+            ui.label(RichText::new("Hover or click a byte to inspect it").color(Color32::from_rgb(120, 120, 120)));
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.strong("Inspector");
+            ui.label(
+                RichText::new(format!("@ {}", settings.format_offset_prefixed(offset, data.len())))
+                    .color(Color32::from_rgb(120, 120, 120)),
+            );
+        });
+
+        let read = |data_type: DataType, endianness: Endianness| {
+            data_type
+                .read_value(data, offset, endianness)
+                .unwrap_or_else(|| "—".to_string())
+        };
+
+        TableBuilder::new(ui)
+            .id_salt("inspector_table")
+            .striped(true)
+            .column(Column::auto().at_least(90.0))
+            .column(Column::remainder().at_least(120.0).clip(true))
+            .column(Column::remainder().at_least(120.0).clip(true))
+            .header(18.0, |mut header| {
+                header.col(|ui| {
+                    ui.strong("Type");
+                });
+                header.col(|ui| {
+                    ui.strong("Little endian");
+                });
+                header.col(|ui| {
+                    ui.strong("Big endian");
+                });
+            })
+            .body(|mut body| {
+                for &data_type in DataType::all() {
+                    body.row(18.0, |mut row| {
+                        row.col(|ui| {
+                            let type_name = Field::new(String::new(), offset, data_type).type_name();
+                            ui.label(RichText::new(type_name).color(Color32::from_rgb(80, 150, 200)));
+                        });
+                        row.col(|ui| {
+                            ui.monospace(read(data_type, Endianness::Little));
+                        });
+                        row.col(|ui| {
+                            // Byte order doesn't matter for single bytes
+                            if data_type.size() > 1 {
+                                ui.monospace(read(data_type, Endianness::Big));
+                            }
+                        });
+                    });
+                }
+            });
+    }
+}
+
+impl Default for InspectorView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
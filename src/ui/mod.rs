@@ -1,5 +1,27 @@
+pub mod analysis_view;
+pub mod compare_view;
+pub mod field_bytes_view;
 pub mod hex_view;
+pub mod inspector_view;
+pub mod notifications;
 pub mod data_view;
+pub mod outline_view;
+pub mod palettes;
+pub mod search_bar;
+pub mod strings_view;
+pub mod view_settings;
+pub mod watch_view;
 
-pub use hex_view::HexView;
+pub use analysis_view::AnalysisView;
+pub use compare_view::{CompareAction, CompareView};
+pub use field_bytes_view::FieldBytesView;
+pub use hex_view::{HexAction, HexView};
+pub use inspector_view::InspectorView;
+pub use notifications::Notifications;
 pub use data_view::{DataView, FieldAction};
+pub use outline_view::OutlineView;
+pub use palettes::Palettes;
+pub use search_bar::{SearchAction, SearchBar};
+pub use strings_view::{StringsAction, StringsView};
+pub use view_settings::{BYTES_PER_ROW_CHOICES, OffsetFormat, ViewSettings};
+pub use watch_view::WatchView;
@@ -0,0 +1,178 @@
+use egui::{Color32, RichText, ScrollArea};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// How long an info toast stays up before dismissing itself
+const INFO_TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// Number of messages kept in the log; older ones are dropped
+const MAX_LOG_ENTRIES: usize = 500;
+
+/// How serious a notification is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// Get the display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Info => "Info",
+            Severity::Warning => "Warning",
+            Severity::Error => "Error",
+        }
+    }
+
+    fn color(&self, visuals: &egui::Visuals) -> Color32 {
+        match self {
+            Severity::Info => visuals.text_color(),
+            Severity::Warning => visuals.warn_fg_color,
+            Severity::Error => visuals.error_fg_color,
+        }
+    }
+}
+
+/// A message shown to the user as a toast and kept in the log
+struct Notification {
+    severity: Severity,
+    message: String,
+    /// Time of day it was posted, as `HH:MM:SS` UTC
+    timestamp: String,
+    posted: Instant,
+    /// Whether its toast was closed; info toasts also go away on their own
+    dismissed: bool,
+}
+
+impl Notification {
+    fn shows_toast(&self) -> bool {
+        !self.dismissed && (self.severity != Severity::Info || self.posted.elapsed() < INFO_TOAST_DURATION)
+    }
+}
+
+/// Format the current time of day as `HH:MM:SS` UTC
+fn time_of_day() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()) % 86_400;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Toasts for messages such as load and save failures, with a log of the past ones
+/// Info toasts dismiss themselves; warnings and errors stay until acknowledged
+pub struct Notifications {
+    entries: Vec<Notification>,
+}
+
+impl Notifications {
+    pub fn new() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    /// Post a message
+    pub fn push(&mut self, severity: Severity, message: impl Into<String>) {
+        if self.entries.len() >= MAX_LOG_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.entries.push(Notification {
+            severity,
+            message: message.into(),
+            timestamp: time_of_day(),
+            posted: Instant::now(),
+            dismissed: false,
+        });
+    }
+
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.push(Severity::Info, message);
+    }
+
+    pub fn warning(&mut self, message: impl Into<String>) {
+        self.push(Severity::Warning, message);
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.push(Severity::Error, message);
+    }
+
+    /// Get the number of errors whose toast is still up
+    pub fn unacknowledged_errors(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|n| n.severity == Severity::Error && !n.dismissed)
+            .count()
+    }
+
+    /// Draw the active toasts stacked in the bottom-right corner
+    pub fn show_toasts(&mut self, ctx: &egui::Context) {
+        if !self.entries.iter().any(Notification::shows_toast) {
+            return;
+        }
+
+        egui::Area::new(egui::Id::new("notification_toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -36.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.set_max_width(360.0);
+                for notification in self.entries.iter_mut().filter(|n| n.shows_toast()) {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            let color = notification.severity.color(ui.visuals());
+                            ui.label(RichText::new(notification.severity.name()).strong().color(color));
+                            if ui.small_button("✖").on_hover_text("Dismiss").clicked() {
+                                notification.dismissed = true;
+                            }
+                        });
+                        ui.label(&notification.message);
+                    });
+                }
+            });
+
+        // Wake up to take down the next info toast
+        let next_expiry = self
+            .entries
+            .iter()
+            .filter(|n| n.severity == Severity::Info && n.shows_toast())
+            .map(|n| INFO_TOAST_DURATION.saturating_sub(n.posted.elapsed()))
+            .min();
+        if let Some(delay) = next_expiry {
+            ctx.request_repaint_after(delay);
+        }
+    }
+
+    /// Render the log of all messages, newest first
+    pub fn show_log(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(format!("{} messages", self.entries.len()));
+            if ui.button("Dismiss All").clicked() {
+                self.entries.iter_mut().for_each(|n| n.dismissed = true);
+            }
+            if ui.button("Clear").clicked() {
+                self.entries.clear();
+            }
+        });
+        ui.separator();
+
+        if self.entries.is_empty() {
+            ui.label(RichText::new("No messages").weak());
+            return;
+        }
+
+        ScrollArea::vertical().auto_shrink([false, true]).show(ui, |ui| {
+            egui::Grid::new("notification_log").striped(true).show(ui, |ui| {
+                for notification in self.entries.iter().rev() {
+                    let color = notification.severity.color(ui.visuals());
+                    ui.monospace(&notification.timestamp).on_hover_text("UTC");
+                    ui.label(RichText::new(notification.severity.name()).color(color));
+                    ui.label(&notification.message);
+                    ui.end_row();
+                }
+            });
+        });
+    }
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,174 @@
+use super::ViewSettings;
+use crate::schema::Field;
+use egui::collapsing_header::CollapsingState;
+use egui::{RichText, ScrollArea};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// A node in the outline tree
+struct OutlineNode {
+    /// Index of the field in the schema
+    field_idx: usize,
+    /// Fields whose byte range lies inside this field's range
+    children: Vec<OutlineNode>,
+}
+
+/// Outline panel listing fields hierarchically by offset, like a table of contents
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutlineView {
+    /// Paths (e.g. `header.flags`) of the nodes the user collapsed, persisted between runs
+    collapsed: HashSet<String>,
+}
+
+impl OutlineView {
+    /// Key used in eframe storage
+    pub const STORAGE_KEY: &'static str = "outline_view";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restore the collapsed nodes from eframe storage, if any were saved
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        storage
+            .and_then(|s| eframe::get_value(s, Self::STORAGE_KEY))
+            .unwrap_or_default()
+    }
+
+    /// Write the collapsed nodes to eframe storage
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, Self::STORAGE_KEY, self);
+    }
+
+    /// Build the outline tree: fields sorted by offset, nested when one field's range contains another's
+    fn build_tree(fields: &[Field]) -> Vec<OutlineNode> {
+        let mut order: Vec<usize> = (0..fields.len()).collect();
+        // Wider fields first at the same offset so they become the parent
+        order.sort_by_key(|&idx| (fields[idx].offset, std::cmp::Reverse(fields[idx].size())));
+
+        // Stack of (node, end offset) for the currently open ancestors
+        let mut roots = Vec::new();
+        let mut stack: Vec<(OutlineNode, usize)> = Vec::new();
+
+        for idx in order {
+            let field = &fields[idx];
+            let end = field.offset + field.size();
+
+            // Close ancestors that don't contain this field
+            while let Some((_, parent_end)) = stack.last() {
+                if end <= *parent_end {
+                    break;
+                }
+                let (node, _) = stack.pop().unwrap();
+                Self::attach(&mut stack, &mut roots, node);
+            }
+
+            stack.push((OutlineNode { field_idx: idx, children: Vec::new() }, end));
+        }
+
+        while let Some((node, _)) = stack.pop() {
+            Self::attach(&mut stack, &mut roots, node);
+        }
+
+        roots
+    }
+
+    /// Attach a finished node to its parent on the stack, or to the roots
+    fn attach(stack: &mut [(OutlineNode, usize)], roots: &mut Vec<OutlineNode>, node: OutlineNode) {
+        match stack.last_mut() {
+            Some((parent, _)) => parent.children.push(node),
+            None => roots.push(node),
+        }
+    }
+
+    /// Render the outline for the given fields
+    /// Returns the index of the field that was clicked, if any
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        fields: &[Field],
+        data_len: usize,
+        selected_fields: &HashSet<usize>,
+        settings: &ViewSettings,
+    ) -> Option<usize> {
+        if fields.is_empty() {
+            ui.label("No fields defined.");
+            return None;
+        }
+
+        let mut clicked = None;
+        let tree = Self::build_tree(fields);
+
+        ScrollArea::vertical()
+            .id_salt("outline_scroll")
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                for node in &tree {
+                    self.show_node(ui, node, "", fields, data_len, selected_fields, settings, &mut clicked);
+                }
+            });
+
+        clicked
+    }
+
+    /// Render a single node and its children
+    /// `parent_path` is the dotted path of the enclosing nodes, used as a stable key for the open state
+    #[allow(clippy::too_many_arguments)]
+    fn show_node(
+        &mut self,
+        ui: &mut egui::Ui,
+        node: &OutlineNode,
+        parent_path: &str,
+        fields: &[Field],
+        data_len: usize,
+        selected_fields: &HashSet<usize>,
+        settings: &ViewSettings,
+        clicked: &mut Option<usize>,
+    ) {
+        let field = &fields[node.field_idx];
+        let is_selected = selected_fields.contains(&node.field_idx);
+        let mut text = RichText::new(format!(
+            "{}  {}",
+            settings.format_offset_prefixed(field.offset, data_len),
+            field.display_name()
+        ));
+        if is_selected {
+            text = text.strong();
+        }
+
+        if node.children.is_empty() {
+            if ui.selectable_label(is_selected, text).clicked() {
+                *clicked = Some(node.field_idx);
+            }
+            return;
+        }
+
+        let path = if parent_path.is_empty() {
+            field.identifier().into_owned()
+        } else {
+            format!("{}.{}", parent_path, field.identifier())
+        };
+        let id = ui.make_persistent_id(("outline_node", &path));
+        let default_open = !self.collapsed.contains(&path);
+        CollapsingState::load_with_default_open(ui.ctx(), id, default_open)
+            .show_header(ui, |ui| {
+                if ui.selectable_label(is_selected, text).clicked() {
+                    *clicked = Some(node.field_idx);
+                }
+            })
+            .body(|ui| {
+                for child in &node.children {
+                    self.show_node(ui, child, &path, fields, data_len, selected_fields, settings, clicked);
+                }
+            });
+
+        // Remember the state under the path so it survives schema reloads and restarts
+        let is_open = CollapsingState::load(ui.ctx(), id).is_none_or(|state| state.is_open());
+        if is_open {
+            self.collapsed.remove(&path);
+        } else {
+            self.collapsed.insert(path);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+
+/// Colors of the built-in palette, cycled through by field index
+const DEFAULT_COLORS: [[u8; 3]; 8] = [
+    [100, 150, 255], // Blue
+    [255, 150, 100], // Orange
+    [150, 255, 100], // Green
+    [255, 100, 200], // Pink
+    [200, 100, 255], // Purple
+    [100, 255, 200], // Cyan
+    [255, 255, 100], // Yellow
+    [255, 150, 150], // Light red
+];
+
+/// Okabe-Ito colors, distinguishable with the common forms of color blindness
+const COLORBLIND_COLORS: [[u8; 3]; 7] = [
+    [230, 159, 0],
+    [86, 180, 233],
+    [0, 158, 115],
+    [240, 228, 66],
+    [0, 114, 178],
+    [213, 94, 0],
+    [204, 121, 167],
+];
+
+/// Named color palettes used to tell fields apart, and which one is active
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Palettes {
+    /// Palette names and their colors
+    pub palettes: Vec<(String, Vec<[u8; 3]>)>,
+    /// Index of the palette in use
+    pub active: usize,
+}
+
+impl Default for Palettes {
+    fn default() -> Self {
+        Self {
+            palettes: vec![
+                ("Default".to_string(), DEFAULT_COLORS.to_vec()),
+                ("Colorblind".to_string(), COLORBLIND_COLORS.to_vec()),
+            ],
+            active: 0,
+        }
+    }
+}
+
+impl Palettes {
+    /// Key used in eframe storage
+    pub const STORAGE_KEY: &'static str = "palettes";
+
+    /// Restore the palettes from eframe storage, if any were saved
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        storage
+            .and_then(|s| eframe::get_value(s, Self::STORAGE_KEY))
+            .unwrap_or_default()
+    }
+
+    /// Write the palettes to eframe storage
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, Self::STORAGE_KEY, self);
+    }
+
+    /// Get the colors of the active palette, falling back to the built-in one if it's empty
+    pub fn active_colors(&self) -> &[[u8; 3]] {
+        match self.palettes.get(self.active) {
+            Some((_, colors)) if !colors.is_empty() => colors,
+            _ => &DEFAULT_COLORS,
+        }
+    }
+
+    /// Render the palette editor
+    pub fn show_editor(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Palette:");
+            let active_name = self.palettes.get(self.active).map(|(name, _)| name.clone()).unwrap_or_default();
+            egui::ComboBox::from_id_salt("active_palette")
+                .selected_text(active_name)
+                .show_ui(ui, |ui| {
+                    for (idx, (name, _)) in self.palettes.iter().enumerate() {
+                        ui.selectable_value(&mut self.active, idx, name);
+                    }
+                });
+
+            if ui.button("Duplicate").on_hover_text("Start a new palette from this one").clicked() {
+                let (name, colors) = self
+                    .palettes
+                    .get(self.active)
+                    .cloned()
+                    .unwrap_or_else(|| ("Palette".to_string(), DEFAULT_COLORS.to_vec()));
+                self.palettes.push((format!("{} copy", name), colors));
+                self.active = self.palettes.len() - 1;
+            }
+            if ui.add_enabled(self.palettes.len() > 1, egui::Button::new("Delete")).clicked() {
+                self.palettes.remove(self.active);
+                self.active = self.active.min(self.palettes.len() - 1);
+            }
+        });
+
+        let Some((name, colors)) = self.palettes.get_mut(self.active) else {
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.label("Name:");
+            ui.text_edit_singleline(name);
+        });
+
+        ui.separator();
+
+        let mut removed = None;
+        ui.horizontal_wrapped(|ui| {
+            for (idx, color) in colors.iter_mut().enumerate() {
+                ui.color_edit_button_srgb(color).context_menu(|ui| {
+                    if ui.button("Remove Color").clicked() {
+                        removed = Some(idx);
+                        ui.close_menu();
+                    }
+                });
+            }
+            if ui.button("+").on_hover_text("Add a color").clicked() {
+                colors.push(colors.last().copied().unwrap_or(DEFAULT_COLORS[0]));
+            }
+        });
+        if let Some(idx) = removed {
+            colors.remove(idx);
+        }
+
+        ui.label(
+            egui::RichText::new("Fields cycle through the colors in order. Right-click a color to remove it.")
+                .small()
+                .weak(),
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use crate::binary_data::BinaryData;
+use egui::{Color32, RichText};
+use std::ops::Range;
+
+/// Maximum number of search hits collected, to keep huge files responsive
+const MAX_SEARCH_MATCHES: usize = 10_000;
+
+/// How the search text is turned into bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchMode {
+    /// Hex byte sequence such as `DE AD BE EF`
+    Hex,
+    /// ASCII text
+    Text,
+}
+
+/// Action requested by the search bar, to be applied by the app
+#[derive(Debug, Clone)]
+pub enum SearchAction {
+    /// Highlight `len` bytes at each of the sorted offsets
+    Highlight(Vec<usize>, usize),
+    /// Bring this match into view and select it
+    Jump(Range<usize>),
+}
+
+/// Search box above the hex view with Next/Previous navigation through the hits
+pub struct SearchBar {
+    query: String,
+    mode: SearchMode,
+    /// Start offsets of the hits, in ascending order
+    matches: Vec<usize>,
+    needle_len: usize,
+    /// Index into `matches` of the hit last jumped to
+    current: Option<usize>,
+    /// Only hits lying entirely within this range are kept ("In selection")
+    scope: Option<Range<usize>>,
+    /// Query, mode, scope and data generation the hits were computed for
+    key: Option<(String, SearchMode, Option<Range<usize>>, u64)>,
+    error: Option<String>,
+    /// Give the search box keyboard focus on the next frame
+    request_focus: bool,
+}
+
+impl Default for SearchBar {
+    fn default() -> Self {
+        Self {
+            query: String::new(),
+            mode: SearchMode::Hex,
+            matches: Vec::new(),
+            needle_len: 0,
+            current: None,
+            scope: None,
+            key: None,
+            error: None,
+            request_focus: false,
+        }
+    }
+}
+
+impl SearchBar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Focus the search box on the next frame (e.g. after Ctrl+F)
+    pub fn focus(&mut self) {
+        self.request_focus = true;
+    }
+
+    /// Turn the query into the bytes to search for
+    fn needle(&self) -> Result<Vec<u8>, String> {
+        match self.mode {
+            SearchMode::Text => Ok(self.query.as_bytes().to_vec()),
+            SearchMode::Hex => {
+                let digits: String = self.query.split_whitespace().collect();
+                let digits = digits.strip_prefix("0x").unwrap_or(&digits);
+                if !digits.is_ascii() {
+                    return Err("only hex digits are allowed".to_string());
+                }
+                if !digits.len().is_multiple_of(2) {
+                    return Err("odd number of hex digits".to_string());
+                }
+                (0..digits.len())
+                    .step_by(2)
+                    .map(|i| {
+                        u8::from_str_radix(&digits[i..i + 2], 16)
+                            .map_err(|_| format!("invalid hex byte `{}`", &digits[i..i + 2]))
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Recompute the hits if the query, mode or data changed
+    fn update_matches(&mut self, data: &BinaryData) -> bool {
+        let key = Some((self.query.clone(), self.mode, self.scope.clone(), data.generation()));
+        if key == self.key {
+            return false;
+        }
+        self.key = key;
+        self.current = None;
+
+        match self.needle() {
+            Ok(needle) => {
+                self.error = None;
+                self.matches = data.find_all(&needle, MAX_SEARCH_MATCHES);
+                if let Some(scope) = &self.scope {
+                    self.matches.retain(|&m| m >= scope.start && m + needle.len() <= scope.end);
+                }
+                self.needle_len = needle.len();
+            }
+            Err(e) => {
+                self.error = Some(e);
+                self.matches.clear();
+                self.needle_len = 0;
+            }
+        }
+        true
+    }
+
+    /// Step to the next (or previous) hit, wrapping around
+    fn step(&mut self, forward: bool) -> Option<SearchAction> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        let last = self.matches.len() - 1;
+        let next = match (self.current, forward) {
+            (None, true) => 0,
+            (None, false) => last,
+            (Some(i), true) => if i == last { 0 } else { i + 1 },
+            (Some(i), false) => if i == 0 { last } else { i - 1 },
+        };
+        self.current = Some(next);
+        let start = self.matches[next];
+        Some(SearchAction::Jump(start..start + self.needle_len))
+    }
+
+    /// Render the search bar
+    /// `selection` is the hex selection, which the search can be limited to
+    pub fn show(&mut self, ui: &mut egui::Ui, data: &BinaryData, selection: Option<Range<usize>>) -> Option<SearchAction> {
+        let mut action = None;
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+            ui.label("Find:");
+            ui.selectable_value(&mut self.mode, SearchMode::Hex, "Hex");
+            ui.selectable_value(&mut self.mode, SearchMode::Text, "Text");
+
+            let hint = match self.mode {
+                SearchMode::Hex => "DE AD BE EF",
+                SearchMode::Text => "text",
+            };
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.query)
+                    .hint_text(hint)
+                    .desired_width(160.0),
+            );
+            if self.request_focus {
+                response.request_focus();
+                self.request_focus = false;
+            }
+            // The scope is fixed when enabled, since jumping to a hit replaces the selection
+            let mut in_selection = self.scope.is_some();
+            let toggle = ui
+                .add_enabled(
+                    in_selection || selection.is_some(),
+                    egui::Checkbox::new(&mut in_selection, "In selection"),
+                )
+                .on_hover_text(match &self.scope {
+                    Some(scope) => format!("Searching 0x{:X}..0x{:X}", scope.start, scope.end),
+                    None => "Only search the selected bytes".to_string(),
+                });
+            if toggle.changed() {
+                self.scope = if in_selection { selection } else { None };
+            }
+            changed = self.update_matches(data);
+            // Enter steps to the next hit (Shift+Enter to the previous) and keeps the focus
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                let forward = !ui.input(|i| i.modifiers.shift);
+                action = self.step(forward);
+                response.request_focus();
+            }
+
+            if ui.add_enabled(!self.matches.is_empty(), egui::Button::new("◀")).on_hover_text("Previous").clicked() {
+                action = self.step(false);
+            }
+            if ui.add_enabled(!self.matches.is_empty(), egui::Button::new("▶")).on_hover_text("Next").clicked() {
+                action = self.step(true);
+            }
+
+            if let Some(error) = &self.error {
+                ui.label(RichText::new(error).color(Color32::from_rgb(200, 80, 80)));
+            } else if !self.query.is_empty() {
+                let capped = if self.matches.len() >= MAX_SEARCH_MATCHES { "+" } else { "" };
+                let text = match self.current {
+                    Some(i) => format!("{} of {}{}", i + 1, self.matches.len(), capped),
+                    None if self.matches.is_empty() => "no matches".to_string(),
+                    None => format!("{}{} matches", self.matches.len(), capped),
+                };
+                ui.label(text);
+            }
+        });
+
+        // New hits replace the highlights before any navigation
+        if changed {
+            return Some(SearchAction::Highlight(self.matches.clone(), self.needle_len));
+        }
+        action
+    }
+}
@@ -0,0 +1,155 @@
+use crate::binary_data::BinaryData;
+use egui::RichText;
+use egui_extras::{Column, TableBuilder};
+
+/// Maximum number of strings listed, to keep large files responsive
+const MAX_STRINGS_SHOWN: usize = 10_000;
+
+/// Action requested from the strings list
+#[derive(Debug, Clone)]
+pub enum StringsAction {
+    /// Scroll the hex view to this offset
+    GoTo(usize),
+    /// Define a fixed-length string field covering these bytes
+    AddField { offset: usize, len: usize },
+}
+
+/// A string found in the data
+struct FoundString {
+    offset: usize,
+    text: String,
+    utf16: bool,
+}
+
+/// Panel listing the readable text found in the binary, like the Unix `strings` tool
+pub struct StringsView {
+    /// Shortest run of printable characters listed
+    min_len: usize,
+    /// Whether UTF-16LE strings are searched too
+    include_utf16: bool,
+    /// Only strings containing this text (ignoring case) are listed
+    filter: String,
+    /// Cached strings, with the data generation, minimum length and UTF-16 setting they were found with
+    found: Option<((u64, usize, bool), Vec<FoundString>)>,
+}
+
+impl StringsView {
+    pub fn new() -> Self {
+        Self {
+            min_len: 4,
+            include_utf16: false,
+            filter: String::new(),
+            found: None,
+        }
+    }
+
+    /// Drop the cached strings (e.g. after a new file is loaded)
+    pub fn invalidate(&mut self) {
+        self.found = None;
+    }
+
+    /// Scan the data again if it or the settings changed since the last scan
+    fn update(&mut self, binary_data: &BinaryData) {
+        let key = (binary_data.generation(), self.min_len, self.include_utf16);
+        if self.found.as_ref().is_some_and(|(found_key, _)| *found_key == key) {
+            return;
+        }
+
+        let mut found: Vec<FoundString> = binary_data
+            .find_strings(self.min_len)
+            .into_iter()
+            .map(|(offset, text)| FoundString { offset, text, utf16: false })
+            .collect();
+        if self.include_utf16 {
+            found.extend(
+                binary_data
+                    .find_utf16_strings(self.min_len)
+                    .into_iter()
+                    .map(|(offset, text)| FoundString { offset, text, utf16: true }),
+            );
+            found.sort_by_key(|s| s.offset);
+        }
+        self.found = Some((key, found));
+    }
+
+    /// Render the settings and the list of strings
+    /// Returns the action requested by clicking an entry
+    pub fn show(&mut self, ui: &mut egui::Ui, binary_data: &BinaryData) -> Option<StringsAction> {
+        if binary_data.is_empty() {
+            ui.label("No file loaded");
+            return None;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Minimum length:");
+            ui.add(egui::DragValue::new(&mut self.min_len).range(2..=256));
+            ui.checkbox(&mut self.include_utf16, "UTF-16LE");
+        });
+        ui.horizontal(|ui| {
+            ui.label("Filter:");
+            ui.text_edit_singleline(&mut self.filter);
+        });
+
+        self.update(binary_data);
+        let Some((_, found)) = &self.found else {
+            return None;
+        };
+
+        let filter = self.filter.to_lowercase();
+        let listed: Vec<&FoundString> = found
+            .iter()
+            .filter(|s| filter.is_empty() || s.text.to_lowercase().contains(&filter))
+            .take(MAX_STRINGS_SHOWN)
+            .collect();
+        let mut summary = format!("{} string(s)", found.len());
+        if listed.len() == MAX_STRINGS_SHOWN {
+            summary.push_str(&format!(", first {} shown", MAX_STRINGS_SHOWN));
+        }
+        ui.label(RichText::new(summary).weak());
+        ui.separator();
+
+        let mut action = None;
+        let dim = ui.visuals().weak_text_color();
+        TableBuilder::new(ui)
+            .striped(true)
+            .column(Column::auto().at_least(80.0)) // Offset
+            .column(Column::auto()) // Encoding
+            .column(Column::remainder().at_least(150.0).clip(true)) // Text
+            .column(Column::auto()) // Add field
+            .body(|body| {
+                body.rows(18.0, listed.len(), |mut row| {
+                    let found = listed[row.index()];
+                    row.col(|ui| {
+                        let text = RichText::new(format!("0x{:X}", found.offset)).monospace().color(dim);
+                        if ui.selectable_label(false, text).on_hover_text("Go to offset").clicked() {
+                            action = Some(StringsAction::GoTo(found.offset));
+                        }
+                    });
+                    row.col(|ui| {
+                        ui.label(RichText::new(if found.utf16 { "UTF-16" } else { "ASCII" }).color(dim));
+                    });
+                    row.col(|ui| {
+                        ui.monospace(&found.text).on_hover_text(&found.text);
+                    });
+                    row.col(|ui| {
+                        // UTF-16 text has no string type, so it can only be viewed
+                        if !found.utf16
+                            && ui.small_button("+ Field").on_hover_text("Define a string field here").clicked()
+                        {
+                            action = Some(StringsAction::AddField {
+                                offset: found.offset,
+                                len: found.text.len(),
+                            });
+                        }
+                    });
+                });
+            });
+        action
+    }
+}
+
+impl Default for StringsView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,210 @@
+use crate::schema::{FloatFormat, NumberFormat};
+use serde::{Deserialize, Serialize};
+
+/// How byte offsets are displayed in the views
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OffsetFormat {
+    /// Hexadecimal, with just enough digits for the file size
+    #[default]
+    HexAuto,
+    Hex4,
+    Hex8,
+    Hex16,
+    Decimal,
+}
+
+impl OffsetFormat {
+    /// Get the display name of this format
+    pub fn name(&self) -> &'static str {
+        match self {
+            OffsetFormat::HexAuto => "Hex (auto width)",
+            OffsetFormat::Hex4 => "Hex (4 digits)",
+            OffsetFormat::Hex8 => "Hex (8 digits)",
+            OffsetFormat::Hex16 => "Hex (16 digits)",
+            OffsetFormat::Decimal => "Decimal",
+        }
+    }
+
+    /// Get all available offset formats
+    pub fn all() -> &'static [OffsetFormat] {
+        &[
+            OffsetFormat::HexAuto,
+            OffsetFormat::Hex4,
+            OffsetFormat::Hex8,
+            OffsetFormat::Hex16,
+            OffsetFormat::Decimal,
+        ]
+    }
+
+    /// Number of digits used to display offsets within a file of `data_len` bytes
+    fn width(&self, data_len: usize) -> usize {
+        let max_offset = data_len.saturating_sub(1);
+        match self {
+            OffsetFormat::HexAuto => {
+                let digits = format!("{:X}", max_offset).len();
+                // Round up to 4, 8 or 16 digits so the column doesn't jitter between files
+                match digits {
+                    0..=4 => 4,
+                    5..=8 => 8,
+                    _ => 16,
+                }
+            }
+            OffsetFormat::Hex4 => 4,
+            OffsetFormat::Hex8 => 8,
+            OffsetFormat::Hex16 => 16,
+            OffsetFormat::Decimal => max_offset.to_string().len(),
+        }
+    }
+
+    /// Format an offset (without prefix) padded for a file of `data_len` bytes
+    pub fn format(&self, offset: usize, data_len: usize) -> String {
+        let width = self.width(data_len);
+        match self {
+            OffsetFormat::Decimal => format!("{:0width$}", offset),
+            _ => format!("{:0width$X}", offset),
+        }
+    }
+
+    /// Format an offset with a `0x` prefix for hex formats
+    pub fn format_prefixed(&self, offset: usize, data_len: usize) -> String {
+        match self {
+            OffsetFormat::Decimal => self.format(offset, data_len),
+            _ => format!("0x{}", self.format(offset, data_len)),
+        }
+    }
+}
+
+/// Row widths offered for the hex view
+pub const BYTES_PER_ROW_CHOICES: [usize; 4] = [8, 16, 24, 32];
+
+/// Display options shared by the hex and data views
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewSettings {
+    /// How offsets are formatted in the offset columns
+    pub offset_format: OffsetFormat,
+    /// Show hex digits in lowercase (`de ad`) instead of uppercase (`DE AD`)
+    pub lowercase_hex: bool,
+    /// Number of bytes shown on each hex view row
+    pub bytes_per_row: usize,
+    /// Radix used for integer values in the data view
+    pub number_format: NumberFormat,
+    /// Notation and precision of float values in the data view
+    pub float_format: FloatFormat,
+    /// Separate thousands in decimal integer values (`4,294,967,295`)
+    pub group_thousands: bool,
+    /// Highlight other occurrences of the selected field's bytes in the hex view
+    pub highlight_field_matches: bool,
+    /// Show the panel decoding the bytes at the hovered or cursor byte as every type
+    pub show_inspector: bool,
+    /// Float values larger than this in magnitude are flagged as suspicious
+    pub float_warning_magnitude: f64,
+}
+
+impl Default for ViewSettings {
+    fn default() -> Self {
+        Self {
+            offset_format: OffsetFormat::default(),
+            lowercase_hex: false,
+            bytes_per_row: 16,
+            number_format: NumberFormat::default(),
+            float_format: FloatFormat::default(),
+            group_thousands: false,
+            highlight_field_matches: false,
+            show_inspector: true,
+            float_warning_magnitude: 1e12,
+        }
+    }
+}
+
+impl ViewSettings {
+    /// Key used in eframe storage
+    pub const STORAGE_KEY: &'static str = "view_settings";
+
+    /// Restore the settings from eframe storage, if any were saved
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        storage
+            .and_then(|s| eframe::get_value(s, Self::STORAGE_KEY))
+            .unwrap_or_default()
+    }
+
+    /// Write the settings to eframe storage
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, Self::STORAGE_KEY, self);
+    }
+
+    /// Apply the hex digit case to text made of hex numbers (e.g. `0xDEAD`)
+    pub fn hex_case(&self, text: String) -> String {
+        if self.lowercase_hex { text.to_ascii_lowercase() } else { text }
+    }
+
+    /// Format a byte as two hex digits
+    pub fn format_byte(&self, byte: u8) -> String {
+        if self.lowercase_hex { format!("{:02x}", byte) } else { format!("{:02X}", byte) }
+    }
+
+    /// Format an offset (without prefix) in the configured format and case
+    pub fn format_offset(&self, offset: usize, data_len: usize) -> String {
+        self.hex_case(self.offset_format.format(offset, data_len))
+    }
+
+    /// Format an offset with a `0x` prefix for hex formats, in the configured case
+    pub fn format_offset_prefixed(&self, offset: usize, data_len: usize) -> String {
+        self.hex_case(self.offset_format.format_prefixed(offset, data_len))
+    }
+
+    /// Format an offset for an offset column like `format_offset`
+    /// With a relative base, the distance from it is shown instead, signed (`+0010`, `-0004`)
+    pub fn format_row_offset(&self, offset: usize, relative_base: Option<usize>, data_len: usize) -> String {
+        match relative_base {
+            Some(base) if offset < base => format!("-{}", self.format_offset(base - offset, data_len)),
+            Some(base) => format!("+{}", self.format_offset(offset - base, data_len)),
+            None => self.format_offset(offset, data_len),
+        }
+    }
+
+    /// Format an offset for an offset column like `format_offset_prefixed`, relative to the base if there is one
+    pub fn format_row_offset_prefixed(&self, offset: usize, relative_base: Option<usize>, data_len: usize) -> String {
+        match relative_base {
+            Some(base) if offset < base => format!("-{}", self.format_offset_prefixed(base - offset, data_len)),
+            Some(base) => format!("+{}", self.format_offset_prefixed(offset - base, data_len)),
+            None => self.format_offset_prefixed(offset, data_len),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn row_offsets_are_absolute_without_a_base() {
+        let settings = ViewSettings::default();
+        assert_eq!(settings.format_row_offset(0x1A, None, 0x100), "001A");
+        assert_eq!(settings.format_row_offset_prefixed(0x1A, None, 0x100), "0x001A");
+    }
+
+    #[test]
+    fn row_offsets_are_signed_distances_from_the_base() {
+        let settings = ViewSettings::default();
+        assert_eq!(settings.format_row_offset(0x30, Some(0x20), 0x100), "+0010");
+        assert_eq!(settings.format_row_offset(0x1C, Some(0x20), 0x100), "-0004");
+        assert_eq!(settings.format_row_offset(0x20, Some(0x20), 0x100), "+0000");
+        assert_eq!(settings.format_row_offset_prefixed(0x1C, Some(0x20), 0x100), "-0x0004");
+    }
+
+    #[test]
+    fn relative_row_offsets_follow_the_format_and_case() {
+        let settings = ViewSettings {
+            offset_format: OffsetFormat::Decimal,
+            ..ViewSettings::default()
+        };
+        assert_eq!(settings.format_row_offset_prefixed(90, Some(100), 1000), "-010");
+
+        let settings = ViewSettings {
+            lowercase_hex: true,
+            ..ViewSettings::default()
+        };
+        assert_eq!(settings.format_row_offset_prefixed(0x2A, Some(0x10), 0x100), "+0x001a");
+    }
+}
@@ -0,0 +1,97 @@
+use crate::schema::Field;
+use crate::watch::ValueHistory;
+use egui::{Color32, RichText, ScrollArea};
+use std::collections::HashMap;
+
+/// Number of recent values listed next to the sparkline
+const RECENT_VALUES_SHOWN: usize = 6;
+
+/// Panel showing the recent value history of watched fields
+pub struct WatchView;
+
+impl WatchView {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Draw a small line chart of numeric values
+    fn sparkline(ui: &mut egui::Ui, values: &[f64]) {
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(120.0, 16.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+
+        if values.len() < 2 {
+            return;
+        }
+
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let range = if max > min { max - min } else { 1.0 };
+
+        let points: Vec<egui::Pos2> = values
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| {
+                let x = rect.left() + rect.width() * i as f32 / (values.len() - 1) as f32;
+                let y = rect.bottom() - rect.height() * ((v - min) / range) as f32;
+                egui::pos2(x, y)
+            })
+            .collect();
+
+        painter.add(egui::Shape::line(
+            points,
+            egui::Stroke::new(1.5, Color32::from_rgb(100, 150, 255)),
+        ));
+    }
+
+    /// Render the watched fields
+    /// Returns the index of a field to stop watching, if any
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        fields: &[Field],
+        watches: &HashMap<usize, ValueHistory>,
+    ) -> Option<usize> {
+        let mut unwatch = None;
+
+        let mut watched: Vec<usize> = watches.keys().copied().collect();
+        watched.sort_unstable();
+
+        ui.strong("Watches");
+        ScrollArea::vertical()
+            .id_salt("watch_scroll")
+            .max_height(120.0)
+            .show(ui, |ui| {
+                for idx in watched {
+                    let (Some(field), Some(history)) = (fields.get(idx), watches.get(&idx)) else {
+                        continue;
+                    };
+
+                    ui.horizontal(|ui| {
+                        if ui.small_button("✖").on_hover_text("Stop watching").clicked() {
+                            unwatch = Some(idx);
+                        }
+                        ui.label(RichText::new(field.display_name()).strong());
+                        Self::sparkline(ui, &history.numeric_values());
+
+                        // Most recent values, newest first
+                        let recent: Vec<String> = history
+                            .values()
+                            .rev()
+                            .take(RECENT_VALUES_SHOWN)
+                            .map(|value| value.clone().unwrap_or_else(|| String::from("(out of bounds)")))
+                            .collect();
+                        ui.label(RichText::new(recent.join(" ← ")).monospace());
+                    });
+                }
+            });
+
+        unwatch
+    }
+}
+
+impl Default for WatchView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
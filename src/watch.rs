@@ -0,0 +1,40 @@
+use std::collections::VecDeque;
+
+/// Maximum number of values kept per watched field
+const HISTORY_CAPACITY: usize = 32;
+
+/// Bounded history of a watched field's decoded values
+#[derive(Debug, Clone, Default)]
+pub struct ValueHistory {
+    values: VecDeque<Option<String>>,
+}
+
+impl ValueHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a newly decoded value, skipping it if unchanged since the last record
+    pub fn record(&mut self, value: Option<String>) {
+        if self.values.back() == Some(&value) {
+            return;
+        }
+        if self.values.len() == HISTORY_CAPACITY {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+    }
+
+    /// Recorded values, oldest first (`None` when the field was out of bounds)
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &Option<String>> {
+        self.values.iter()
+    }
+
+    /// Recorded values that parse as numbers, oldest first
+    pub fn numeric_values(&self) -> Vec<f64> {
+        self.values
+            .iter()
+            .filter_map(|value| value.as_ref()?.parse::<f64>().ok())
+            .collect()
+    }
+}